@@ -274,6 +274,13 @@ impl SourceMap {
         }
     }
 
+    /// Reserves the address space below `pos`, so the next new file starts at `pos`
+    /// if the used address space is below it.
+    pub fn reserve_address_space(&self, pos: BytePos) {
+        self.used_address_space
+            .fetch_max(pos.to_u32(), Ordering::Relaxed);
+    }
+
     /// Creates a new `SourceFile`.
     /// If a file already exists in the `SourceMap` with the same ID, that file is returned
     /// unmodified.
@@ -38,7 +38,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use kclvm_span::Loc;
+use kclvm_span::{Loc, ProgramSourceMap};
 use rustc_span::Pos;
 
 use super::token;
@@ -196,6 +196,9 @@ pub struct Program {
     pub pkgs: HashMap<String, Vec<Module>>,
     pub cmd_args: Vec<CmdArgSpec>,
     pub cmd_overrides: Vec<OverrideSpec>,
+    /// The source map of all the files of the program.
+    #[serde(skip)]
+    pub source_map: ProgramSourceMap,
 }
 
 impl Program {
@@ -5,6 +5,7 @@ use crate::service::api::*;
 use crate::service::util::*;
 use std::ffi::{CStr, CString};
 use std::fs;
+use std::io::{Read, Write};
 use std::path::Path;
 const TEST_DATA_PATH: &str = "./src/testdata";
 
@@ -77,3 +78,121 @@ fn test_c_api_call_override_file() {
     kclvm_service_delete(serv);
     kclvm_service_free_string(result_ptr);
 }
+
+fn http_post(addr: &std::net::SocketAddr, path: &str, body: &str) -> (String, serde_json::Value) {
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+        path,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status_line = head.lines().next().unwrap().to_string();
+    (status_line, serde_json::from_str(body).unwrap())
+}
+
+#[test]
+fn test_server() {
+    use crate::service::server::{Server, MAX_BODY_SIZE};
+    use crate::service::service::KclvmService;
+
+    let server = Server::bind("127.0.0.1:0", KclvmService::default(), 2).unwrap();
+    let addr = server.local_addr().unwrap();
+    let handle = server.shutdown_handle();
+    let serve = std::thread::spawn(move || server.serve());
+
+    let (status, resp) = http_post(&addr, "/api:protorpc/Ping", r#"{"value": "hello"}"#);
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert_eq!(resp["error"], "");
+    assert_eq!(resp["result"]["value"], "hello");
+    assert_eq!(
+        resp["result"]["@type"],
+        "type.googleapis.com/gpyrpc.Ping_Result"
+    );
+    let (status, resp) = http_post(&addr, "/api:protorpc/KclvmService.Ping", "");
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert_eq!(resp["result"]["value"], "");
+    let (status, resp) = http_post(&addr, "/api:protorpc/Unknown", "{}");
+    assert_eq!(status, "HTTP/1.1 404 Not Found");
+    assert_eq!(resp["error"], "unknown method 'Unknown'");
+    let (_, resp) = http_post(&addr, "/api:protorpc/Ping", "{");
+    assert!(resp["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("invalid arguments"));
+    assert_eq!(resp["kcl_err"], serde_json::Value::Null);
+    let (status, resp) = http_post(
+        &addr,
+        "/api:protorpc/ExecProgram",
+        r#"{"k_filename_list": ["./src/testdata/not_found.k"]}"#,
+    );
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert_ne!(resp["error"], "");
+    assert_eq!(resp["kcl_err"]["msg"], resp["error"]);
+
+    let (status, resp) = http_post(
+        &addr,
+        "/api:protorpc/ExecProgram",
+        &fs::read_to_string("./src/testdata/exec-program.json").unwrap(),
+    );
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert_eq!(resp["error"], "");
+    assert_eq!(resp["result"]["yaml_result"], "a: 1\n");
+    assert_eq!(
+        resp["result"]["@type"],
+        "type.googleapis.com/gpyrpc.ExecProgram_Result"
+    );
+
+    // The body is not read when it is too large.
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "POST /api:protorpc/Ping HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+        MAX_BODY_SIZE + 1
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 413 Payload Too Large"));
+
+    handle.shutdown();
+    serve.join().unwrap().unwrap();
+    assert!(std::net::TcpStream::connect(addr).is_err());
+}
+
+#[test]
+fn test_server_parallel_exec_program() {
+    use crate::service::server::Server;
+    use crate::service::service::KclvmService;
+
+    let server = Server::bind("127.0.0.1:0", KclvmService::default(), 4).unwrap();
+    let addr = server.local_addr().unwrap();
+    let handle = server.shutdown_handle();
+    let serve = std::thread::spawn(move || server.serve());
+
+    let clients: Vec<_> = (0..8)
+        .map(|i| {
+            std::thread::spawn(move || {
+                let args = format!(
+                    r#"{{"work_dir": "./src/testdata", "k_filename_list": ["option.k"], "args": [{{"name": "a", "value": "{}"}}]}}"#,
+                    i
+                );
+                let (status, resp) = http_post(&addr, "/api:protorpc/ExecProgram", &args);
+                assert_eq!(status, "HTTP/1.1 200 OK");
+                assert_eq!(resp["error"], "");
+                assert_eq!(resp["result"]["yaml_result"], format!("a: {}\n", i));
+            })
+        })
+        .collect();
+    for client in clients {
+        client.join().unwrap();
+    }
+
+    handle.shutdown();
+    serve.join().unwrap().unwrap();
+}
@@ -135,7 +135,8 @@ pub fn exec_program(serv: &mut KclvmService, args: &[u8]) -> *const c_char {
             Ok(bytes) => bytes,
             Err(err) => panic!("{}", err.to_string()),
         },
-        Err(err) => panic!("{}", err.clone()),
+        // The callers parse the panic info JSON string in the error message.
+        Err(err) => panic!("{}", err.to_panic_info().to_json_string()),
     };
     CString::new(result_byte).unwrap().into_raw()
 }
@@ -0,0 +1,366 @@
+//! The HTTP/JSON server of [KclvmService], used by `kclvm_cli server`.
+//!
+//! Every service method is served as `POST /api:protorpc/<Method>` (or
+//! `/api:protorpc/KclvmService.<Method>`), the request body is the JSON of the
+//! method arguments and the response body is the JSON of a `RestResponse`, both
+//! follow the protobuf JSON mapping. e.g.
+//!
+//! ```text
+//! $ curl -X POST http://127.0.0.1:2021/api:protorpc/Ping -d '{"value": "hello"}'
+//! {"result":{"@type":"type.googleapis.com/gpyrpc.Ping_Result","value":"hello"},"error":"","kcl_err":null}
+//! ```
+//!
+//! The requests are handled concurrently by a thread pool, and the server stops
+//! accepting connections and waits for the handling requests after a shutdown.
+//! The KCL runtime keeps the running context and the panic hook in the process
+//! globals, so the `ExecProgram` calls are run one at a time.
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use kclvm_error::{DiagnosticId, KclvmError, Level};
+use protobuf::MessageFull;
+use protobuf_json_mapping::{print_to_string_with_options, PrintOptions};
+use threadpool::ThreadPool;
+
+use crate::model::gpyrpc::*;
+use crate::service::service::KclvmService;
+use crate::service::util::parse_message_from_json;
+
+/// The URL path prefix of the service methods.
+pub const API_PATH_PREFIX: &str = "/api:protorpc/";
+/// The service name prefix allowed in the method names.
+const SERVICE_NAME_PREFIX: &str = "KclvmService.";
+/// The default thread count to handle the requests.
+pub const DEFAULT_SERVER_THREADS: usize = 8;
+/// The interval to check the shutdown flag when there is no connection.
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// The max time to wait for a request from a connection.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// The max request header size.
+const MAX_HEADER_SIZE: usize = 64 * 1024;
+/// The max request body size.
+pub const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+/// The lock to run the KCL programs one at a time.
+static EXEC_LOCK: Mutex<()> = Mutex::new(());
+
+/// ShutdownHandle stops the server from another thread or a signal handler.
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownHandle {
+    shutdown: Arc<AtomicBool>,
+}
+
+impl ShutdownHandle {
+    pub fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.shutdown.load(Ordering::SeqCst)
+    }
+}
+
+/// Server serves the [KclvmService] methods over HTTP.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kclvm_capi::service::server::Server;
+/// use kclvm_capi::service::service::KclvmService;
+///
+/// let server = Server::bind("127.0.0.1:2021", KclvmService::default(), 8).unwrap();
+/// let handle = server.shutdown_handle();
+/// std::thread::spawn(move || {
+///     std::thread::sleep(std::time::Duration::from_secs(60));
+///     handle.shutdown();
+/// });
+/// server.serve().unwrap();
+/// ```
+pub struct Server {
+    listener: TcpListener,
+    serv: Arc<KclvmService>,
+    threads: usize,
+    shutdown: ShutdownHandle,
+}
+
+impl Server {
+    pub fn bind(addr: &str, serv: KclvmService, threads: usize) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        // Accept without blocking to check the shutdown flag.
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            serv: Arc::new(serv),
+            threads: threads.max(1),
+            shutdown: ShutdownHandle::default(),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    /// Serve the requests until shutdown, and returns after all the accepted
+    /// requests are handled.
+    pub fn serve(self) -> io::Result<()> {
+        let pool = ThreadPool::new(self.threads);
+        while !self.shutdown.is_shutdown() {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    let serv = Arc::clone(&self.serv);
+                    pool.execute(move || {
+                        // The connection errors only affect the connection itself.
+                        let _ = handle_connection(&serv, stream);
+                    });
+                }
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        drop(self.listener);
+        pool.join();
+        Ok(())
+    }
+}
+
+/// An HTTP response with a JSON body.
+struct Response {
+    status: u16,
+    body: String,
+}
+
+fn handle_connection(serv: &KclvmService, mut stream: TcpStream) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let response = match read_request(&mut stream) {
+        Ok((method, path, body)) => handle_request(serv, &method, &path, &body),
+        Err(response) => response,
+    };
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+/// Read the request method, path and body, returns the error response when the
+/// request is invalid.
+fn read_request(stream: &mut TcpStream) -> Result<(String, String, String), Response> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).map_err(bad_request)?;
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return Err(error_response(400, "invalid request line")),
+    };
+    let mut content_length = 0;
+    let mut header_size = request_line.len();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).map_err(bad_request)? == 0 {
+            break;
+        }
+        header_size += line.len();
+        if header_size > MAX_HEADER_SIZE {
+            return Err(error_response(400, "request header too large"));
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| error_response(400, "invalid Content-Length"))?;
+            }
+        }
+    }
+    if content_length > MAX_BODY_SIZE {
+        return Err(error_response(
+            413,
+            &format!("request body larger than {} bytes", MAX_BODY_SIZE),
+        ));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(bad_request)?;
+    let body = String::from_utf8(body).map_err(|_| error_response(400, "the body is not UTF-8"))?;
+    Ok((method, path, body))
+}
+
+fn error_response(status: u16, msg: &str) -> Response {
+    Response {
+        status,
+        body: rest_response(Err(KclvmError::other(msg))),
+    }
+}
+
+fn bad_request(err: io::Error) -> Response {
+    error_response(400, &err.to_string())
+}
+
+fn handle_request(serv: &KclvmService, method: &str, path: &str, body: &str) -> Response {
+    let name = match path.strip_prefix(API_PATH_PREFIX) {
+        Some(name) => name.trim_start_matches(SERVICE_NAME_PREFIX),
+        None => return error_response(404, &format!("unknown path '{}'", path)),
+    };
+    if method != "POST" {
+        return error_response(405, &format!("method '{}' is not allowed", method));
+    }
+    // The service panics on the compile errors with the panic info.
+    let result = std::panic::catch_unwind(|| call_json(serv, name, body))
+        .unwrap_or_else(|err| Err(KclvmError::from_panic(err)));
+    let status = match &result {
+        Err(_) if !is_service_method(name) => 404,
+        _ => 200,
+    };
+    Response {
+        status,
+        body: rest_response(result),
+    }
+}
+
+fn is_service_method(name: &str) -> bool {
+    matches!(name, "Ping" | "ExecProgram" | "OverrideFile")
+}
+
+/// Call the service method with the JSON arguments, returns the JSON of the `Any`
+/// result with its `@type`.
+pub fn call_json(serv: &KclvmService, name: &str, args: &str) -> Result<String, KclvmError> {
+    match name {
+        "Ping" => to_any_json(&serv.ping(&parse_args::<Ping_Args>(args)?)),
+        "ExecProgram" => {
+            let args = parse_args::<ExecProgram_Args>(args)?;
+            // The lock is poisoned by the panics of the previous calls caught by the callers.
+            let _guard = EXEC_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+            to_any_json(&serv.exec_program(&args)?)
+        }
+        "OverrideFile" => to_any_json(
+            &serv
+                .override_file(&parse_args::<OverrideFile_Args>(args)?)
+                .map_err(|err| KclvmError::other(&err))?,
+        ),
+        _ => Err(KclvmError::other(&format!("unknown method '{}'", name))),
+    }
+}
+
+fn parse_args<M: MessageFull>(args: &str) -> Result<M, KclvmError> {
+    // An empty body means the default arguments.
+    let args = if args.trim().is_empty() { "{}" } else { args };
+    parse_message_from_json::<M>(args)
+        .map_err(|err| KclvmError::other(&format!("invalid arguments: {}", err)))
+}
+
+fn print_options() -> PrintOptions {
+    PrintOptions {
+        enum_values_int: true,
+        proto_field_name: true,
+        always_output_default_values: true,
+        _future_options: (),
+    }
+}
+
+fn to_any_json<M: MessageFull>(message: &M) -> Result<String, KclvmError> {
+    let json = print_to_string_with_options(message, &print_options())
+        .map_err(|err| KclvmError::other(&err.to_string()))?;
+    let mut value: serde_json::Value =
+        serde_json::from_str(&json).map_err(|err| KclvmError::other(&err.to_string()))?;
+    if let Some(object) = value.as_object_mut() {
+        object.insert(
+            "@type".to_string(),
+            serde_json::Value::String(format!(
+                "type.googleapis.com/{}",
+                M::descriptor().full_name()
+            )),
+        );
+    }
+    Ok(value.to_string())
+}
+
+/// Returns the JSON of the `RestResponse` of the result. The `kcl_err` is set when
+/// the error is a KCL error with the diagnostics.
+pub fn rest_response(result: Result<String, KclvmError>) -> String {
+    let (result, error, kcl_err) = match result {
+        Ok(result) => (
+            serde_json::from_str(&result).unwrap_or(serde_json::Value::Null),
+            String::new(),
+            serde_json::Value::Null,
+        ),
+        Err(err @ KclvmError::Other(_)) => (
+            serde_json::Value::Null,
+            err.to_string(),
+            serde_json::Value::Null,
+        ),
+        Err(err) => (serde_json::Value::Null, err.message(), kcl_err_json(&err)),
+    };
+    serde_json::json!({
+        "result": result,
+        "error": error,
+        "kcl_err": kcl_err,
+    })
+    .to_string()
+}
+
+/// Returns the JSON of the `KclError` with an error info of every diagnostic message.
+fn kcl_err_json(err: &KclvmError) -> serde_json::Value {
+    let kcl_err = KclError {
+        // The error kind name of the first diagnostic, e.g. `TypeError`.
+        name: match err
+            .diagnostics()
+            .first()
+            .and_then(|diag| diag.code.as_ref())
+        {
+            Some(DiagnosticId::Error(kind)) => format!("{:?}", kind),
+            Some(DiagnosticId::Warning(kind)) => format!("{:?}", kind),
+            None => String::new(),
+        },
+        msg: err.message(),
+        error_infos: err
+            .diagnostics()
+            .iter()
+            .flat_map(|diag| {
+                let err_level = match diag.level {
+                    Level::Warning => "warning",
+                    _ => "error",
+                };
+                diag.messages.iter().map(move |msg| KclErrorInfo {
+                    err_level: err_level.to_string(),
+                    arg_msg: msg.message.clone(),
+                    filename: msg.pos.filename.clone(),
+                    line_no: msg.pos.line.to_string(),
+                    col_no: msg.pos.column.unwrap_or(0).to_string(),
+                    ..Default::default()
+                })
+            })
+            .collect(),
+        ..Default::default()
+    };
+    print_to_string_with_options(&kcl_err, &print_options())
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or(serde_json::Value::Null)
+}
@@ -3,6 +3,7 @@ use std::{path::Path, string::String, time::SystemTime};
 use crate::model::gpyrpc::*;
 
 use kclvm::ValueRef;
+use kclvm_error::{KclvmError, KclvmResult};
 use kclvm_parser::load_program;
 use kclvm_query::apply_overrides;
 use kclvm_query::override_file;
@@ -58,7 +59,7 @@ impl KclvmService {
     /// let exec_result = serv.exec_program(args).unwrap();
     /// println!("{}",exec_result.json_result);
     /// ```
-    pub fn exec_program(&self, args: &ExecProgram_Args) -> Result<ExecProgram_Result, String> {
+    pub fn exec_program(&self, args: &ExecProgram_Args) -> KclvmResult<ExecProgram_Result> {
         // transform args to json
         let args_json = print_to_string_with_options(
             args,
@@ -93,30 +94,24 @@ impl KclvmService {
             &[],
             native_args.print_override_ast,
         ) {
-            return Err(err.to_string());
+            return Err(KclvmError::load(&err.to_string()));
         }
 
         let start_time = SystemTime::now();
         let exec_result = kclvm_runner::execute(program, self.plugin_agent, &native_args);
         let escape_time = match SystemTime::now().duration_since(start_time) {
             Ok(dur) => dur.as_secs_f32(),
-            Err(err) => return Err(err.to_string()),
+            Err(err) => return Err(KclvmError::other(&err.to_string())),
         };
         result.escaped_time = escape_time.to_string();
-        let json_result = match exec_result {
-            Ok(res) => res,
-            Err(res) => {
-                if res.is_empty() {
-                    return Ok(result);
-                } else {
-                    return Err(res);
-                }
-            }
-        };
+        let json_result = exec_result?;
+        if json_result.is_empty() {
+            return Ok(result);
+        }
         let kcl_val = ValueRef::from_json(&json_result).unwrap();
         if let Some(val) = kcl_val.get_by_key("__kcl_PanicInfo__") {
             if val.is_truthy() {
-                return Err(json_result);
+                return Err(KclvmError::from_panic_message(&json_result));
             }
         }
         let (json_result, yaml_result) = kcl_val.plan();
@@ -43,3 +43,26 @@ pub fn emit_code(
     // Generate user KCL code LLVM IR
     crate::codegen::emit_code(ctx, opt)
 }
+
+/// Generate the LLVM module of the whole KCL program in memory. The packages
+/// of the program are linked into one module along with the runtime function
+/// declarations, so the module can be executed by the LLVM JIT execution engine
+/// without emitting any files or invoking the linker.
+pub fn emit_module<'ctx>(
+    context: &'ctx Context,
+    program: &'ctx ast::Program,
+    import_names: IndexMap<String, IndexMap<String, String>>,
+) -> Result<Module<'ctx>, Box<dyn error::Error>> {
+    // Init LLVM targets
+    LLVM_INIT.get_or_init(|| {
+        inkwell::targets::Target::initialize_all(&Default::default());
+    });
+    let module = load_runtime(context);
+    let ctx = LLVMCodeGenContext::new(context, module, program, import_names, false);
+    ctx.emit_code(&EmitOptions {
+        from_path: None,
+        emit_path: None,
+        no_link: false,
+    })?;
+    Ok(ctx.module)
+}
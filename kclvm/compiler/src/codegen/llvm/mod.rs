@@ -12,7 +12,7 @@ mod node;
 mod schema;
 mod utils;
 
-pub use emit::emit_code;
+pub use emit::{emit_code, emit_module};
 
 /// Object file type format suffix .o
 pub const OBJECT_FILE_SUFFIX: &str = ".o";
@@ -1,186 +1,381 @@
 // Copyright 2021 The KCL Authors. All rights reserved.
 extern crate chrono;
 use super::modfile::KCL_FILE_SUFFIX;
-use crypto::digest::Digest;
-use crypto::md5::Md5;
 use fslock::LockFile;
 use serde::{de::DeserializeOwned, Serialize};
-use std::collections::HashMap;
-use std::error;
-use std::fs::{create_dir_all, File};
-use std::io::{Read, Write};
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs::{create_dir_all, read_dir, remove_dir_all, remove_file, File};
+use std::path::{Path, PathBuf};
 
+pub use kclvm_utils::hash::ContentHasher;
 use kclvm_version as version;
 
 const LOCK_SUFFIX: &str = ".lock";
 const DEFAULT_CACHE_DIR: &str = ".kclvm/cache";
 const CACHE_INFO_FILENAME: &str = "info";
-const KCL_SUFFIX_PATTERN: &str = "*.k";
+const CACHE_LOCKS_DIRNAME: &str = "locks";
+/// The environment variable overriding the cache directory, e.g. a shared volume in CI.
+pub const KCLVM_CACHE_DIR: &str = "KCLVM_CACHE_DIR";
+/// The default max size of the cache in bytes, the least recently used entries
+/// are evicted once the cache grows larger than it.
+pub const DEFAULT_CACHE_MAX_SIZE: u64 = 1 << 30;
 
-pub type CacheInfo = String;
-pub type Cache = HashMap<String, CacheInfo>;
+/// The content address of a cache entry.
+pub type CacheKey = String;
+/// The last access timestamps of the cache entries in nanoseconds.
+pub type CacheInfo = HashMap<CacheKey, i64>;
 
-#[allow(dead_code)]
+#[derive(Clone)]
 pub struct CacheOption {
     cache_dir: String,
+    max_size: u64,
+}
+
+impl CacheOption {
+    /// Set the cache directory, a relative path is relative to the program root.
+    pub fn with_cache_dir(mut self, cache_dir: &str) -> Self {
+        self.cache_dir = cache_dir.to_string();
+        self
+    }
+
+    /// Set the max size of the cache in bytes.
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
 }
 
 impl Default for CacheOption {
     fn default() -> Self {
+        let cache_dir = match env::var(KCLVM_CACHE_DIR) {
+            // A relative override is relative to the working directory instead of the program root.
+            Ok(cache_dir) if !cache_dir.is_empty() => match env::current_dir() {
+                Ok(current_dir) => current_dir.join(cache_dir).to_string_lossy().to_string(),
+                Err(_) => cache_dir,
+            },
+            _ => DEFAULT_CACHE_DIR.to_string(),
+        };
         Self {
-            cache_dir: DEFAULT_CACHE_DIR.to_string(),
+            cache_dir,
+            max_size: DEFAULT_CACHE_MAX_SIZE,
         }
     }
 }
 
-/// Load pkg cache.
-pub fn load_pkg_cache<T>(root: &str, pkgpath: &str, option: CacheOption) -> Option<T>
+/// The statistics of the cache of a compiler version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheStat {
+    /// The compiler version of the cache.
+    pub version: String,
+    pub path: String,
+    /// The number of cache entries.
+    pub entries: usize,
+    /// The total size in bytes.
+    pub size: u64,
+}
+
+/// Load the cache entry addressed by the content hash `key`. The programs without
+/// a root use the cache in the working directory, see [get_cache_root].
+pub fn load_content_cache<T>(root: &str, key: &str, option: CacheOption) -> Option<T>
 where
     T: DeserializeOwned + Default,
 {
-    if root.is_empty() || pkgpath.is_empty() {
+    if key.is_empty() {
         None
     } else {
-        let filename = get_cache_filename(root, pkgpath, Some(&option.cache_dir));
-        if !Path::new(&filename).exists() {
-            None
-        } else {
-            // Compare the md5 using cache
-            let real_path = get_pkg_realpath_from_pkgpath(root, pkgpath);
-            if Path::new(&real_path).exists() {
-                let cache_info = read_info_cache(root, Some(&option.cache_dir));
-                let relative_path = real_path.replacen(root, ".", 1);
-                match cache_info.get(&relative_path) {
-                    Some(path_info_in_cache) => {
-                        if get_cache_info(&real_path).ne(path_info_in_cache) {
-                            return None;
-                        }
-                    }
-                    None => return None,
-                };
-            }
-            load_data_from_file(&filename)
+        let data = load_data_from_file(&get_cache_filename(root, key, Some(&option.cache_dir)));
+        if data.is_some() {
+            touch_cache_entry(&get_cache_dir(root, Some(&option.cache_dir)), key);
         }
+        data
     }
 }
 
-/// Save pkg cache.
-pub fn save_pkg_cache<T>(root: &str, pkgpath: &str, data: T, option: CacheOption)
+/// Save the cache entry addressed by the content hash `key`. The entries are
+/// immutable because the key changes along with any of its inputs, so the
+/// writers need no lock and the last rename wins with the same data.
+///
+/// The entries are not evicted here, see [evict_content_cache].
+pub fn save_content_cache<T>(root: &str, key: &str, data: T, option: CacheOption)
 where
     T: Serialize,
 {
-    if root.is_empty() || pkgpath.is_empty() {
+    if key.is_empty() {
         return;
     }
-    let dst_filename = get_cache_filename(root, pkgpath, Some(&option.cache_dir));
-    let real_path = get_pkg_realpath_from_pkgpath(root, pkgpath);
-    if Path::new(&real_path).exists() {
-        write_info_cache(root, Some(&option.cache_dir), &real_path).unwrap();
-    }
+    let dst_filename = get_cache_filename(root, key, Some(&option.cache_dir));
     let cache_dir = get_cache_dir(root, Some(&option.cache_dir));
     create_dir_all(&cache_dir).unwrap();
-    let tmp_filename = temp_file(&cache_dir, pkgpath);
-    save_data_to_file(&dst_filename, &tmp_filename, data)
+    let tmp_filename = temp_file(&cache_dir, key);
+    let file = File::create(&tmp_filename).unwrap();
+    ron::ser::to_writer(file, &data).unwrap();
+    std::fs::rename(&tmp_filename, &dst_filename).unwrap();
+    touch_cache_entry(&cache_dir, key);
+}
+
+/// Evict the least recently used entries except the `keep` ones once the cache
+/// grows larger than the max size of the option, and return the freed size in
+/// bytes. A run keeps all the entries it loads or saves, which are evicted
+/// after the run instead of in the middle of it.
+pub fn evict_content_cache(root: &str, keep: &[&str], option: &CacheOption) -> u64 {
+    let cache_dir = get_cache_dir(root, Some(&option.cache_dir));
+    evict_cache(&cache_dir, option.max_size, &keep.iter().copied().collect())
+}
+
+/// Returns the cache statistics of all the compiler versions.
+pub fn get_cache_stats(root: &str, option: &CacheOption) -> Vec<CacheStat> {
+    let cache_root = get_cache_root(root, Some(&option.cache_dir));
+    let mut stats = vec![];
+    if let Ok(entries) = read_dir(&cache_root) {
+        for entry in entries.flatten() {
+            if !entry.path().is_dir() {
+                continue;
+            }
+            let path = entry.path().to_string_lossy().to_string();
+            let cache_entries = read_cache_entries(&path);
+            stats.push(CacheStat {
+                version: entry.file_name().to_string_lossy().to_string(),
+                entries: cache_entries.len(),
+                size: cache_entries.values().map(|entry| entry.size).sum(),
+                path,
+            });
+        }
+    }
+    stats.sort_by(|a, b| a.version.cmp(&b.version));
+    stats
+}
+
+/// Remove the whole cache directory and return the freed size in bytes.
+pub fn clean_cache(root: &str, option: &CacheOption) -> std::io::Result<u64> {
+    let cache_root = get_cache_root(root, Some(&option.cache_dir));
+    if !Path::new(&cache_root).exists() {
+        return Ok(0);
+    }
+    let size = get_cache_stats(root, option)
+        .iter()
+        .map(|stat| stat.size)
+        .sum();
+    remove_dir_all(&cache_root)?;
+    Ok(size)
+}
+
+/// Remove the caches of the other compiler versions, then evict the least
+/// recently used entries until the cache is not larger than the max size of
+/// the option. Returns the freed size in bytes.
+///
+/// The lock files of the current version are kept, they are bounded, see
+/// [get_cache_lock_filename], and removing a lock file which another process
+/// is waiting for would let two processes hold the same lock.
+pub fn prune_cache(root: &str, option: &CacheOption) -> std::io::Result<u64> {
+    let cache_dir = get_cache_dir(root, Some(&option.cache_dir));
+    let mut freed = 0;
+    for stat in get_cache_stats(root, option) {
+        if stat.path != cache_dir {
+            remove_dir_all(&stat.path)?;
+            freed += stat.size;
+        }
+    }
+    Ok(freed + evict_cache(&cache_dir, option.max_size, &HashSet::new()))
+}
+
+/// Returns the cache root directory which contains the caches of all the compiler versions.
+///
+/// The programs without a root, e.g. the ones compiled from the source code, use
+/// the cache in the working directory.
+#[inline]
+pub fn get_cache_root(root: &str, cache_dir: Option<&str>) -> String {
+    let cache_dir = match cache_dir {
+        Some(cache_dir) => cache_dir.to_string(),
+        None => CacheOption::default().cache_dir,
+    };
+    let root = if root.is_empty() {
+        env::current_dir().unwrap_or_default()
+    } else {
+        PathBuf::from(root)
+    };
+    // An absolute cache directory replaces the root.
+    root.join(cache_dir).to_string_lossy().to_string()
 }
 
+/// Returns the cache directory of the current compiler version.
 #[inline]
-fn get_cache_dir(root: &str, cache_dir: Option<&str>) -> String {
-    let cache_dir = cache_dir.or(Some(DEFAULT_CACHE_DIR)).unwrap();
-    format!(
-        "{}/{}/{}-{}",
-        root,
-        cache_dir,
-        version::VERSION,
-        version::CHECK_SUM
-    )
+pub fn get_cache_dir(root: &str, cache_dir: Option<&str>) -> String {
+    Path::new(&get_cache_root(root, cache_dir))
+        .join(format!("{}-{}", version::VERSION, version::CHECK_SUM))
+        .to_string_lossy()
+        .to_string()
 }
 
+/// Returns the filename of the cache entry addressed by `key`.
 #[inline]
-#[allow(dead_code)]
-fn get_cache_filename(root: &str, pkgpath: &str, cache_dir: Option<&str>) -> String {
-    let cache_dir = cache_dir.or(Some(DEFAULT_CACHE_DIR)).unwrap();
-    format!(
-        "{}/{}/{}-{}/{}",
-        root,
-        cache_dir,
-        version::VERSION,
-        version::CHECK_SUM,
-        pkgpath
-    )
+pub fn get_cache_filename(root: &str, key: &str, cache_dir: Option<&str>) -> String {
+    Path::new(&get_cache_dir(root, cache_dir))
+        .join(key)
+        .to_string_lossy()
+        .to_string()
 }
 
+/// Returns the lock file which serializes the writers of the cache entry `key`.
+/// The keys share 256 lock files by their first two hex digits, so the lock files
+/// are bounded and never removed.
 #[inline]
-fn get_cache_info_filename(root: &str, cache_dir: Option<&str>) -> String {
-    let cache_dir = cache_dir.or(Some(DEFAULT_CACHE_DIR)).unwrap();
-    format!(
-        "{}/{}/{}-{}/{}",
-        root,
-        cache_dir,
-        version::VERSION,
-        version::CHECK_SUM,
-        CACHE_INFO_FILENAME
-    )
-}
-
-/// Read the cache if it exists and is well formed.
-/// If it is not well formed, the call to write_info_cache later should resolve the issue.
-pub fn read_info_cache(root: &str, cache_dir: Option<&str>) -> Cache {
-    let cache_file = get_cache_info_filename(root, cache_dir);
-    if !Path::new(&cache_file).exists() {
-        return Cache::default();
-    }
-    let file = File::open(cache_file).unwrap();
-    match ron::de::from_reader(file) {
-        Ok(cache) => cache,
-        Err(_) => HashMap::new(),
-    }
-}
-
-/// Update the cache info file.
-pub fn write_info_cache(
-    root: &str,
-    cache_name: Option<&str>,
-    filepath: &str,
-) -> Result<(), Box<dyn error::Error>> {
-    let dst_filename = get_cache_info_filename(root, cache_name);
-    let cache_dir = get_cache_dir(root, cache_name);
-    let path = Path::new(&cache_dir);
-    create_dir_all(path).unwrap();
-    let relative_path = filepath.replacen(root, ".", 1);
-    let cache_info = get_cache_info(filepath);
-    let tmp_filename = temp_file(&cache_dir, "");
+pub fn get_cache_lock_filename(root: &str, key: &str, cache_dir: Option<&str>) -> String {
+    Path::new(&get_cache_dir(root, cache_dir))
+        .join(CACHE_LOCKS_DIRNAME)
+        .join(format!("{}{}", key.get(..2).unwrap_or(key), LOCK_SUFFIX))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// The files of a cache entry, which are all named by the key, e.g. the data
+/// file `<key>`, the library `<key>.so` and the lock files.
+#[derive(Default)]
+struct CacheEntry {
+    files: Vec<String>,
+    size: u64,
+}
+
+fn read_cache_entries(cache_dir: &str) -> HashMap<CacheKey, CacheEntry> {
+    let mut cache_entries: HashMap<CacheKey, CacheEntry> = HashMap::new();
+    if let Ok(entries) = read_dir(cache_dir) {
+        for entry in entries.flatten() {
+            // Skip the lock directory.
+            if entry.path().is_dir() {
+                continue;
+            }
+            let filename = entry.file_name().to_string_lossy().to_string();
+            let key = filename.split('.').next().unwrap_or_default();
+            if key.is_empty() || key == CACHE_INFO_FILENAME {
+                continue;
+            }
+            let cache_entry = cache_entries.entry(key.to_string()).or_default();
+            cache_entry.size += entry.metadata().map(|m| m.len()).unwrap_or_default();
+            cache_entry
+                .files
+                .push(entry.path().to_string_lossy().to_string());
+        }
+    }
+    cache_entries
+}
+
+/// Evict the least recently used entries except `keep` until the cache is not
+/// larger than `max_size`, and return the freed size in bytes.
+fn evict_cache(cache_dir: &str, max_size: u64, keep: &HashSet<&str>) -> u64 {
+    let cache_entries = read_cache_entries(cache_dir);
+    let total: u64 = cache_entries.values().map(|entry| entry.size).sum();
+    let mut size = total;
+    if size <= max_size {
+        return 0;
+    }
+    let info = read_cache_info(cache_dir);
+    // The entries without the access info are evicted first.
+    let mut keys: Vec<&CacheKey> = cache_entries.keys().collect();
+    keys.sort_by_key(|key| (info.get(*key).copied().unwrap_or_default(), key.to_string()));
+    let mut evicted = vec![];
+    for key in keys {
+        if size <= max_size {
+            break;
+        }
+        if keep.contains(key.as_str()) {
+            continue;
+        }
+        let cache_entry = &cache_entries[key];
+        // Remove the data file first, so the entry is never loaded without its library.
+        let mut files: Vec<&String> = cache_entry
+            .files
+            .iter()
+            .filter(|file| !file.ends_with(LOCK_SUFFIX))
+            .collect();
+        files.sort_by_key(|file| file.len());
+        for file in files {
+            let _ = remove_file(file);
+        }
+        size = size.saturating_sub(cache_entry.size);
+        evicted.push(key.to_string());
+    }
+    update_cache_info(cache_dir, |info| {
+        for key in &evicted {
+            info.remove(key);
+        }
+    });
+    total - size
+}
+
+/// Read the cache info if it exists and is well formed.
+fn read_cache_info(cache_dir: &str) -> CacheInfo {
+    load_data_from_file(&format!("{}/{}", cache_dir, CACHE_INFO_FILENAME)).unwrap_or_default()
+}
+
+/// Record the last access time of the cache entry.
+fn touch_cache_entry(cache_dir: &str, key: &str) {
+    let timestamp = chrono::Local::now().timestamp_nanos();
+    update_cache_info(cache_dir, |info| {
+        info.insert(key.to_string(), timestamp);
+    });
+}
+
+/// Update the cache info file, the reading and writing are in the same file lock.
+fn update_cache_info<F>(cache_dir: &str, update: F)
+where
+    F: FnOnce(&mut CacheInfo),
+{
+    if !Path::new(cache_dir).exists() {
+        return;
+    }
+    let dst_filename = format!("{}/{}", cache_dir, CACHE_INFO_FILENAME);
     let mut lock_file = LockFile::open(&format!("{}{}", dst_filename, LOCK_SUFFIX)).unwrap();
     lock_file.lock().unwrap();
-    let mut cache = read_info_cache(root, cache_name);
-    cache.insert(relative_path, cache_info);
-    let mut file = File::create(&tmp_filename).unwrap();
-    file.write_all(&ron::ser::to_string(&cache).unwrap().as_bytes())
-        .unwrap();
+    let mut info = read_cache_info(cache_dir);
+    update(&mut info);
+    let tmp_filename = temp_file(cache_dir, CACHE_INFO_FILENAME);
+    let file = File::create(&tmp_filename).unwrap();
+    ron::ser::to_writer(file, &info).unwrap();
     std::fs::rename(&tmp_filename, &dst_filename).unwrap();
     lock_file.unlock().unwrap();
-    Ok(())
-}
-
-/// Return the information used to check if a file or path is already changed or not.
-fn get_cache_info(path_str: &str) -> CacheInfo {
-    let path = Path::new(path_str);
-    let mut md5 = Md5::new();
-    if path.is_file() {
-        let mut file = File::open(path_str).unwrap();
-        let mut buf: Vec<u8> = vec![];
-        file.read_to_end(&mut buf).unwrap();
-        md5.input(buf.as_slice());
+}
+
+/// Parse the human readable size such as `512M` and `1G` into bytes.
+pub fn parse_size(size: &str) -> Result<u64, String> {
+    let size = size.trim();
+    let (number, unit) = match size.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => size.split_at(index),
+        None => (size, ""),
+    };
+    let shift = match unit
+        .trim()
+        .to_ascii_uppercase()
+        .trim_end_matches("IB")
+        .trim_end_matches('B')
+    {
+        "" => 0,
+        "K" => 10,
+        "M" => 20,
+        "G" => 30,
+        "T" => 40,
+        _ => return Err(format!("invalid size '{}'", size)),
+    };
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(1 << shift))
+        .ok_or_else(|| format!("invalid size '{}'", size))
+}
+
+/// Format the size in bytes into the human readable size such as `1.5 MiB`.
+pub fn format_size(size: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", size, UNITS[0])
     } else {
-        let pattern = format!("{}/{}", path_str, KCL_SUFFIX_PATTERN);
-        for file in glob::glob(&pattern).unwrap().flatten() {
-            let mut file = File::open(file).unwrap();
-            let mut buf: Vec<u8> = vec![];
-            file.read_to_end(&mut buf).unwrap();
-            md5.input(buf.as_slice());
-        }
+        format!("{:.1} {}", value, UNITS[unit])
     }
-    md5.result_str()
 }
 
 pub fn get_pkg_realpath_from_pkgpath(root: &str, pkgpath: &str) -> String {
@@ -223,3 +418,122 @@ fn temp_file(cache_dir: &str, pkgpath: &str) -> String {
     let id = std::process::id();
     format!("{}/{}.{}.{}.tmp", cache_dir, pkgpath, id, timestamp)
 }
+
+#[cfg(test)]
+mod cache_test {
+    use crate::cache::*;
+
+    #[test]
+    fn test_content_cache() {
+        let root = std::env::temp_dir().join(format!("kclvm_cache_test_{}", std::process::id()));
+        let root = root.to_str().unwrap();
+        let key = ContentHasher::new().input_str("pkg").result();
+        assert_eq!(
+            load_content_cache::<String>(root, &key, CacheOption::default()),
+            None
+        );
+        save_content_cache(root, &key, "./pkg.so".to_string(), CacheOption::default());
+        assert!(read_cache_info(&get_cache_dir(root, None)).contains_key(&key));
+        assert_eq!(
+            load_content_cache::<String>(root, &key, CacheOption::default()),
+            Some("./pkg.so".to_string())
+        );
+        assert_eq!(
+            load_content_cache::<String>("", &key, CacheOption::default()),
+            None
+        );
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_evict_cache() {
+        let root = std::env::temp_dir().join(format!("kclvm_evict_test_{}", std::process::id()));
+        let root = root.to_str().unwrap();
+        let option = CacheOption::default().with_cache_dir("cache");
+        let cache_dir = get_cache_dir(root, Some("cache"));
+        let keys: Vec<CacheKey> = ["a", "b", "c"]
+            .iter()
+            .map(|data| ContentHasher::new().input_str(data).result())
+            .collect();
+        for key in &keys {
+            save_content_cache(root, key, key.clone(), option.clone());
+            std::fs::write(format!("{}/{}.so", cache_dir, key), vec![0; 100]).unwrap();
+        }
+        // Access the first entry, the second one is the least recently used.
+        assert!(load_content_cache::<String>(root, &keys[0], option.clone()).is_some());
+        let stats = get_cache_stats(root, &option);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].entries, 3);
+
+        // The entries kept by the current run are never evicted.
+        let small = option.clone().with_max_size(1);
+        assert_eq!(
+            evict_content_cache(root, &[&keys[0], &keys[1], &keys[2]], &small),
+            0
+        );
+        assert_eq!(get_cache_stats(root, &option)[0].entries, 3);
+
+        let freed = prune_cache(root, &option.clone().with_max_size(stats[0].size - 1)).unwrap();
+        assert!(freed > 100);
+        assert!(load_content_cache::<String>(root, &keys[0], option.clone()).is_some());
+        assert!(load_content_cache::<String>(root, &keys[1], option.clone()).is_none());
+        assert!(load_content_cache::<String>(root, &keys[2], option.clone()).is_some());
+        assert!(!Path::new(&format!("{}/{}.so", cache_dir, keys[1])).exists());
+        // The entries have no lock files of their own.
+        assert!(!Path::new(&format!("{}/{}.lock", cache_dir, keys[0])).exists());
+
+        assert!(clean_cache(root, &option).unwrap() > 0);
+        assert!(get_cache_stats(root, &option).is_empty());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_content_cache_without_root() {
+        // The programs without a root are cached and evicted as well.
+        let cache_root =
+            std::env::temp_dir().join(format!("kclvm_no_root_test_{}", std::process::id()));
+        let option = CacheOption::default().with_cache_dir(cache_root.to_str().unwrap());
+        let keys: Vec<CacheKey> = ["a", "b"]
+            .iter()
+            .map(|data| ContentHasher::new().input_str(data).result())
+            .collect();
+        for key in &keys {
+            save_content_cache("", key, key.clone(), option.clone());
+        }
+        assert_eq!(
+            load_content_cache::<String>("", &keys[0], option.clone()),
+            Some(keys[0].clone())
+        );
+        assert_eq!(get_cache_stats("", &option)[0].entries, 2);
+        assert!(evict_content_cache("", &[&keys[0]], &option.clone().with_max_size(1)) > 0);
+        assert!(load_content_cache::<String>("", &keys[0], option.clone()).is_some());
+        assert!(load_content_cache::<String>("", &keys[1], option.clone()).is_none());
+        std::fs::remove_dir_all(cache_root).unwrap();
+    }
+
+    #[test]
+    fn test_cache_dir() {
+        let cache_dir = get_cache_dir("/root", None);
+        assert!(cache_dir.starts_with("/root/.kclvm/cache/"));
+        // The programs without a root use the cache in the working directory.
+        let current_dir = std::env::current_dir().unwrap();
+        assert!(Path::new(&get_cache_dir("", None)).starts_with(current_dir));
+        assert_eq!(get_cache_root("/root", Some("/tmp/cache")), "/tmp/cache");
+        assert!(get_cache_lock_filename("/root", "abcd", None).ends_with("locks/ab.lock"));
+    }
+
+    #[test]
+    fn test_size() {
+        assert_eq!(parse_size("1024"), Ok(1024));
+        assert_eq!(parse_size("512M"), Ok(512 << 20));
+        assert_eq!(parse_size("1GiB"), Ok(1 << 30));
+        assert_eq!(parse_size("2kb"), Ok(2048));
+        assert!(parse_size("1X").is_err());
+        assert!(parse_size("M").is_err());
+        assert!(parse_size("16777216T").is_err());
+        assert!(parse_size("18446744073709551616").is_err());
+        assert_eq!(format_size(100), "100 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(1 << 30), "1.0 GiB");
+    }
+}
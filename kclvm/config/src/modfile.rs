@@ -1,7 +1,7 @@
 // Copyright 2021 The KCL Authors. All rights reserved.
 
+use crate::vfs::{DiskFileSystem, FileSystem};
 use serde::Deserialize;
-use std::io::Read;
 use toml;
 
 pub const KCL_MOD_FILE: &str = "kcl.mod";
@@ -15,6 +15,8 @@ pub struct KCLModFile {
     pub root_pkg: Option<String>,
     pub build: Option<KCLModFileBuildSection>,
     pub expected: Option<KCLModFileExpectedSection>,
+    pub deps: Option<KCLModFileDepsSection>,
+    pub targets: Option<Vec<KCLModFileTarget>>,
 }
 
 #[allow(dead_code)]
@@ -25,6 +27,29 @@ pub struct KCLModFileBuildSection {
     pub target: Option<String>,
 }
 
+#[allow(dead_code)]
+#[derive(Default, Deserialize)]
+pub struct KCLModFileDepsSection {
+    /// The package directories or `.zip`/`.tar.gz` package archives to search the imported
+    /// packages in, relative to the kcl.mod file.
+    pub paths: Option<Vec<String>>,
+}
+
+/// An entry program of the package, e.g.
+///
+/// ```toml
+/// [[targets]]
+/// name = "prod"
+/// files = ["prod/main.k"]
+/// ```
+#[allow(dead_code)]
+#[derive(Default, Deserialize)]
+pub struct KCLModFileTarget {
+    pub name: Option<String>,
+    /// The main files of the program, relative to the kcl.mod file.
+    pub files: Vec<String>,
+}
+
 #[allow(dead_code)]
 #[derive(Default, Deserialize)]
 pub struct KCLModFileExpectedSection {
@@ -36,6 +61,14 @@ pub struct KCLModFileExpectedSection {
 }
 
 pub fn get_pkg_root_from_paths(file_paths: &[String]) -> Result<String, String> {
+    get_pkg_root_from_paths_with_fs(&DiskFileSystem, file_paths)
+}
+
+/// Get the package root of the file paths in the file system "fs".
+pub fn get_pkg_root_from_paths_with_fs(
+    fs: &dyn FileSystem,
+    file_paths: &[String],
+) -> Result<String, String> {
     if file_paths.is_empty() {
         return Err("No input KCL files or paths".to_string());
     }
@@ -46,7 +79,7 @@ pub fn get_pkg_root_from_paths(file_paths: &[String]) -> Result<String, String>
         if s.contains(KCL_MOD_PATH_ENV) {
             continue;
         }
-        if let Some(root) = get_pkg_root(s) {
+        if let Some(root) = get_pkg_root_with_fs(fs, s) {
             m.insert(root.clone(), root.clone());
             last_root = root.clone();
         }
@@ -62,15 +95,20 @@ pub fn get_pkg_root_from_paths(file_paths: &[String]) -> Result<String, String>
 }
 
 pub fn get_pkg_root(k_file_path: &str) -> Option<String> {
+    get_pkg_root_with_fs(&DiskFileSystem, k_file_path)
+}
+
+/// Get the package root of the file path in the file system "fs".
+pub fn get_pkg_root_with_fs(fs: &dyn FileSystem, k_file_path: &str) -> Option<String> {
     if k_file_path.is_empty() {
         return None;
     }
     // # search by kcl.mod file
-    if let Ok(module_path) = std::path::Path::new(k_file_path).canonicalize() {
-        let mut module_path = module_path;
-        while module_path.exists() {
+    if let Ok(module_path) = fs.canonicalize(k_file_path) {
+        let mut module_path = std::path::PathBuf::from(module_path);
+        while fs.exists(module_path.to_str().unwrap()) {
             let kcl_mod_path = module_path.join(KCL_MOD_FILE);
-            if kcl_mod_path.exists() && kcl_mod_path.is_file() {
+            if fs.is_file(kcl_mod_path.to_str().unwrap()) {
                 return Some(module_path.to_str().unwrap().to_string());
             }
             if let Some(path) = module_path.parent() {
@@ -81,8 +119,8 @@ pub fn get_pkg_root(k_file_path: &str) -> Option<String> {
         }
     }
     if k_file_path.ends_with(KCL_FILE_SUFFIX) {
-        if let Ok(path) = std::path::Path::new(k_file_path).canonicalize() {
-            if let Some(path) = path.parent() {
+        if let Ok(path) = fs.canonicalize(k_file_path) {
+            if let Some(path) = std::path::Path::new(&path).parent() {
                 return Some(path.to_str().unwrap().to_string());
             }
         }
@@ -91,14 +129,18 @@ pub fn get_pkg_root(k_file_path: &str) -> Option<String> {
 }
 
 pub fn load_mod_file(root: &str) -> KCLModFile {
+    load_mod_file_with_fs(&DiskFileSystem, root)
+}
+
+/// Load the kcl.mod file under the root directory in the file system "fs".
+pub fn load_mod_file_with_fs(fs: &dyn FileSystem, root: &str) -> KCLModFile {
     let k_mod_file_path = std::path::Path::new(root).join(KCL_MOD_FILE);
-    if !k_mod_file_path.exists() {
+    let k_mod_file_path = k_mod_file_path.to_str().unwrap();
+    if !fs.exists(k_mod_file_path) {
         return KCLModFile::default();
     }
-    let mut file = std::fs::File::open(k_mod_file_path.to_str().unwrap()).unwrap();
-    let mut buffer: Vec<u8> = vec![];
-    file.read_to_end(&mut buffer).unwrap();
-    toml::from_slice(buffer.as_slice()).unwrap()
+    let content = fs.read_to_string(k_mod_file_path).unwrap();
+    toml::from_str(&content).unwrap()
 }
 
 #[cfg(test)]
@@ -135,6 +177,23 @@ mod modfile_test {
         assert_eq!(root.unwrap().as_str(), expected);
     }
 
+    #[test]
+    fn test_get_pkg_root_with_fs() {
+        let fs = crate::vfs::MemoryFileSystem::default();
+        fs.insert("/konfig/kcl.mod", "");
+        fs.insert("/konfig/app/main.k", "a = 1");
+        fs.insert("/app/main.k", "a = 1");
+        assert_eq!(
+            get_pkg_root_with_fs(&fs, "/konfig/app/main.k"),
+            Some("/konfig".to_string())
+        );
+        assert_eq!(
+            get_pkg_root_with_fs(&fs, "/app/main.k"),
+            Some("/app".to_string())
+        );
+        assert_eq!(get_pkg_root_with_fs(&fs, "/app/other.k"), None);
+    }
+
     #[test]
     fn test_load_mod_file() {
         let kcl_mod = load_mod_file(TEST_ROOT);
@@ -172,5 +231,14 @@ mod modfile_test {
                 .unwrap(),
             "v0.2.0"
         );
+        assert_eq!(
+            kcl_mod.deps.as_ref().unwrap().paths.as_ref().unwrap(),
+            &vec!["vendor/lib.tar.gz".to_string()]
+        );
+        let targets = kcl_mod.targets.as_ref().unwrap();
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].name.as_deref(), Some("prod"));
+        assert_eq!(targets[0].files, vec!["prod/main.k".to_string()]);
+        assert_eq!(targets[1].name, None);
     }
 }
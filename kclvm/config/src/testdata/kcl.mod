@@ -4,3 +4,10 @@ cached_pkg_prefix="pkg.path"
 [expected]
 kclvm_version="v0.3.0"
 kcl_plugin_version="v0.2.0"
+[deps]
+paths=["vendor/lib.tar.gz"]
+[[targets]]
+name="prod"
+files=["prod/main.k"]
+[[targets]]
+files=["dev/main.k", "dev/dev.k"]
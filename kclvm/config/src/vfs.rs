@@ -1,6 +1,328 @@
 extern crate pathdiff;
 
-use std::path::Path;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// FileSystem is the file system abstraction used by the program loader, the paths are
+/// the same as the ones of the KCL files and package directories.
+pub trait FileSystem: Debug + Send + Sync {
+    /// Read the entire contents of a file into a string.
+    fn read_to_string(&self, path: &str) -> io::Result<String>;
+    /// Read the entire contents of a file into a bytes vector.
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        self.read_to_string(path).map(String::into_bytes)
+    }
+    /// Returns the sorted entry paths in a directory.
+    fn read_dir(&self, path: &str) -> io::Result<Vec<String>>;
+    /// Returns the absolute normalized form of a path.
+    fn canonicalize(&self, path: &str) -> io::Result<String>;
+    fn is_file(&self, path: &str) -> bool;
+    fn is_dir(&self, path: &str) -> bool;
+    fn exists(&self, path: &str) -> bool {
+        self.is_file(path) || self.is_dir(path)
+    }
+}
+
+/// DiskFileSystem reads the files on disk with `std::fs`.
+#[derive(Debug, Default, Clone)]
+pub struct DiskFileSystem;
+
+impl FileSystem for DiskFileSystem {
+    fn read_to_string(&self, path: &str) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+        let mut list = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            list.push(format!("{}", entry?.path().display()));
+        }
+        list.sort();
+        Ok(list)
+    }
+
+    fn canonicalize(&self, path: &str) -> io::Result<String> {
+        let path = std::fs::canonicalize(path)?;
+        Ok(path.to_str().unwrap().to_string())
+    }
+
+    fn is_file(&self, path: &str) -> bool {
+        Path::new(path).is_file()
+    }
+
+    fn is_dir(&self, path: &str) -> bool {
+        Path::new(path).is_dir()
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        Path::new(path).exists()
+    }
+}
+
+/// MemoryFileSystem holds the files in memory, the directories are implied by the file paths.
+/// Relative paths are resolved against the current working directory.
+///
+/// # Examples
+///
+/// ```
+/// use kclvm_config::vfs::{FileSystem, MemoryFileSystem};
+///
+/// let fs = MemoryFileSystem::default();
+/// fs.insert("/app/main.k", "a = 1");
+/// assert!(fs.is_dir("/app"));
+/// assert_eq!(fs.read_to_string("/app/./main.k").unwrap(), "a = 1");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MemoryFileSystem {
+    files: Arc<RwLock<BTreeMap<PathBuf, Vec<u8>>>>,
+}
+
+impl MemoryFileSystem {
+    /// Add or replace a file.
+    pub fn insert(&self, path: &str, content: &str) {
+        self.insert_bytes(path, content.as_bytes().to_vec());
+    }
+
+    /// Add or replace a file with the bytes content.
+    pub fn insert_bytes(&self, path: &str, content: Vec<u8>) {
+        self.files
+            .write()
+            .unwrap()
+            .insert(normalize_path(path), content);
+    }
+
+    /// Remove a file and returns its content.
+    pub fn remove(&self, path: &str) -> Option<String> {
+        self.files
+            .write()
+            .unwrap()
+            .remove(&normalize_path(path))
+            .map(|content| String::from_utf8_lossy(&content).to_string())
+    }
+
+    /// Add all the files in a `.zip`, `.tar.gz` or `.tgz` package archive, the files are placed
+    /// under the archive path as if the archive were a directory, e.g., the entry `pkg/main.k`
+    /// of the archive `vendor/lib.tar.gz` is added as `vendor/lib.tar.gz/pkg/main.k`.
+    pub fn insert_archive(&self, archive: &str, data: &[u8]) -> io::Result<()> {
+        for (name, content) in read_archive(archive, data)? {
+            let rel_path = Path::new(&name);
+            if rel_path
+                .components()
+                .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid entry '{}' in the archive {}", name, archive),
+                ));
+            }
+            let path = Path::new(archive).join(rel_path);
+            self.insert_bytes(path.to_str().unwrap(), content);
+        }
+        Ok(())
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn read_to_string(&self, path: &str) -> io::Result<String> {
+        String::from_utf8(self.read(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        match self.files.read().unwrap().get(&normalize_path(path)) {
+            Some(content) => Ok(content.clone()),
+            None => Err(not_found(path)),
+        }
+    }
+
+    fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+        let dir = normalize_path(path);
+        let mut list = BTreeSet::new();
+        for file in self.files.read().unwrap().keys() {
+            if let Ok(rel_path) = file.strip_prefix(&dir) {
+                if let Some(name) = rel_path.components().next() {
+                    list.insert(format!("{}", dir.join(name).display()));
+                }
+            }
+        }
+        if list.is_empty() {
+            return Err(not_found(path));
+        }
+        Ok(list.into_iter().collect())
+    }
+
+    fn canonicalize(&self, path: &str) -> io::Result<String> {
+        if self.exists(path) {
+            Ok(format!("{}", normalize_path(path).display()))
+        } else {
+            Err(not_found(path))
+        }
+    }
+
+    fn is_file(&self, path: &str) -> bool {
+        self.files
+            .read()
+            .unwrap()
+            .contains_key(&normalize_path(path))
+    }
+
+    fn is_dir(&self, path: &str) -> bool {
+        let dir = normalize_path(path);
+        self.files
+            .read()
+            .unwrap()
+            .keys()
+            .any(|file| file != &dir && file.starts_with(&dir))
+    }
+}
+
+/// OverlayFileSystem layers an upper file system such as the unsaved editor buffers over
+/// a lower one, the files in the upper layer shadow the ones in the lower layer and the
+/// directory entries of both layers are merged.
+#[derive(Debug, Clone)]
+pub struct OverlayFileSystem {
+    upper: Arc<dyn FileSystem>,
+    lower: Arc<dyn FileSystem>,
+}
+
+impl OverlayFileSystem {
+    pub fn new(upper: Arc<dyn FileSystem>, lower: Arc<dyn FileSystem>) -> Self {
+        Self { upper, lower }
+    }
+}
+
+impl FileSystem for OverlayFileSystem {
+    fn read_to_string(&self, path: &str) -> io::Result<String> {
+        if self.upper.is_file(path) {
+            self.upper.read_to_string(path)
+        } else {
+            self.lower.read_to_string(path)
+        }
+    }
+
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        if self.upper.is_file(path) {
+            self.upper.read(path)
+        } else {
+            self.lower.read(path)
+        }
+    }
+
+    fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+        match (self.upper.read_dir(path), self.lower.read_dir(path)) {
+            (Err(err), Err(_)) => Err(err),
+            (upper, lower) => {
+                // Entries are keyed by the file name, the lower paths take precedence to
+                // keep the path form of the lower layer.
+                let mut entries = BTreeMap::new();
+                for entry in lower.into_iter().chain(upper).flatten() {
+                    if let Some(name) = Path::new(&entry).file_name() {
+                        entries.entry(name.to_owned()).or_insert(entry);
+                    }
+                }
+                Ok(entries.into_values().collect())
+            }
+        }
+    }
+
+    fn canonicalize(&self, path: &str) -> io::Result<String> {
+        match self.lower.canonicalize(path) {
+            Ok(path) => Ok(path),
+            Err(_) => self.upper.canonicalize(path),
+        }
+    }
+
+    fn is_file(&self, path: &str) -> bool {
+        self.upper.is_file(path) || self.lower.is_file(path)
+    }
+
+    fn is_dir(&self, path: &str) -> bool {
+        self.upper.is_dir(path) || self.lower.is_dir(path)
+    }
+}
+
+/// The suffixes of the supported package archives.
+pub const ARCHIVE_SUFFIXES: &[&str] = &[".zip", ".tar.gz", ".tgz"];
+
+/// Whether the path is a package archive.
+pub fn is_archive(path: &str) -> bool {
+    ARCHIVE_SUFFIXES.iter().any(|suffix| path.ends_with(suffix))
+}
+
+/// Read the file entries of a package archive, returns the entry names and contents.
+fn read_archive(archive: &str, data: &[u8]) -> io::Result<Vec<(String, Vec<u8>)>> {
+    let mut entries = Vec::new();
+    if archive.ends_with(".zip") {
+        let mut zip = zip::ZipArchive::new(io::Cursor::new(data))
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        for i in 0..zip.len() {
+            let mut file = zip
+                .by_index(i)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            if file.is_file() {
+                let mut content = Vec::new();
+                file.read_to_end(&mut content)?;
+                entries.push((file.name().to_string(), content));
+            }
+        }
+    } else if is_archive(archive) {
+        let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(data));
+        for file in tar.entries()? {
+            let mut file = file?;
+            if file.header().entry_type().is_file() {
+                let name = file.path()?.to_str().unwrap_or_default().to_string();
+                let mut content = Vec::new();
+                file.read_to_end(&mut content)?;
+                entries.push((name, content));
+            }
+        }
+    } else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "unsupported package archive {}, expected one of {:?}",
+                archive, ARCHIVE_SUFFIXES
+            ),
+        ));
+    }
+    Ok(entries)
+}
+
+/// Returns the absolute path without the `.` and `..` components, the symbolic links are
+/// not resolved.
+pub fn normalize_path(path: &str) -> PathBuf {
+    let path = Path::new(path);
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                result.pop();
+            }
+            _ => result.push(component),
+        }
+    }
+    result
+}
+
+fn not_found(path: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("No such file or directory: {}", path),
+    )
+}
 
 pub fn is_abs_pkgpath(pkgpath: &str) -> bool {
     if pkgpath.is_empty() {
@@ -117,3 +439,96 @@ fn test_fix_import_path() {
     let s = fix_import_path(root, "path/to/app/file.k", ".....sub");
     assert_eq!(s, "");
 }
+
+#[test]
+fn test_memory_file_system() {
+    let fs = MemoryFileSystem::default();
+    fs.insert("/app/main.k", "a = 1");
+    fs.insert("/app/pkg/../sub/sub.k", "b = 1");
+
+    assert!(fs.is_file("/app/main.k"));
+    assert!(fs.is_dir("/app/sub"));
+    assert!(!fs.is_dir("/app/main.k"));
+    assert!(!fs.exists("/app/pkg"));
+    assert_eq!(fs.read_to_string("/app/sub/sub.k").unwrap(), "b = 1");
+    assert_eq!(
+        fs.read_dir("/app").unwrap(),
+        vec!["/app/main.k".to_string(), "/app/sub".to_string()]
+    );
+    assert_eq!(
+        fs.canonicalize("/app/sub/../main.k").unwrap(),
+        "/app/main.k"
+    );
+    assert!(fs.read_dir("/other").is_err());
+
+    assert_eq!(fs.remove("/app/main.k"), Some("a = 1".to_string()));
+    assert!(fs.read_to_string("/app/main.k").is_err());
+}
+
+#[test]
+fn test_overlay_file_system() {
+    let lower = MemoryFileSystem::default();
+    lower.insert("/app/main.k", "a = 1");
+    lower.insert("/app/base.k", "base = 1");
+    let upper = MemoryFileSystem::default();
+    upper.insert("/app/main.k", "a = 2");
+    upper.insert("/app/gen/gen.k", "gen = 1");
+    let fs = OverlayFileSystem::new(Arc::new(upper), Arc::new(lower));
+
+    assert_eq!(fs.read_to_string("/app/main.k").unwrap(), "a = 2");
+    assert_eq!(fs.read_to_string("/app/base.k").unwrap(), "base = 1");
+    assert!(fs.is_dir("/app/gen"));
+    assert_eq!(
+        fs.read_dir("/app").unwrap(),
+        vec![
+            "/app/base.k".to_string(),
+            "/app/gen".to_string(),
+            "/app/main.k".to_string()
+        ]
+    );
+}
+
+#[test]
+fn test_insert_archive() {
+    use std::io::Write;
+
+    let mut tar_gz = Vec::new();
+    {
+        let encoder = flate2::write::GzEncoder::new(&mut tar_gz, flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        let content = b"schema Base:\n    name: str\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "lib/base/base.k", &content[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+    let mut zip_data = Vec::new();
+    {
+        let mut writer = zip::ZipWriter::new(io::Cursor::new(&mut zip_data));
+        writer
+            .start_file("lib/main.k", zip::write::FileOptions::default())
+            .unwrap();
+        writer.write_all(b"a = 1").unwrap();
+        writer.finish().unwrap();
+    }
+
+    let fs = MemoryFileSystem::default();
+    fs.insert_archive("/vendor/lib.tar.gz", &tar_gz).unwrap();
+    fs.insert_archive("/vendor/lib.zip", &zip_data).unwrap();
+    assert!(fs.is_dir("/vendor/lib.tar.gz/lib/base"));
+    assert_eq!(
+        fs.read_to_string("/vendor/lib.tar.gz/lib/base/base.k")
+            .unwrap(),
+        "schema Base:\n    name: str\n"
+    );
+    assert_eq!(
+        fs.read_to_string("/vendor/lib.zip/lib/main.k").unwrap(),
+        "a = 1"
+    );
+    assert!(is_archive("lib.tgz"));
+    assert!(fs.insert_archive("/vendor/lib.rar", &zip_data).is_err());
+    assert!(fs.insert_archive("/vendor/bad.zip", b"bad").is_err());
+}
@@ -1,6 +1,7 @@
 use std::fmt;
 use std::hash::Hash;
 
+use kclvm::PanicInfo;
 use kclvm_span::Loc;
 use rustc_span::Pos;
 use termcolor::{Color, ColorSpec};
@@ -13,6 +14,9 @@ pub struct Diagnostic {
     pub level: Level,
     pub messages: Vec<Message>,
     pub code: Option<DiagnosticId>,
+    /// The panic info the diagnostic is converted from, which keeps the fields
+    /// without a diagnostic counterpart such as the package path and the Rust location.
+    pub panic_info: Option<Box<PanicInfo>>,
 }
 
 /// Position describes an arbitrary source position including the filename,
@@ -114,6 +118,7 @@ impl Diagnostic {
                 note: None,
             }],
             code,
+            panic_info: None,
         }
     }
 
@@ -10,6 +10,7 @@ pub mod bug;
 mod diagnostic;
 mod emitter;
 mod error;
+mod result;
 #[cfg(test)]
 mod tests;
 
@@ -20,6 +21,7 @@ pub use emitter::{Emitter, EmitterWriter};
 pub use error::*;
 use indexmap::IndexSet;
 use kclvm_span::SourceMap;
+pub use result::{KclvmError, KclvmResult};
 
 /// A handler deals with errors and other compiler output.
 /// Certain errors (error, bug) may cause immediate exit,
@@ -209,6 +211,14 @@ impl Handler {
         self
     }
 
+    /// Put the diagnostics of a [KclvmError] into the handler diagnostic buffer.
+    pub fn add_kclvm_error(&mut self, err: &KclvmError) -> &mut Self {
+        for diag in err.diagnostics() {
+            self.add_diagnostic(diag.clone());
+        }
+        self
+    }
+
     /// Add an error into the handler
     /// ```
     /// use kclvm_error::*;
@@ -227,6 +237,7 @@ impl Handler {
             level: Level::Error,
             messages: msgs.to_owned(),
             code: Some(DiagnosticId::Error(err)),
+            panic_info: None,
         };
         self.add_diagnostic(diag);
 
@@ -251,6 +262,7 @@ impl Handler {
             level: Level::Warning,
             messages: msgs.to_owned(),
             code: Some(DiagnosticId::Warning(warning)),
+            panic_info: None,
         };
         self.add_diagnostic(diag);
 
@@ -0,0 +1,340 @@
+//! The error type of loading, compiling and executing a KCL program.
+//!
+//! [KclvmError] tells the callers which stage failed and carries the diagnostics
+//! with the error kinds and positions, so they need not guess whether an error
+//! string is a message or a JSON encoded [PanicInfo].
+use std::any::Any;
+use std::fmt;
+
+use kclvm::{ErrType, PanicInfo};
+
+use crate::{Diagnostic, DiagnosticId, ErrorKind, Level, Message, Position, Style};
+
+/// The result of loading, compiling or executing a KCL program.
+pub type KclvmResult<T> = Result<T, KclvmError>;
+
+/// The error of loading, compiling or executing a KCL program.
+///
+/// # Examples
+///
+/// ```
+/// use kclvm_error::KclvmError;
+///
+/// let err = KclvmError::load("Cannot find the module 'app'");
+/// match &err {
+///     KclvmError::Load(diags) => assert_eq!(diags.len(), 1),
+///     _ => unreachable!(),
+/// }
+/// assert_eq!(err.to_string(), "Cannot find the module 'app'");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KclvmError {
+    /// The KCL files can not be parsed, e.g. syntax errors.
+    Parse(Vec<Diagnostic>),
+    /// The files or the packages can not be loaded, e.g. a missing file or module.
+    Load(Vec<Diagnostic>),
+    /// The program can not be resolved or compiled, e.g. type errors.
+    Compile(Vec<Diagnostic>),
+    /// The program fails when it runs, e.g. schema check failures and assertion errors.
+    Runtime(Vec<Diagnostic>),
+    /// The other errors, e.g. the toolchain or the system errors.
+    Other(Vec<Diagnostic>),
+}
+
+impl KclvmError {
+    /// A load error without a position.
+    pub fn load(msg: &str) -> Self {
+        KclvmError::Load(vec![message_diagnostic(msg)])
+    }
+
+    /// A runtime error without a position.
+    pub fn runtime(msg: &str) -> Self {
+        KclvmError::Runtime(vec![message_diagnostic(msg)])
+    }
+
+    /// An other error without a position.
+    pub fn other(msg: &str) -> Self {
+        KclvmError::Other(vec![message_diagnostic(msg)])
+    }
+
+    /// Returns the error of the panic info, the category is decided by its error type.
+    ///
+    /// The argument message of the panic info is the note of the first message, and
+    /// the config meta position such as the failed schema instance is the second message.
+    pub fn from_panic_info(panic_info: &PanicInfo) -> Self {
+        let (category, kind) = err_type_category(panic_info.err_type_code);
+        let mut messages = vec![Message {
+            pos: panic_position(
+                &panic_info.kcl_file,
+                panic_info.kcl_line,
+                panic_info.kcl_col,
+            ),
+            style: Style::LineAndColumn,
+            message: panic_info.message.clone(),
+            note: if panic_info.kcl_arg_msg.is_empty() {
+                None
+            } else {
+                Some(panic_info.kcl_arg_msg.clone())
+            },
+        }];
+        if !panic_info.kcl_config_meta_file.is_empty()
+            || !panic_info.kcl_config_meta_arg_msg.is_empty()
+        {
+            messages.push(Message {
+                pos: panic_position(
+                    &panic_info.kcl_config_meta_file,
+                    panic_info.kcl_config_meta_line,
+                    panic_info.kcl_config_meta_col,
+                ),
+                style: Style::LineAndColumn,
+                message: panic_info.kcl_config_meta_arg_msg.clone(),
+                note: None,
+            });
+        }
+        category.error(vec![Diagnostic {
+            level: if panic_info.is_warning {
+                Level::Warning
+            } else {
+                Level::Error
+            },
+            messages,
+            code: kind.map(DiagnosticId::Error),
+            panic_info: Some(Box::new(panic_info.clone())),
+        }])
+    }
+
+    /// Returns the error of the message raised by the compiler or returned by the runtime,
+    /// which is a JSON encoded [PanicInfo] or a plain message of an other error.
+    pub fn from_panic_message(msg: &str) -> Self {
+        match serde_json::from_str::<PanicInfo>(msg) {
+            Ok(panic_info) if panic_info.__kcl_PanicInfo__ => Self::from_panic_info(&panic_info),
+            _ => Self::other(msg),
+        }
+    }
+
+    /// Returns the error of the panic payload caught by [std::panic::catch_unwind].
+    pub fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+        if let Some(msg) = payload.downcast_ref::<String>() {
+            Self::from_panic_message(msg)
+        } else if let Some(msg) = payload.downcast_ref::<&str>() {
+            Self::from_panic_message(msg)
+        } else {
+            Self::other("unknown panic")
+        }
+    }
+
+    /// Returns the diagnostics of the error.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        match self {
+            KclvmError::Parse(diags)
+            | KclvmError::Load(diags)
+            | KclvmError::Compile(diags)
+            | KclvmError::Runtime(diags)
+            | KclvmError::Other(diags) => diags,
+        }
+    }
+
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        match self {
+            KclvmError::Parse(diags)
+            | KclvmError::Load(diags)
+            | KclvmError::Compile(diags)
+            | KclvmError::Runtime(diags)
+            | KclvmError::Other(diags) => diags,
+        }
+    }
+
+    /// Returns the message of the first diagnostic, or its note when the message is empty.
+    pub fn message(&self) -> String {
+        self.diagnostics()
+            .first()
+            .and_then(|diag| diag.messages.first())
+            .map(message_text)
+            .unwrap_or_default()
+    }
+
+    /// Returns the panic info of the first diagnostic, which is used by the C API and
+    /// the Python bindings. The original panic info is returned when the error is
+    /// converted from one.
+    pub fn to_panic_info(&self) -> PanicInfo {
+        let diag = self.diagnostics().first();
+        if let Some(panic_info) = diag.and_then(|diag| diag.panic_info.as_ref()) {
+            return panic_info.as_ref().clone();
+        }
+        let mut panic_info = PanicInfo {
+            __kcl_PanicInfo__: true,
+            err_type_code: self.err_type_code(diag.and_then(|diag| diag.code.as_ref())),
+            ..Default::default()
+        };
+        if let Some(diag) = diag {
+            panic_info.is_warning = diag.level == Level::Warning;
+            if let Some(msg) = diag.messages.first() {
+                panic_info.message = msg.message.clone();
+                panic_info.kcl_arg_msg = msg.note.clone().unwrap_or_default();
+                panic_info.kcl_file = msg.pos.filename.clone();
+                panic_info.kcl_line = msg.pos.line as i32;
+                panic_info.kcl_col = msg.pos.column.unwrap_or(0) as i32;
+            }
+            if let Some(msg) = diag.messages.get(1) {
+                panic_info.kcl_config_meta_arg_msg = msg.message.clone();
+                panic_info.kcl_config_meta_file = msg.pos.filename.clone();
+                panic_info.kcl_config_meta_line = msg.pos.line as i32;
+                panic_info.kcl_config_meta_col = msg.pos.column.unwrap_or(0) as i32;
+            }
+        }
+        panic_info
+    }
+
+    /// Returns the [ErrType] code of the error category and the error kind of the first
+    /// diagnostic. All the compile errors are [ErrType::CompileError_TYPE] as the
+    /// resolver reports them.
+    fn err_type_code(&self, code: Option<&DiagnosticId>) -> i32 {
+        let (category, default) = match self {
+            KclvmError::Parse(_) => (Category::Parse, ErrType::InvalidSyntax_TYPE),
+            KclvmError::Load(_) => (Category::Load, ErrType::CannotFindModule_TYPE),
+            KclvmError::Compile(_) => return ErrType::CompileError_TYPE as i32,
+            KclvmError::Runtime(_) => (Category::Runtime, ErrType::EvaluationError_TYPE),
+            KclvmError::Other(_) => return ErrType::EvaluationError_TYPE as i32,
+        };
+        match code {
+            Some(DiagnosticId::Error(kind)) => (0..=MAX_ERR_TYPE_CODE)
+                .find(|code| err_type_category(*code) == (category, Some(kind.clone())))
+                .unwrap_or(default as i32),
+            _ => default as i32,
+        }
+    }
+}
+
+impl fmt::Display for KclvmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = vec![];
+        for diag in self.diagnostics() {
+            for msg in &diag.messages {
+                let pos = &msg.pos;
+                let text = message_text(msg);
+                if pos.filename.is_empty() {
+                    lines.push(text);
+                } else if let Some(column) = pos.column {
+                    lines.push(format!(
+                        "{}:{}:{}: {}",
+                        pos.filename, pos.line, column, text
+                    ));
+                } else {
+                    lines.push(format!("{}:{}: {}", pos.filename, pos.line, text));
+                }
+            }
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl std::error::Error for KclvmError {}
+
+/// A diagnostic of the message without a position and an error kind.
+fn message_diagnostic(msg: &str) -> Diagnostic {
+    Diagnostic {
+        level: Level::Error,
+        messages: vec![Message {
+            pos: Position::default(),
+            style: Style::Empty,
+            message: msg.to_string(),
+            note: None,
+        }],
+        code: None,
+        panic_info: None,
+    }
+}
+
+/// The position of a panic info, the column 0 means no column.
+fn panic_position(filename: &str, line: i32, column: i32) -> Position {
+    Position {
+        filename: filename.to_string(),
+        line: line.max(0) as u64,
+        column: if column > 0 {
+            Some(column as u64)
+        } else {
+            None
+        },
+    }
+}
+
+/// The message text, or the note such as the assertion message when the text is empty.
+fn message_text(msg: &Message) -> String {
+    match &msg.note {
+        Some(note) if msg.message.is_empty() => note.clone(),
+        _ => msg.message.clone(),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Parse,
+    Load,
+    Compile,
+    Runtime,
+}
+
+impl Category {
+    fn error(self, diags: Vec<Diagnostic>) -> KclvmError {
+        match self {
+            Category::Parse => KclvmError::Parse(diags),
+            Category::Load => KclvmError::Load(diags),
+            Category::Compile => KclvmError::Compile(diags),
+            Category::Runtime => KclvmError::Runtime(diags),
+        }
+    }
+}
+
+/// The max [ErrType] code.
+const MAX_ERR_TYPE_CODE: i32 = 43;
+
+/// Returns the category and the error kind of the [ErrType] code, see the names
+/// of the codes in the comments.
+fn err_type_category(code: i32) -> (Category, Option<ErrorKind>) {
+    use Category::*;
+    match code {
+        0 => (Parse, Some(ErrorKind::InvalidSyntax)), // InvalidSyntax
+        1 => (Parse, Some(ErrorKind::TabError)),      // TabError
+        2 => (Parse, Some(ErrorKind::Indentation)),   // IndentationError
+        3 => (Load, Some(ErrorKind::CannotFindModule)), // CannotFindModule
+        4 => (Load, None),                            // FailedLoadModule
+        5 => (Compile, Some(ErrorKind::CompileError)), // CompileError
+        6 => (Runtime, Some(ErrorKind::EvaluationError)), // EvaluationError
+        7 => (Load, Some(ErrorKind::RecursiveLoad)),  // RecursiveLoad
+        8 => (Runtime, Some(ErrorKind::FloatOverflow)), // FloatOverflow
+        9 => (Runtime, Some(ErrorKind::FloatUnderflow)), // FloatUnderflow
+        10 => (Runtime, Some(ErrorKind::IntOverflow)), // IntOverflow
+        11 => (Compile, Some(ErrorKind::InvalidDocstring)), // InvalidDocstring
+        12 | 41 => (Compile, Some(ErrorKind::Deprecated)), // Deprecated, Deprecated_Warning
+        13 => (Compile, Some(ErrorKind::UnKnownDecorator)), // UnKnownDecorator
+        14 => (Compile, Some(ErrorKind::InvalidDecoratorTarget)), // InvalidDecoratorTarget
+        15 => (Compile, Some(ErrorKind::InvalidFormatSpec)), // InvalidFormatSpec
+        16 => (Runtime, None),                        // SelectorError
+        17 => (Runtime, Some(ErrorKind::SchemaCheckFailure)), // SchemaCheckFailure
+        18 | 19 => (Compile, None),                   // MixinNamingError, MixinStructureIllegal
+        20 => (Compile, Some(ErrorKind::IndexSignatureError)), // IndexSignatureError
+        21 => (Runtime, Some(ErrorKind::TypeError)),  // TypeError_Runtime
+        22 => (Compile, Some(ErrorKind::TypeError)),  // TypeError_Compile
+        23 => (Compile, Some(ErrorKind::NameError)),  // NameError
+        24 => (Runtime, Some(ErrorKind::ValueError)), // ValueError
+        25 => (Runtime, Some(ErrorKind::KeyError)),   // KeyError
+        26 => (Compile, Some(ErrorKind::UniqueKeyError)), // UniqueKeyError
+        27 => (Compile, Some(ErrorKind::AttributeError)), // AttributeError
+        28 => (Runtime, Some(ErrorKind::AttributeError)), // AttributeError_Runtime
+        29 => (Runtime, Some(ErrorKind::AssertionError)), // AssertionError
+        30 => (Compile, Some(ErrorKind::ImmutableError)), // ImmutableCompileError
+        31 => (Runtime, Some(ErrorKind::ImmutableError)), // ImmutableRuntimeError
+        32 => (Compile, Some(ErrorKind::MultiInheritError)), // MultiInheritError
+        33 => (Compile, Some(ErrorKind::CycleInheritError)), // CycleInheritError
+        34 => (Compile, Some(ErrorKind::IllegalInheritError)), // IllegalInheritError
+        35 => (Compile, Some(ErrorKind::IllegalAttributeError)), // IllegalAttributeError
+        36 => (Runtime, Some(ErrorKind::IllegalParameterError)), // IllegalArgumentError
+        37 => (Compile, Some(ErrorKind::IllegalParameterError)), // IllegalArgumentError_Complie
+        38 => (Parse, Some(ErrorKind::IllegalParameterError)), // IllegalArgumentError_Syntax
+        39 => (Runtime, Some(ErrorKind::RecursionError)), // RecursionError
+        40 => (Runtime, Some(ErrorKind::PlanError)),  // PlanError
+        42 => (Compile, Some(ErrorKind::CannotAddMembers)), // CannotAddMembers
+        43 => (Runtime, Some(ErrorKind::CannotAddMembers)), // CannotAddMembers_Runtime
+        _ => (Runtime, None),
+    }
+}
@@ -15,3 +15,93 @@ fn test_bug_macro() {
     });
     assert!(result.is_err());
 }
+
+#[test]
+fn test_kclvm_error_from_panic_info() {
+    let mut panic_info = kclvm::PanicInfo {
+        __kcl_PanicInfo__: true,
+        rust_file: "runtime/src/value/api.rs".to_string(),
+        rust_line: 2194,
+        rust_col: 9,
+        kcl_pkgpath: "__main__".to_string(),
+        message: "Instance check failed".to_string(),
+        err_type_code: kclvm::ErrType::SchemaCheckFailure_TYPE as i32,
+        kcl_file: "main.k".to_string(),
+        kcl_line: 3,
+        kcl_col: 5,
+        ..Default::default()
+    };
+    let err = KclvmError::from_panic_message(&panic_info.to_json_string());
+    match &err {
+        KclvmError::Runtime(diags) => {
+            assert_eq!(
+                diags[0].code,
+                Some(DiagnosticId::Error(ErrorKind::SchemaCheckFailure))
+            );
+            assert_eq!(diags[0].messages[0].pos.line, 3);
+            assert_eq!(diags[0].messages[0].pos.column, Some(5));
+        }
+        _ => panic!("unexpected error {:?}", err),
+    }
+    assert_eq!(err.to_string(), "main.k:3:5: Instance check failed");
+    assert_eq!(err.message(), "Instance check failed");
+    // The package path and the Rust location are kept.
+    assert_eq!(err.to_panic_info(), panic_info);
+    // The panic info of an error not converted from one.
+    let err = KclvmError::Runtime(vec![Diagnostic::new_with_code(
+        Level::Error,
+        "Instance check failed",
+        Position {
+            filename: "main.k".to_string(),
+            line: 3,
+            column: Some(5),
+        },
+        Some(DiagnosticId::Error(ErrorKind::SchemaCheckFailure)),
+    )]);
+    let built = err.to_panic_info();
+    assert!(built.__kcl_PanicInfo__);
+    assert_eq!(built.kcl_file, "main.k");
+    assert_eq!(built.kcl_line, 3);
+    assert_eq!(built.kcl_col, 5);
+    assert_eq!(built.message, "Instance check failed");
+    assert_eq!(
+        built.err_type_code,
+        kclvm::ErrType::SchemaCheckFailure_TYPE as i32
+    );
+
+    // The argument message and the config meta are kept.
+    let mut check_info = panic_info.clone();
+    check_info.message = "".to_string();
+    check_info.kcl_arg_msg = "Check failed on the condition".to_string();
+    check_info.kcl_config_meta_line = 1;
+    check_info.kcl_config_meta_col = 1;
+    check_info.kcl_config_meta_arg_msg = "Instance check failed".to_string();
+    let err = KclvmError::from_panic_info(&check_info);
+    assert_eq!(err.diagnostics()[0].messages.len(), 2);
+    assert_eq!(err.message(), "Check failed on the condition");
+    assert_eq!(err.to_panic_info(), check_info);
+
+    panic_info.err_type_code = kclvm::ErrType::TypeError_Compile_TYPE as i32;
+    assert!(matches!(
+        KclvmError::from_panic_info(&panic_info),
+        KclvmError::Compile(_)
+    ));
+}
+
+#[test]
+fn test_kclvm_error_from_panic() {
+    // A plain message is not guessed as a panic info and does not panic.
+    let err = KclvmError::from_panic_message("{ not json");
+    assert!(matches!(&err, KclvmError::Other(_)));
+    assert_eq!(err.to_string(), "{ not json");
+    let result = std::panic::catch_unwind(|| {
+        bug!("an error msg");
+    });
+    let err = KclvmError::from_panic(result.unwrap_err());
+    assert!(matches!(&err, KclvmError::Other(_)));
+    assert!(err.message().contains("an error msg"));
+
+    let mut handler = Handler::default();
+    handler.add_kclvm_error(&KclvmError::load("Cannot find the module"));
+    assert!(handler.has_errors());
+}
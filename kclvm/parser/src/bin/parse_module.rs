@@ -2,7 +2,7 @@ extern crate kclvm_parser;
 
 fn main() {
     let filename = std::env::args().nth(1).expect("filename missing");
-    let m = kclvm_parser::parse_program(filename.as_str());
+    let m = kclvm_parser::parse_program(filename.as_str()).map_err(|err| err.to_string());
     let json = serde_json::ser::to_string(&m).unwrap();
     println!("{}", json);
 }
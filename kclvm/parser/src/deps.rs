@@ -0,0 +1,202 @@
+//! The import dependency graph of a KCL program.
+//!
+//! The graph is built from the loaded [ast::Program], the nodes are the user packages
+//! with their files and each edge is an import statement with its position. The
+//! standard system modules and the plugin modules are not in the graph.
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use kclvm_ast::ast;
+use kclvm_error::KclvmResult;
+
+use crate::{load_program, LoadProgramOptions};
+
+/// An import of the package `to` in the file `filename` of the package `from`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DepEdge {
+    pub from: String,
+    pub to: String,
+    pub filename: String,
+    pub line: u64,
+    pub column: u64,
+}
+
+/// The package dependency graph of a program.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kclvm_parser::deps::load_dep_graph;
+///
+/// let graph = load_dep_graph(&["main.k"], None).unwrap();
+/// for file in graph.dep_files("__main__") {
+///     println!("{}", file);
+/// }
+/// for cycle in graph.cycles() {
+///     println!("{}", cycle.join(" -> "));
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DepGraph {
+    /// The files of every package, sorted by the package path.
+    pub pkgs: BTreeMap<String, Vec<String>>,
+    /// The import edges sorted by the importing package and position.
+    pub edges: Vec<DepEdge>,
+}
+
+/// Load the program and returns its dependency graph.
+pub fn load_dep_graph(paths: &[&str], opts: Option<LoadProgramOptions>) -> KclvmResult<DepGraph> {
+    Ok(DepGraph::from_program(&load_program(paths, opts)?))
+}
+
+impl DepGraph {
+    /// Build the dependency graph of a loaded program, the import paths in the program
+    /// have been resolved to the absolute package paths by the loader.
+    pub fn from_program(program: &ast::Program) -> Self {
+        let mut graph = DepGraph::default();
+        for (pkgpath, modules) in &program.pkgs {
+            graph.pkgs.insert(
+                pkgpath.clone(),
+                modules.iter().map(|m| m.filename.clone()).collect(),
+            );
+            for m in modules {
+                for stmt in &m.body {
+                    if let ast::Stmt::Import(import_stmt) = &stmt.node {
+                        if program.pkgs.contains_key(&import_stmt.path) {
+                            graph.edges.push(DepEdge {
+                                from: pkgpath.clone(),
+                                to: import_stmt.path.clone(),
+                                filename: m.filename.clone(),
+                                line: stmt.line,
+                                column: stmt.column,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        graph.edges.sort();
+        graph
+    }
+
+    /// Returns the packages imported by the package directly.
+    pub fn imports(&self, pkgpath: &str) -> BTreeSet<&str> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.from == pkgpath)
+            .map(|edge| edge.to.as_str())
+            .collect()
+    }
+
+    /// Returns the import statements of the package in the other packages.
+    pub fn importers(&self, pkgpath: &str) -> Vec<&DepEdge> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.to == pkgpath && edge.from != pkgpath)
+            .collect()
+    }
+
+    /// Returns all the packages the package depends on transitively, sorted and not
+    /// including the package itself.
+    pub fn dependencies(&self, pkgpath: &str) -> Vec<String> {
+        self.walk(pkgpath, |pkgpath| self.imports(pkgpath))
+    }
+
+    /// Returns all the packages depending on the package transitively, sorted and not
+    /// including the package itself.
+    pub fn dependents(&self, pkgpath: &str) -> Vec<String> {
+        self.walk(pkgpath, |pkgpath| {
+            self.importers(pkgpath)
+                .into_iter()
+                .map(|edge| edge.from.as_str())
+                .collect()
+        })
+    }
+
+    /// Returns the files of the package and all its dependencies.
+    pub fn dep_files(&self, pkgpath: &str) -> Vec<String> {
+        let mut files = vec![];
+        for pkgpath in std::iter::once(pkgpath.to_string()).chain(self.dependencies(pkgpath)) {
+            if let Some(pkg_files) = self.pkgs.get(&pkgpath) {
+                files.extend(pkg_files.iter().cloned());
+            }
+        }
+        files
+    }
+
+    fn walk<'a, F>(&'a self, pkgpath: &str, next: F) -> Vec<String>
+    where
+        F: Fn(&str) -> BTreeSet<&'a str>,
+    {
+        let mut visited = BTreeSet::new();
+        let mut queue = VecDeque::from([pkgpath.to_string()]);
+        while let Some(current) = queue.pop_front() {
+            for pkgpath in next(&current) {
+                if visited.insert(pkgpath.to_string()) {
+                    queue.push_back(pkgpath.to_string());
+                }
+            }
+        }
+        visited.remove(pkgpath);
+        visited.into_iter().collect()
+    }
+
+    /// Returns the elementary import cycles, every cycle is the full package path which
+    /// starts and ends with the same package, e.g. `["a", "b", "a"]`. A cycle is reported
+    /// once starting with its smallest package.
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let mut cycles = BTreeSet::new();
+        for pkgpath in self.pkgs.keys() {
+            let mut stack = vec![pkgpath.as_str()];
+            self.find_cycles(pkgpath, &mut stack, &mut cycles);
+        }
+        cycles.into_iter().collect()
+    }
+
+    /// Finds the cycles through `start` whose other packages are all greater than it, so
+    /// every cycle is only found from its smallest package. Only the packages on the current
+    /// path are skipped, the other paths through a visited package are different cycles.
+    fn find_cycles<'a>(
+        &'a self,
+        start: &'a str,
+        stack: &mut Vec<&'a str>,
+        cycles: &mut BTreeSet<Vec<String>>,
+    ) {
+        let pkgpath = stack[stack.len() - 1];
+        for import in self.imports(pkgpath) {
+            if import == start {
+                let mut path: Vec<String> = stack.iter().map(|p| p.to_string()).collect();
+                path.push(start.to_string());
+                cycles.insert(path);
+            } else if import > start && !stack.contains(&import) {
+                stack.push(import);
+                self.find_cycles(start, stack, cycles);
+                stack.pop();
+            }
+        }
+    }
+
+    /// Returns the graph in the DOT format, every edge is labeled with the import positions.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph deps {\n");
+        for pkgpath in self.pkgs.keys() {
+            dot.push_str(&format!("    {:?};\n", pkgpath));
+        }
+        let mut labels: BTreeMap<(&str, &str), Vec<String>> = BTreeMap::new();
+        for edge in &self.edges {
+            labels
+                .entry((&edge.from, &edge.to))
+                .or_default()
+                .push(format!("{}:{}:{}", edge.filename, edge.line, edge.column));
+        }
+        for ((from, to), positions) in labels {
+            dot.push_str(&format!(
+                "    {:?} -> {:?} [label={:?}];\n",
+                from,
+                to,
+                positions.join("\n")
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -1,5 +1,6 @@
 // Copyright 2021 The KCL Authors. All rights reserved.
 
+pub mod deps;
 mod lexer;
 mod parser;
 mod session;
@@ -10,9 +11,10 @@ mod tests;
 extern crate kclvm_error;
 
 use crate::session::ParseSession;
-use kclvm::{ErrType, PanicInfo};
 use kclvm_ast::ast;
-use kclvm_span::{self, FilePathMapping, SourceMap};
+pub use kclvm_config::vfs::{DiskFileSystem, FileSystem, MemoryFileSystem, OverlayFileSystem};
+use kclvm_error::{KclvmError, KclvmResult};
+use kclvm_span::{self, FilePathMapping, ProgramSourceMap, SourceMap};
 
 use lexer::parse_token_streams;
 use parser::Parser;
@@ -23,10 +25,15 @@ use std::error::Error;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::PathBuf;
+use std::sync::mpsc::channel;
 use std::sync::Arc;
+use threadpool::ThreadPool;
 
 use kclvm_span::create_session_globals_then;
 
+/// The parsing thread count when the available parallelism is unknown.
+const DEFAULT_PARSE_THREAD_COUNT: usize = 4;
+
 /// parser mode
 #[derive(Debug, Clone)]
 pub enum ParseMode {
@@ -42,7 +49,7 @@ pub fn parse_program_from_json_file(path: &str) -> Result<ast::Program, Box<dyn
     Ok(u)
 }
 
-pub fn parse_program(filename: &str) -> Result<ast::Program, String> {
+pub fn parse_program(filename: &str) -> KclvmResult<ast::Program> {
     let abspath = std::fs::canonicalize(&std::path::PathBuf::from(filename)).unwrap();
 
     let mut prog = ast::Program {
@@ -51,6 +58,7 @@ pub fn parse_program(filename: &str) -> Result<ast::Program, String> {
         pkgs: std::collections::HashMap::new(),
         cmd_args: Vec::new(),
         cmd_overrides: Vec::new(),
+        source_map: Default::default(),
     };
 
     let mainpkg = "__main__";
@@ -65,28 +73,39 @@ pub fn parse_program(filename: &str) -> Result<ast::Program, String> {
     Ok(prog)
 }
 
-pub fn parse_file(filename: &str, code: Option<String>) -> Result<ast::Module, String> {
-    create_session_globals_then(move || {
-        let src = if let Some(s) = code {
-            s
-        } else {
-            match std::fs::read_to_string(filename) {
-                Ok(src) => src,
-                Err(_err) => {
-                    let err_msg =
-                        format!("Failed to load KCL file '{}'. Because '{}'", filename, _err);
-                    return Err(err_msg);
-                }
+/// Parse a KCL file, the syntax errors are returned as [KclvmError::Parse].
+pub fn parse_file(filename: &str, code: Option<String>) -> KclvmResult<ast::Module> {
+    let src = if let Some(s) = code {
+        s
+    } else {
+        match std::fs::read_to_string(filename) {
+            Ok(src) => src,
+            Err(_err) => {
+                let err_msg = format!("Failed to load KCL file '{}'. Because '{}'", filename, _err);
+                return Err(KclvmError::load(&err_msg));
             }
-        };
+        }
+    };
+    parse_source(filename, src, BytePos::from_u32(0))
+}
 
+/// Parse the source of a KCL file which starts at `start_pos`, the start position of the
+/// file in the [ProgramSourceMap] when it is loaded in a program, so the positions in the
+/// parse session are the same as the ones in the program source map.
+fn parse_source(filename: &str, src: String, start_pos: BytePos) -> KclvmResult<ast::Module> {
+    create_session_globals_then(move || {
         let sm = kclvm_span::SourceMap::new(FilePathMapping::empty());
+        sm.reserve_address_space(start_pos);
         let sf = sm.new_source_file(PathBuf::from(filename).into(), src.to_string());
         let sess = &ParseSession::with_source_map(std::sync::Arc::new(sm));
 
-        let stream = lexer::parse_token_streams(sess, src.as_str(), sf.start_pos);
-        let mut p = parser::Parser::new(sess, stream);
-        let mut m = p.parse_module();
+        // The syntax errors are raised as panics with the panic info.
+        let mut m = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let stream = lexer::parse_token_streams(sess, src.as_str(), sf.start_pos);
+            let mut p = parser::Parser::new(sess, stream);
+            p.parse_module()
+        }))
+        .map_err(|err| KclvmError::Parse(KclvmError::from_panic(err).into_diagnostics()))?;
 
         m.filename = filename.to_string();
         m.pkg = kclvm_ast::MAIN_PKG.to_string();
@@ -129,6 +148,14 @@ pub fn parse_expr(src: &str) -> Option<ast::NodeRef<ast::Expr>> {
 pub struct LoadProgramOptions {
     pub work_dir: String,
     pub k_code_list: Vec<String>,
+    /// The file system to load the KCL files and packages from, defaults to [DiskFileSystem].
+    /// Use [MemoryFileSystem] or [OverlayFileSystem] to load unsaved or generated files
+    /// anywhere in the import graph.
+    pub fs: Option<Arc<dyn FileSystem>>,
+    /// The package directories or `.zip`/`.tar.gz` package archives to search the imported
+    /// packages in after the package root, relative to the work directory. They take precedence
+    /// over the `deps.paths` in kcl.mod.
+    pub package_paths: Vec<String>,
 
     pub cmd_args: Vec<ast::CmdArgSpec>,
     pub cmd_overrides: Vec<ast::OverrideSpec>,
@@ -137,10 +164,8 @@ pub struct LoadProgramOptions {
     pub _load_packages: bool,
 }
 
-pub fn load_program(
-    paths: &[&str],
-    opts: Option<LoadProgramOptions>,
-) -> Result<ast::Program, String> {
+/// Load the program of the KCL files and all the packages they import transitively.
+pub fn load_program(paths: &[&str], opts: Option<LoadProgramOptions>) -> KclvmResult<ast::Program> {
     // todo: support cache
     if let Some(opts) = opts {
         Loader::new(paths, Some(opts)).load_main()
@@ -152,48 +177,55 @@ pub fn load_program(
 struct Loader {
     paths: Vec<String>,
     opts: LoadProgramOptions,
+    fs: Arc<dyn FileSystem>,
 
     pkgroot: String,
 
     modfile: kclvm_config::modfile::KCLModFile,
     pkgs: std::collections::HashMap<String, Vec<ast::Module>>,
     missing_pkgs: Vec<String>,
-    // todo: add shared source_map all parse_file.
+    source_map: ProgramSourceMap,
+    /// The package directories and archives to search the imported packages in.
+    search_roots: Vec<String>,
 }
 
 impl Loader {
     fn new(paths: &[&str], opts: Option<LoadProgramOptions>) -> Self {
+        let opts = opts.unwrap_or_default();
         Self {
             paths: paths.iter().map(|s| s.to_string()).collect(),
-            opts: opts.unwrap_or_default(),
+            fs: opts.fs.clone().unwrap_or_else(|| Arc::new(DiskFileSystem)),
+            opts,
 
             pkgroot: "".to_string(),
 
             modfile: Default::default(),
             pkgs: Default::default(),
             missing_pkgs: Default::default(),
+            source_map: Default::default(),
+            search_roots: Default::default(),
         }
     }
 
-    fn load_main(&mut self) -> Result<ast::Program, String> {
-        match self._load_main() {
-            Ok(x) => Ok(x),
-            Err(s) => Err(self.str_to_panic_info(&s).to_json_string()),
-        }
-    }
-
-    fn _load_main(&mut self) -> Result<ast::Program, String> {
+    fn load_main(&mut self) -> KclvmResult<ast::Program> {
         debug_assert!(!self.paths.is_empty());
 
-        self.pkgroot = kclvm_config::modfile::get_pkg_root_from_paths(&self.paths)?;
+        self.pkgroot =
+            kclvm_config::modfile::get_pkg_root_from_paths_with_fs(self.fs.as_ref(), &self.paths)
+                .map_err(|err| KclvmError::load(&err))?;
 
         if !self.pkgroot.is_empty() {
             debug_assert!(self.is_dir(self.pkgroot.as_str()));
             debug_assert!(self.path_exist(self.pkgroot.as_str()));
 
-            self.modfile = kclvm_config::modfile::load_mod_file(self.pkgroot.as_str());
+            self.modfile = kclvm_config::modfile::load_mod_file_with_fs(
+                self.fs.as_ref(),
+                self.pkgroot.as_str(),
+            );
         }
 
+        self.init_search_roots()?;
+
         // fix path
         let mut path_list = Vec::new();
         for s in &self.paths {
@@ -206,9 +238,8 @@ impl Loader {
                 );
             }
             if !self.pkgroot.is_empty() && !self.is_absolute(s.as_str()) {
-                let p = std::path::Path::new(s.as_str());
-                if let Ok(x) = std::fs::canonicalize(p) {
-                    s = x.to_str().unwrap_or(s.as_str()).to_string();
+                if let Ok(x) = self.fs.canonicalize(s.as_str()) {
+                    s = x;
                 }
             }
 
@@ -226,7 +257,7 @@ impl Loader {
             // read dir/*.k
             if self.is_dir(path) {
                 if self.opts.k_code_list.len() > i {
-                    return Err("invalid code list".to_string());
+                    return Err(KclvmError::load("invalid code list"));
                 }
                 //k_code_list
                 for s in self.get_dir_kfile_list(path)? {
@@ -237,7 +268,7 @@ impl Loader {
         }
 
         if k_files.is_empty() {
-            return Err("No input KCL files".to_string());
+            return Err(KclvmError::load("No input KCL files"));
         }
 
         // check all file exists
@@ -252,27 +283,26 @@ impl Loader {
             }
 
             if !self.path_exist(filename.as_str()) {
-                return Err(format!(
+                return Err(KclvmError::load(&format!(
                     "Cannot find the kcl file, please check whether the file path {}",
                     filename.as_str(),
-                ));
+                )));
             }
         }
 
         // load module
-        let mut pkg_files = Vec::new();
-        for (i, filename) in (&k_files).iter().enumerate() {
-            // todo: add shared source map for all files
+        let mut sources = Vec::new();
+        for (i, filename) in k_files.iter().enumerate() {
             if i < self.opts.k_code_list.len() {
-                let mut m = parse_file(filename, Some(self.opts.k_code_list[i].clone()))?;
-                self.fix_rel_import_path(&mut m);
-                pkg_files.push(m)
+                sources.push((filename.clone(), self.opts.k_code_list[i].clone()));
             } else {
-                let mut m = parse_file(filename, None)?;
-                self.fix_rel_import_path(&mut m);
-                pkg_files.push(m);
+                sources.push((filename.clone(), self.read_file(filename)?));
             }
         }
+        let mut pkg_files = self.parse_files(sources)?;
+        for m in &mut pkg_files {
+            self.fix_rel_import_path(m);
+        }
 
         let __kcl_main__ = kclvm_ast::MAIN_PKG;
         let import_list = self.get_import_list(&pkg_files);
@@ -280,9 +310,12 @@ impl Loader {
         self.pkgs.insert(__kcl_main__.to_string(), pkg_files);
 
         // load imported packages
-        for import_spec in import_list {
-            self.load_package(import_spec.path.to_string())?;
-        }
+        self.load_packages(
+            import_list
+                .into_iter()
+                .map(|import_spec| import_spec.path)
+                .collect(),
+        )?;
 
         // Ok
         Ok(ast::Program {
@@ -291,14 +324,127 @@ impl Loader {
             pkgs: self.pkgs.clone(),
             cmd_args: Vec::new(),
             cmd_overrides: Vec::new(),
+            source_map: self.source_map.clone(),
         })
     }
 
+    /// Add the sources to the program source map in order and parse them in parallel, returns
+    /// the modules in the same order as the sources.
+    fn parse_files(&self, sources: Vec<(String, String)>) -> KclvmResult<Vec<ast::Module>> {
+        let sources: Vec<(String, String, BytePos)> = sources
+            .into_iter()
+            .map(|(filename, code)| {
+                let id = self.source_map.add_file(&filename, code.clone());
+                let start_pos = self.source_map.get_file(id).unwrap().start_pos;
+                (filename, code, start_pos)
+            })
+            .collect();
+        if sources.len() <= 1 {
+            return sources
+                .into_iter()
+                .map(|(filename, code, start_pos)| parse_source(&filename, code, start_pos))
+                .collect();
+        }
+
+        let thread_count = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(DEFAULT_PARSE_THREAD_COUNT)
+            .min(sources.len());
+        let pool = ThreadPool::new(thread_count);
+        let (tx, rx) = channel();
+        let file_count = sources.len();
+        for (i, (filename, code, start_pos)) in sources.into_iter().enumerate() {
+            let tx = tx.clone();
+            pool.execute(move || {
+                // Catch the unexpected panics and resume them in the loading thread.
+                let result =
+                    std::panic::catch_unwind(move || parse_source(&filename, code, start_pos));
+                tx.send((i, result))
+                    .expect("channel will be there waiting for the pool");
+            });
+        }
+        let mut results: Vec<_> = rx.iter().take(file_count).collect();
+        results.sort_by_key(|(i, _)| *i);
+        let mut modules = Vec::with_capacity(file_count);
+        for (_, result) in results {
+            match result {
+                Ok(m) => modules.push(m?),
+                Err(err) => std::panic::resume_unwind(err),
+            }
+        }
+        Ok(modules)
+    }
+
+    /// Mount the package archives and collect the search roots of the imported packages.
+    fn init_search_roots(&mut self) -> KclvmResult<()> {
+        let mut paths = Vec::new();
+        for path in &self.opts.package_paths {
+            paths.push(
+                std::path::Path::new(&self.opts.work_dir)
+                    .join(path)
+                    .to_str()
+                    .unwrap()
+                    .to_string(),
+            );
+        }
+        if let Some(deps) = &self.modfile.deps {
+            for path in deps.paths.iter().flatten() {
+                paths.push(
+                    std::path::Path::new(&self.pkgroot)
+                        .join(path)
+                        .to_str()
+                        .unwrap()
+                        .to_string(),
+                );
+            }
+        }
+
+        let archives = MemoryFileSystem::default();
+        let mut has_archive = false;
+        for path in paths {
+            let path = self.fs.canonicalize(&path).map_err(|err| {
+                KclvmError::load(&format!("Cannot find the package path {}: {}", path, err))
+            })?;
+            if kclvm_config::vfs::is_archive(&path) {
+                let data = self
+                    .fs
+                    .read(&path)
+                    .and_then(|data| archives.insert_archive(&path, &data));
+                if let Err(err) = data {
+                    return Err(KclvmError::load(&format!(
+                        "Failed to load the package archive {}: {}",
+                        path, err
+                    )));
+                }
+                has_archive = true;
+            }
+            if !self.search_roots.contains(&path) {
+                self.search_roots.push(path);
+            }
+        }
+        // The files in the archives are read as if the archives were directories.
+        if has_archive {
+            self.fs = Arc::new(OverlayFileSystem::new(Arc::new(archives), self.fs.clone()));
+        }
+        Ok(())
+    }
+
+    /// Returns the root of the file to fix the relative import paths, which is the search root
+    /// for the files of the packages in the package paths.
+    fn get_file_root(&self, filename: &str) -> String {
+        self.search_roots
+            .iter()
+            .find(|root| std::path::Path::new(filename).starts_with(root))
+            .unwrap_or(&self.pkgroot)
+            .clone()
+    }
+
     fn fix_rel_import_path(&mut self, m: &mut ast::Module) {
+        let root = self.get_file_root(&m.filename);
         for stmt in &mut m.body {
             if let ast::Stmt::Import(ref mut import_spec) = &mut stmt.node {
                 import_spec.path = kclvm_config::vfs::fix_import_path(
-                    &self.pkgroot,
+                    &root,
                     &m.filename,
                     import_spec.path.as_str(),
                 );
@@ -306,67 +452,83 @@ impl Loader {
         }
     }
 
-    fn load_package(&mut self, pkgpath: String) -> Result<(), String> {
-        if pkgpath.is_empty() {
-            return Ok(());
-        }
-
-        if self.pkgs.contains_key(&pkgpath) {
-            return Ok(());
-        }
-        if self.missing_pkgs.contains(&pkgpath) {
-            return Ok(());
-        }
-
-        // plugin pkgs
-        if self.is_plugin_pkg(pkgpath.as_str()) {
-            return Ok(());
-        }
+    /// Load the imported packages level by level, the files of all the packages in the same
+    /// level are parsed in parallel.
+    fn load_packages(&mut self, mut pkgpaths: Vec<String>) -> KclvmResult<()> {
+        while !pkgpaths.is_empty() {
+            let mut level_pkgpaths: Vec<String> = Vec::new();
+            let mut sources = Vec::new();
+            let mut source_pkgpaths = Vec::new();
+            for pkgpath in pkgpaths {
+                if pkgpath.is_empty()
+                    || self.pkgs.contains_key(&pkgpath)
+                    || self.missing_pkgs.contains(&pkgpath)
+                    || level_pkgpaths.contains(&pkgpath)
+                {
+                    continue;
+                }
 
-        // builtin pkgs
-        if self.is_builtin_pkg(pkgpath.as_str()) {
-            return Ok(());
-        }
+                // plugin pkgs
+                if self.is_plugin_pkg(pkgpath.as_str()) {
+                    continue;
+                }
 
-        let k_files = self.get_pkg_kfile_list(pkgpath.as_str())?;
+                // builtin pkgs
+                if self.is_builtin_pkg(pkgpath.as_str()) {
+                    continue;
+                }
 
-        if k_files.is_empty() {
-            self.missing_pkgs.push(pkgpath);
-            return Ok(());
-        }
+                let k_files = self.get_pkg_kfile_list(pkgpath.as_str())?;
 
-        let mut pkg_files = Vec::new();
-        for filename in k_files {
-            debug_assert!(self.is_file(filename.as_str()));
-            debug_assert!(self.path_exist(filename.as_str()));
+                if k_files.is_empty() {
+                    self.missing_pkgs.push(pkgpath);
+                    continue;
+                }
 
-            let mut m = parse_file(filename.as_str(), None)?;
+                for filename in k_files {
+                    debug_assert!(self.is_file(filename.as_str()));
+                    debug_assert!(self.path_exist(filename.as_str()));
 
-            m.pkg = pkgpath.clone();
-            m.name = "".to_string();
-            self.fix_rel_import_path(&mut m);
+                    let code = self.read_file(filename.as_str())?;
+                    sources.push((filename, code));
+                    source_pkgpaths.push(pkgpath.clone());
+                }
+                level_pkgpaths.push(pkgpath);
+            }
 
-            pkg_files.push(m);
-        }
+            let mut level_pkgs: std::collections::HashMap<String, Vec<ast::Module>> =
+                Default::default();
+            let modules = self.parse_files(sources)?;
+            for (mut m, pkgpath) in modules.into_iter().zip(source_pkgpaths) {
+                m.pkg = pkgpath.clone();
+                m.name = "".to_string();
+                self.fix_rel_import_path(&mut m);
 
-        let import_list = self.get_import_list(&pkg_files);
-        self.pkgs.insert(pkgpath, pkg_files);
+                level_pkgs.entry(pkgpath).or_default().push(m);
+            }
 
-        for import_spec in import_list {
-            self.load_package(import_spec.path.to_string())?;
+            pkgpaths = Vec::new();
+            for pkgpath in level_pkgpaths {
+                let pkg_files = level_pkgs.remove(&pkgpath).unwrap_or_default();
+                for import_spec in self.get_import_list(&pkg_files) {
+                    pkgpaths.push(import_spec.path);
+                }
+                self.pkgs.insert(pkgpath, pkg_files);
+            }
         }
 
-        return Ok(());
+        Ok(())
     }
 
     fn get_import_list(&self, pkg: &[ast::Module]) -> Vec<ast::ImportStmt> {
         let mut import_list = Vec::new();
         for m in pkg {
+            let root = self.get_file_root(&m.filename);
             for stmt in &m.body {
                 if let ast::Stmt::Import(import_spec) = &stmt.node {
                     let mut import_spec = import_spec.clone();
                     import_spec.path = kclvm_config::vfs::fix_import_path(
-                        &self.pkgroot,
+                        &root,
                         &m.filename,
                         import_spec.path.as_str(),
                     );
@@ -377,7 +539,7 @@ impl Loader {
         import_list
     }
 
-    fn get_pkg_kfile_list(&self, pkgpath: &str) -> Result<Vec<String>, String> {
+    fn get_pkg_kfile_list(&self, pkgpath: &str) -> KclvmResult<Vec<String>> {
         debug_assert!(!pkgpath.is_empty());
 
         // plugin pkgs
@@ -390,55 +552,71 @@ impl Loader {
             return Ok(Vec::new());
         }
 
-        if self.pkgroot.is_empty() {
-            return Err("pkgroot not found".to_string());
+        if self.pkgroot.is_empty() && self.search_roots.is_empty() {
+            return Err(KclvmError::load("pkgroot not found"));
+        }
+
+        // Search the package root first and then the package paths.
+        let roots = std::iter::once(&self.pkgroot).chain(self.search_roots.iter());
+        for root in roots.filter(|root| !root.is_empty()) {
+            let k_files = self.get_root_pkg_kfile_list(root, pkgpath)?;
+            if !k_files.is_empty() {
+                return Ok(k_files);
+            }
         }
 
+        Ok(Vec::new())
+    }
+
+    fn get_root_pkg_kfile_list(&self, root: &str, pkgpath: &str) -> KclvmResult<Vec<String>> {
         let mut pathbuf = std::path::PathBuf::new();
-        pathbuf.push(&self.pkgroot);
+        pathbuf.push(root);
         for s in pkgpath.split('.') {
             pathbuf.push(s);
         }
 
-        let pkgpath: String = pathbuf.as_path().to_str().unwrap().to_string();
-        let abspath: String = std::path::Path::new(&self.pkgroot)
-            .join(pkgpath)
-            .to_str()
-            .unwrap()
-            .to_string();
+        let abspath: String = pathbuf.as_path().to_str().unwrap().to_string();
 
-        if std::path::Path::new(abspath.as_str()).exists() {
+        if self.path_exist(abspath.as_str()) {
             return self.get_dir_kfile_list(abspath.as_str());
         }
 
         let as_k_path = abspath + ".k";
-        if std::path::Path::new((&as_k_path).as_str()).exists() {
+        if self.path_exist(as_k_path.as_str()) {
             return Ok(vec![as_k_path]);
         }
 
         Ok(Vec::new())
     }
-    fn get_dir_kfile_list(&self, dir: &str) -> Result<Vec<String>, String> {
-        if !std::path::Path::new(dir).exists() {
+
+    fn get_dir_kfile_list(&self, dir: &str) -> KclvmResult<Vec<String>> {
+        if !self.path_exist(dir) {
             return Ok(Vec::new());
         }
 
         let mut list = Vec::new();
 
-        for path in std::fs::read_dir(dir).unwrap() {
-            let path = path.unwrap();
-            if !path.file_name().to_str().unwrap().ends_with(".k") {
+        for path in self
+            .fs
+            .read_dir(dir)
+            .map_err(|err| KclvmError::load(&err.to_string()))?
+        {
+            let name = std::path::Path::new(&path)
+                .file_name()
+                .unwrap()
+                .to_str()
+                .unwrap();
+            if !name.ends_with(".k") {
                 continue;
             }
-            if path.file_name().to_str().unwrap().ends_with("_test.k") {
+            if name.ends_with("_test.k") {
                 continue;
             }
-            if path.file_name().to_str().unwrap().starts_with('_') {
+            if name.starts_with('_') {
                 continue;
             }
 
-            let s = format!("{}", path.path().display());
-            list.push(s);
+            list.push(path);
         }
 
         list.sort();
@@ -458,10 +636,10 @@ impl Loader {
 // utils
 impl Loader {
     fn is_file(&self, path: &str) -> bool {
-        std::path::Path::new(path).is_file()
+        self.fs.is_file(path)
     }
     fn is_dir(&self, path: &str) -> bool {
-        std::path::Path::new(path).is_dir()
+        self.fs.is_dir(path)
     }
 
     fn is_absolute(&self, path: &str) -> bool {
@@ -469,18 +647,15 @@ impl Loader {
     }
 
     fn path_exist(&self, path: &str) -> bool {
-        std::path::Path::new(path).exists()
+        self.fs.exists(path)
     }
-}
 
-impl Loader {
-    fn str_to_panic_info(&self, s: &str) -> PanicInfo {
-        let mut panic_info = PanicInfo::default();
-
-        panic_info.__kcl_PanicInfo__ = true;
-        panic_info.message = format!("{}", s);
-        panic_info.err_type_code = ErrType::CompileError_TYPE as i32;
-
-        panic_info
+    fn read_file(&self, filename: &str) -> KclvmResult<String> {
+        self.fs.read_to_string(filename).map_err(|err| {
+            KclvmError::load(&format!(
+                "Failed to load KCL file '{}'. Because '{}'",
+                filename, err
+            ))
+        })
     }
 }
@@ -1274,8 +1274,9 @@ fn test_parse_file_not_found() {
         Ok(_) => {
             panic!("unreachable")
         }
-        Err(err_msg) => {
-            assert_eq!(err_msg, "Failed to load KCL file 'The file path is invalid'. Because 'No such file or directory (os error 2)'");
+        Err(err) => {
+            assert!(matches!(err, kclvm_error::KclvmError::Load(_)));
+            assert_eq!(err.to_string(), "Failed to load KCL file 'The file path is invalid'. Because 'No such file or directory (os error 2)'");
         }
     }
 }
@@ -1,6 +1,7 @@
 use std::panic::{catch_unwind, set_hook};
 
 use crate::*;
+use kclvm::PanicInfo;
 
 use core::any::Any;
 use expect_test::{expect, Expect};
@@ -95,3 +96,205 @@ pub fn test_parse_expr_invalid() {
         check_result_panic_info(result);
     }
 }
+
+#[test]
+fn test_load_program_with_memory_fs() {
+    let fs = MemoryFileSystem::default();
+    fs.insert("/konfig/kcl.mod", "");
+    fs.insert("/konfig/app/main.k", "import base\nimport .sub\na = base.b");
+    fs.insert("/konfig/app/sub/sub.k", "s = 1");
+    fs.insert("/konfig/base/base.k", "b = 1");
+    fs.insert("/konfig/base/base_test.k", "t = 1");
+    let opts = LoadProgramOptions {
+        fs: Some(Arc::new(fs)),
+        ..Default::default()
+    };
+    let program = load_program(&["/konfig/app/main.k"], Some(opts)).unwrap();
+    assert_eq!(program.root, "/konfig");
+    let mut pkgs: Vec<&String> = program.pkgs.keys().collect();
+    pkgs.sort();
+    assert_eq!(pkgs, vec!["__main__", "app.sub", "base"]);
+    assert_eq!(program.pkgs["base"].len(), 1);
+    assert_eq!(program.pkgs["base"][0].filename, "/konfig/base/base.k");
+}
+
+#[test]
+fn test_load_program_with_overlay_fs() {
+    let upper = MemoryFileSystem::default();
+    let main_file = std::env::current_dir()
+        .unwrap()
+        .join("testdata")
+        .join("overlay_main.k");
+    let main_file = main_file.to_str().unwrap();
+    upper.insert(main_file, "a = 1");
+    let opts = LoadProgramOptions {
+        fs: Some(Arc::new(OverlayFileSystem::new(
+            Arc::new(upper),
+            Arc::new(DiskFileSystem),
+        ))),
+        ..Default::default()
+    };
+    let program = load_program(&[main_file], Some(opts)).unwrap();
+    assert_eq!(program.pkgs["__main__"][0].filename, main_file);
+}
+
+#[test]
+fn test_load_program_source_map() {
+    let fs = MemoryFileSystem::default();
+    fs.insert("/konfig/kcl.mod", "");
+    fs.insert("/konfig/main.k", "import pkg\na = pkg.b");
+    fs.insert("/konfig/pkg/a.k", "b = 1");
+    fs.insert("/konfig/pkg/b.k", "c = 1\nd = 2");
+    let fs: Arc<dyn FileSystem> = Arc::new(fs);
+    let opts = LoadProgramOptions {
+        fs: Some(fs.clone()),
+        ..Default::default()
+    };
+    let program = load_program(&["/konfig/main.k"], Some(opts)).unwrap();
+    let filenames: Vec<String> = program
+        .source_map
+        .files()
+        .into_iter()
+        .map(|file| file.name)
+        .collect();
+    assert_eq!(
+        filenames,
+        vec!["/konfig/main.k", "/konfig/pkg/a.k", "/konfig/pkg/b.k"]
+    );
+    let file = program.source_map.get_file(2).unwrap();
+    let loc = program
+        .source_map
+        .lookup_char_pos(file.start_pos + BytePos::from_usize(6))
+        .unwrap();
+    assert_eq!(
+        (loc.filename.as_str(), loc.line, loc.col),
+        ("/konfig/pkg/b.k", 2, 0)
+    );
+    assert_eq!(program.pkgs["pkg"][1].body[1].line, 2);
+
+    // The syntax errors in the parsing threads are returned by the loading thread.
+    let opts = LoadProgramOptions {
+        fs: Some(fs),
+        ..Default::default()
+    };
+    let fs = MemoryFileSystem::default();
+    fs.insert("/app/main.k", "import pkg");
+    fs.insert("/app/pkg/a.k", "a = 1");
+    fs.insert("/app/pkg/b.k", "b = ");
+    let opts = LoadProgramOptions {
+        fs: Some(Arc::new(fs)),
+        ..opts
+    };
+    match load_program(&["/app/main.k"], Some(opts)) {
+        Err(KclvmError::Parse(diags)) => {
+            assert_eq!(diags[0].messages[0].pos.filename, "/app/pkg/b.k");
+            assert_eq!(diags[0].messages[0].pos.line, 1);
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn test_load_program_from_archives() {
+    let program = load_program(&["./testdata/archive/main.k"], None).unwrap();
+    let root = std::path::Path::new("./testdata/archive")
+        .canonicalize()
+        .unwrap();
+    let mut pkgs: Vec<&String> = program.pkgs.keys().collect();
+    pkgs.sort();
+    assert_eq!(pkgs, vec!["__main__", "lib.base", "lib.base.types", "util"]);
+    let archive = root.join("vendor").join("lib.tar.gz");
+    assert_eq!(
+        program.pkgs["lib.base"][0].filename,
+        archive.join("lib/base/base.k").to_str().unwrap()
+    );
+    assert_eq!(
+        program.pkgs["util"][0].filename,
+        root.join("vendor/util.zip/util/util.k").to_str().unwrap()
+    );
+
+    // The package paths in the options are searched before the ones in kcl.mod.
+    let opts = LoadProgramOptions {
+        work_dir: "./testdata/archive".to_string(),
+        package_paths: vec!["vendor/util.zip".to_string()],
+        ..Default::default()
+    };
+    let program = load_program(&["./testdata/archive/main.k"], Some(opts)).unwrap();
+    assert!(program.pkgs.contains_key("util"));
+
+    let opts = LoadProgramOptions {
+        package_paths: vec!["./testdata/archive/vendor/missing.zip".to_string()],
+        ..Default::default()
+    };
+    assert!(matches!(
+        load_program(&["./testdata/archive/main.k"], Some(opts)),
+        Err(KclvmError::Load(_))
+    ));
+}
+
+#[test]
+fn test_dep_graph() {
+    let root = std::path::Path::new("./testdata/deps")
+        .canonicalize()
+        .unwrap();
+    let file = |path: &str| root.join(path).to_str().unwrap().to_string();
+    let graph = deps::load_dep_graph(&["./testdata/deps/main.k"], None).unwrap();
+    assert_eq!(
+        graph.pkgs.keys().collect::<Vec<&String>>(),
+        vec!["__main__", "app", "base"]
+    );
+    assert_eq!(graph.dependencies("__main__"), vec!["app", "base"]);
+    assert_eq!(
+        graph.dep_files("app"),
+        vec![file("app/app.k"), file("base/base.k")]
+    );
+    assert_eq!(graph.dependents("base"), vec!["__main__", "app"]);
+    let importers = graph.importers("base");
+    assert_eq!(importers.len(), 2);
+    assert_eq!(
+        (
+            importers[0].from.as_str(),
+            importers[0].line,
+            importers[0].column
+        ),
+        ("__main__", 2, 0)
+    );
+    assert_eq!(importers[1].filename, file("app/app.k"));
+    assert!(graph.cycles().is_empty());
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph deps {\n"));
+    assert!(dot.contains("\"app\" -> \"base\""));
+
+    let graph = deps::load_dep_graph(&["./testdata/deps/cycle.k"], None).unwrap();
+    assert_eq!(
+        graph.cycles(),
+        vec![vec![
+            "cyc.a".to_string(),
+            "cyc.b".to_string(),
+            "cyc.a".to_string()
+        ]]
+    );
+    assert_eq!(graph.dependents("cyc.a"), vec!["__main__", "cyc.b"]);
+}
+
+#[test]
+fn test_dep_graph_cycles() {
+    // Both cycles through `c` are reported although `c` is visited from `b` first.
+    let fs = MemoryFileSystem::default();
+    fs.insert("/cyc/kcl.mod", "");
+    fs.insert("/cyc/main.k", "import a");
+    fs.insert("/cyc/a/a.k", "import b\nimport d");
+    fs.insert("/cyc/b/b.k", "import c");
+    fs.insert("/cyc/c/c.k", "import a");
+    fs.insert("/cyc/d/d.k", "import c");
+    let opts = LoadProgramOptions {
+        fs: Some(Arc::new(fs)),
+        ..Default::default()
+    };
+    let graph = deps::load_dep_graph(&["/cyc/main.k"], Some(opts)).unwrap();
+    let cycle = |pkgs: &[&str]| -> Vec<String> { pkgs.iter().map(|p| p.to_string()).collect() };
+    assert_eq!(
+        graph.cycles(),
+        vec![cycle(&["a", "b", "c", "a"]), cycle(&["a", "d", "c", "a"])]
+    );
+}
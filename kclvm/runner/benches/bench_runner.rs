@@ -28,7 +28,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
 criterion_group!(benches, criterion_benchmark);
 criterion_main!(benches);
 
-fn exec(file: &str) -> Result<String, String> {
+fn exec(file: &str) -> kclvm_error::KclvmResult<String> {
     let mut args = ExecProgramArgs::default();
     args.k_filename_list.push(file.to_string());
     let plugin_agent = 0;
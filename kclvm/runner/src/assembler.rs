@@ -5,11 +5,14 @@ use kclvm_compiler::codegen::{
     llvm::{emit_code, LL_FILE_SUFFIX},
     EmitOptions,
 };
-use kclvm_config::cache::{load_pkg_cache, save_pkg_cache, CacheOption};
+use kclvm_config::cache::{
+    evict_content_cache, get_cache_dir, get_cache_lock_filename, load_content_cache,
+    save_content_cache, CacheKey, CacheOption, ContentHasher,
+};
 use kclvm_error::bug;
 use kclvm_sema::resolver::scope::ProgramScope;
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     env,
     path::{Path, PathBuf},
     sync::mpsc::channel,
@@ -262,12 +265,74 @@ impl KclvmAssembler {
         cache_dir
     }
 
+    /// The cache dir is `<prog_root>/.kclvm/cache/<version>` by default, the
+    /// programs compiled from the source code without a root use the working
+    /// directory instead. It can be overridden by the environment variable
+    /// `KCLVM_CACHE_DIR`.
     #[inline]
     pub(crate) fn construct_cache_dir(&self, prog_root_name: &str) -> PathBuf {
-        Path::new(prog_root_name)
-            .join(".kclvm")
-            .join("cache")
-            .join(kclvm_version::get_full_version())
+        PathBuf::from(get_cache_dir(prog_root_name, None))
+    }
+
+    /// Returns the content addresses of all the package libraries.
+    ///
+    /// The key of a package hashes the compiler version, the IR and library
+    /// formats, and the contents of the package and all the packages it imports
+    /// transitively, i.e. their ASTs and the import names of their files, so a
+    /// change in any transitive dependency also changes the key. The emit
+    /// options are the same for all the package libraries and the command line
+    /// arguments are passed to the runner at runtime, neither are compiled into
+    /// the libraries differently, so they are not a part of the key.
+    pub(crate) fn gen_cache_keys(&self) -> HashMap<String, CacheKey> {
+        let mut hashes: HashMap<&str, CacheKey> = HashMap::new();
+        let mut imports: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+        for (pkgpath, modules) in &self.program.pkgs {
+            let mut hasher = ContentHasher::new();
+            hasher
+                .input_str(&self.single_file_assembler.get_code_file_suffix())
+                .input_str(&Command::get_lib_suffix())
+                .input_str(pkgpath);
+            let pkg_imports = imports.entry(pkgpath.as_str()).or_default();
+            for module in modules {
+                hasher.input_str(&serde_json::to_string(module).unwrap());
+                if let Some(import_names) = self.scope.import_names.get(&module.filename) {
+                    for (name, path) in import_names {
+                        hasher.input_str(name).input_str(path);
+                    }
+                }
+                for stmt in &module.body {
+                    if let ast::Stmt::Import(import_stmt) = &stmt.node {
+                        // The system and plugin modules are not the packages of the program.
+                        if let Some((path, _)) = self.program.pkgs.get_key_value(&import_stmt.path)
+                        {
+                            pkg_imports.insert(path.as_str());
+                        }
+                    }
+                }
+            }
+            hashes.insert(pkgpath.as_str(), hasher.result());
+        }
+        // Hash the closure of the imported packages instead of their keys, which
+        // is well defined in the import cycles too.
+        let mut keys = HashMap::new();
+        for pkgpath in self.program.pkgs.keys() {
+            let mut deps = BTreeSet::new();
+            let mut stack = vec![pkgpath.as_str()];
+            while let Some(pkg) = stack.pop() {
+                for dep in &imports[pkg] {
+                    if deps.insert(*dep) {
+                        stack.push(*dep);
+                    }
+                }
+            }
+            let mut hasher = ContentHasher::new();
+            hasher.input_str(&hashes[pkgpath.as_str()]);
+            for dep in deps {
+                hasher.input_str(dep).input_str(&hashes[dep]);
+            }
+            keys.insert(pkgpath.to_string(), hasher.result());
+        }
+        keys
     }
 
     /// Generate the dynamic link libraries and return file paths.
@@ -278,6 +343,10 @@ impl KclvmAssembler {
     /// This method will generate dynamic link library files (such as "*.dylib", "*.dll.lib", "*.so")
     /// and ir code files, and return the file paths of the dynamic link library files in [Vec<String>].
     ///
+    /// The libraries of all the packages including the main package are stored in the cache directory
+    /// by their content addresses, see [KclvmAssembler::gen_cache_keys], so re-running an unchanged
+    /// program reuses every library.
+    ///
     /// `gen_libs` will create multiple threads and call the method provided by [KclvmLibAssembler] in each thread
     /// to generate the dynamic link library in parallel.
     pub(crate) fn gen_libs(self) -> Vec<String> {
@@ -286,6 +355,7 @@ impl KclvmAssembler {
             &self.single_file_assembler.get_code_file_suffix(),
         );
         let cache_dir = self.load_cache_dir(&self.program.root);
+        let cache_keys = self.gen_cache_keys();
         let mut compile_progs: IndexMap<
             String,
             (
@@ -303,6 +373,7 @@ impl KclvmAssembler {
                 pkgs,
                 cmd_args: vec![],
                 cmd_overrides: vec![],
+                source_map: self.program.source_map.clone(),
             };
             compile_progs.insert(
                 pkgpath,
@@ -321,78 +392,64 @@ impl KclvmAssembler {
             // clone a single file assembler for one thread.
             let assembler = self.single_file_assembler.clone();
 
-            let code_file = self.entry_file.clone();
+            let key = cache_keys[&pkgpath].clone();
+            let file = cache_dir.join(&key);
+            let code_file = file.to_str().unwrap().to_string();
             let code_file_path = assembler.add_code_file_suffix(&code_file);
-            let lock_file_path = format!("{}.lock", code_file_path);
+            let lock_file_path = get_cache_lock_filename(&compile_prog.root, &key, None);
             let lib_path = format!("{}{}", code_file, Command::get_lib_suffix());
 
             pool.execute(move || {
-                // Locking file for parallel code generation.
+                // Locking file for parallel code generation, the processes which
+                // compile the same package wait for each other and share the library.
+                if let Some(lock_dir) = Path::new(&lock_file_path).parent() {
+                    let _ = std::fs::create_dir_all(lock_dir);
+                }
                 let mut file_lock = fslock::LockFile::open(&lock_file_path)
                     .unwrap_or_else(|_| panic!("{} not found", lock_file_path));
                 file_lock.lock().unwrap();
 
                 let root = &compile_prog.root;
-                let is_main_pkg = pkgpath == kclvm_ast::MAIN_PKG;
-                // The main package does not perform cache reading and writing,
-                // and other packages perform read and write caching. Because
-                // KCL supports multi-file compilation, it is impossible to
-                // specify a standard entry for these multi-files and cannot
-                // be shared, so the cache of the main package is not read and
-                // written.
-                let lib_path = if is_main_pkg {
-                    // generate dynamic link library for single file kcl program
-                    assembler.assemble_lib(
-                        &compile_prog,
-                        import_names,
-                        &code_file,
-                        &code_file_path,
-                        &lib_path,
-                    )
-                } else {
-                    let file = cache_dir.join(&pkgpath);
-                    // Read the lib path cache
-                    let lib_relative_path: Option<String> =
-                        load_pkg_cache(root, &pkgpath, CacheOption::default());
-                    let lib_abs_path = match lib_relative_path {
-                        Some(lib_relative_path) => {
-                            let path = if lib_relative_path.starts_with('.') {
-                                lib_relative_path.replacen('.', root, 1)
-                            } else {
-                                lib_relative_path
-                            };
-                            if Path::new(&path).exists() {
-                                Some(path)
-                            } else {
-                                None
-                            }
-                        }
-                        None => None,
-                    };
-                    match lib_abs_path {
-                        Some(path) => path,
-                        None => {
-                            let code_file = file.to_str().unwrap();
-                            let code_file_path = assembler.add_code_file_suffix(code_file);
-                            let lib_path = format!("{}{}", code_file, Command::get_lib_suffix());
-                            // generate dynamic link library for single file kcl program
-                            let lib_path = assembler.assemble_lib(
-                                &compile_prog,
-                                import_names,
-                                code_file,
-                                &code_file_path,
-                                &lib_path,
-                            );
-                            let lib_relative_path = lib_path.replacen(root, ".", 1);
-                            save_pkg_cache(
-                                root,
-                                &pkgpath,
-                                lib_relative_path,
-                                CacheOption::default(),
-                            );
-                            lib_path
+                // Read the lib path cache
+                let lib_relative_path: Option<String> =
+                    load_content_cache(root, &key, CacheOption::default());
+                let lib_abs_path = match lib_relative_path {
+                    Some(lib_relative_path) => {
+                        // The programs without a root save the absolute paths.
+                        let path = if !root.is_empty() && lib_relative_path.starts_with('.') {
+                            lib_relative_path.replacen('.', root, 1)
+                        } else {
+                            lib_relative_path
+                        };
+                        if Path::new(&path).exists() {
+                            Some(path)
+                        } else {
+                            None
                         }
                     }
+                    None => None,
+                };
+                let lib_path = match lib_abs_path {
+                    Some(path) => path,
+                    None => {
+                        // generate dynamic link library for single file kcl program
+                        let lib_path = assembler.assemble_lib(
+                            &compile_prog,
+                            import_names,
+                            &code_file,
+                            &code_file_path,
+                            &lib_path,
+                        );
+                        // The cache dir may be out of the program root.
+                        let lib_relative_path = match Path::new(&lib_path).strip_prefix(root) {
+                            Ok(path) if !root.is_empty() => {
+                                Path::new(".").join(path).to_string_lossy().to_string()
+                            }
+                            _ => lib_path.clone(),
+                        };
+                        save_content_cache(root, &key, lib_relative_path, CacheOption::default());
+                        lib_path
+                    }
                 };
                 file_lock.unlock().unwrap();
                 tx.send(lib_path)
@@ -411,6 +468,10 @@ impl KclvmAssembler {
                 .unwrap();
             lib_paths.push(lib_path);
         }
+        // Evict the cache after all the libraries of the program are ready, the
+        // libraries of the program itself are kept.
+        let keep: Vec<&str> = cache_keys.values().map(|key| key.as_str()).collect();
+        evict_content_cache(&self.program.root, &keep, &CacheOption::default());
         self.single_file_assembler.clean_lock_file(&self.entry_file);
         lib_paths
     }
@@ -1,3 +1,4 @@
+use kclvm_error::{KclvmError, KclvmResult};
 use std::env::consts::DLL_SUFFIX;
 use std::{env, path::PathBuf};
 
@@ -74,6 +75,56 @@ impl Command {
         path.to_str().unwrap().to_string()
     }
 
+    /// Link the program objects and the static KCLVM runtime library into one
+    /// standalone executable.
+    pub(crate) fn link_executable(
+        &mut self,
+        files: &[String],
+        exe_path: &str,
+    ) -> KclvmResult<String> {
+        let exe_path = if exe_path.is_empty() {
+            "a.out"
+        } else {
+            exe_path
+        };
+
+        let mut args: Vec<String> = vec![
+            "-Wno-override-module".to_string(),
+            "-Wno-error=unused-command-line-argument".to_string(),
+            "-Wno-unused-command-line-argument".to_string(),
+        ];
+        args.extend(files.iter().cloned());
+        args.push(format!("{}/lib/libkclvm_native.a", self.executable_root));
+        if !Self::is_windows() {
+            args.extend(
+                ["-lpthread", "-ldl", "-lm"]
+                    .iter()
+                    .map(|arg| arg.to_string()),
+            );
+        }
+        args.push("-o".to_string());
+        args.push(exe_path.to_string());
+
+        let result = std::process::Command::new(self.clang_path.clone())
+            .args(&args)
+            .output()
+            .map_err(|err| KclvmError::other(&format!("run clang failed: {}", err)))?;
+
+        if !result.status.success() {
+            return Err(KclvmError::other(&format!(
+                "run clang failed: stdout {}, stderr: {}",
+                String::from_utf8_lossy(&result.stdout),
+                String::from_utf8_lossy(&result.stderr)
+            )));
+        }
+
+        // Use absolute path.
+        let path = PathBuf::from(exe_path)
+            .canonicalize()
+            .map_err(|err| KclvmError::other(&format!("{} not found: {}", exe_path, err)))?;
+        Ok(path.to_string_lossy().to_string())
+    }
+
     /// Get the kclvm executable root.
     fn get_executable_root() -> String {
         if Self::is_windows() {
@@ -0,0 +1,71 @@
+//! KclvmJitRunner links the KCL program into one LLVM module in memory and
+//! executes it through the LLVM MCJIT execution engine in the current process.
+//!
+//! Different from [crate::runner::KclvmRunner], it does not need to invoke the
+//! `clang` linker or load any dynamic link library, the runtime functions declared
+//! in the module are resolved to the KCL runtime linked into the current process.
+use crate::runner::{kcl_run_main, plugin_method, ExecProgramArgs, KclvmRunnerOptions};
+use indexmap::IndexMap;
+use inkwell::{context::Context, execution_engine::ExecutionEngine, OptimizationLevel};
+use kclvm_ast::ast::Program;
+use kclvm_compiler::codegen::llvm::emit_module;
+use kclvm_error::{KclvmError, KclvmResult};
+
+/// The entry function name of the compiled KCL program.
+const KCLVM_MAIN_FN: &str = "kclvm_main";
+/// The name prefix of all the runtime functions.
+const RUNTIME_FN_PREFIX: &str = "kclvm_";
+
+pub struct KclvmJitRunner {
+    opts: KclvmRunnerOptions,
+}
+
+impl KclvmJitRunner {
+    pub fn new(opts: Option<KclvmRunnerOptions>) -> Self {
+        Self {
+            opts: opts.unwrap_or_default(),
+        }
+    }
+
+    /// Compile the resolved program in memory and run it.
+    ///
+    /// "import_names" is the import names of the program scope returned by the resolver.
+    pub fn run(
+        &self,
+        program: &Program,
+        import_names: IndexMap<String, IndexMap<String, String>>,
+        args: &ExecProgramArgs,
+    ) -> KclvmResult<String> {
+        let context = Context::create();
+        let module = emit_module(&context, program, import_names)
+            .map_err(|err| KclvmError::other(&err.to_string()))?;
+        ExecutionEngine::link_in_mc_jit();
+        let engine = module
+            .create_jit_execution_engine(OptimizationLevel::None)
+            .map_err(|err| KclvmError::other(&err.to_string()))?;
+        // The runtime functions are only declared in the module, map them to
+        // the runtime functions in the current process.
+        for function in module.get_functions() {
+            let name = function.get_name().to_string_lossy();
+            if function.count_basic_blocks() == 0 && name.starts_with(RUNTIME_FN_PREFIX) {
+                let fn_ptr = kclvm::_kclvm_get_fn_ptr_by_name(&name);
+                if fn_ptr == 0 {
+                    return Err(KclvmError::other(&format!(
+                        "runtime function {} not found",
+                        name
+                    )));
+                }
+                engine.add_global_mapping(&function, fn_ptr as usize);
+            }
+        }
+        let kclvm_main_ptr = engine
+            .get_function_address(KCLVM_MAIN_FN)
+            .map_err(|err| KclvmError::other(&format!("{} not found: {:?}", KCLVM_MAIN_FN, err)))?
+            as u64;
+        unsafe {
+            // register plugin agent
+            kclvm::kclvm_plugin_init(plugin_method(self.opts.plugin_agent_ptr));
+            kcl_run_main(kclvm::_kcl_run, kclvm_main_ptr, args)
+        }
+    }
+}
@@ -2,11 +2,17 @@ use std::{collections::HashMap, path::Path, time::SystemTime};
 
 use assembler::KclvmLibAssembler;
 use command::Command;
+use jit::KclvmJitRunner;
 use kclvm::ValueRef;
 use kclvm_ast::{
     ast::{Module, Program},
     MAIN_PKG,
 };
+use kclvm_compiler::codegen::{
+    llvm::{emit_code, LL_FILE_SUFFIX},
+    EmitOptions,
+};
+use kclvm_error::{KclvmError, KclvmResult};
 use kclvm_parser::load_program;
 use kclvm_query::apply_overrides;
 use kclvm_sema::resolver::resolve_program;
@@ -16,19 +22,45 @@ use tempfile::tempdir;
 
 pub mod assembler;
 pub mod command;
+pub mod jit;
 pub mod linker;
 pub mod runner;
 
 #[cfg(test)]
 pub mod tests;
 
+/// The C main function of the executables built by `build_program`, the arguments
+/// given at build time replace `BUILD_ARGS` and are passed before the ones given
+/// at run time, and the run time arguments take precedence over them.
+const EXECUTABLE_MAIN_C: &str = r#"#include <stdint.h>
+#include <stdlib.h>
+
+extern void* kclvm_main(void* ctx);
+extern int _kcl_main(int argc, char** argv, int build_argc, uint64_t kclvm_main_ptr);
+
+static char* build_args[] = {BUILD_ARGS NULL};
+
+int main(int argc, char** argv) {
+    int build_argc = sizeof(build_args) / sizeof(build_args[0]) - 1;
+    char** args = malloc(sizeof(char*) * (argc + build_argc));
+    args[0] = argv[0];
+    for (int i = 0; i < build_argc; i++) {
+        args[i + 1] = build_args[i];
+    }
+    for (int i = 1; i < argc; i++) {
+        args[build_argc + i] = argv[i];
+    }
+    return _kcl_main(argc + build_argc, args, build_argc, (uint64_t)(uintptr_t)kclvm_main);
+}
+"#;
+
 /// After the kcl program passed through kclvm-parser in the compiler frontend,
 /// KCLVM needs to resolve ast, generate corresponding LLVM IR, dynamic link library or
 /// executable file for kcl program in the compiler backend.
 ///
 /// Method “execute” is the entry point for the compiler backend.
 ///
-/// It returns the KCL program executing result as Result<a_json_string, an_err_string>,
+/// It returns the KCL program executing result as Result<a_json_string, [KclvmError]>,
 /// and mainly takes "program" (ast.Program returned by kclvm-parser) as input.
 ///
 /// "args" is the items selected by the user in the KCLVM CLI.
@@ -61,50 +93,21 @@ pub mod tests;
 /// // Result is the kcl in json format.
 /// let result = exec_program(&args, 0).unwrap();
 /// ```
-pub fn exec_program(
-    args: &ExecProgramArgs,
-    plugin_agent: u64,
-) -> Result<ExecProgramResult, String> {
-    // parse args from json string
-    let opts = args.get_load_program_options();
-    let k_files = &args.k_filename_list;
-    let mut kcl_paths = Vec::<String>::new();
-    let work_dir = args.work_dir.clone().unwrap_or_default();
-
-    // join work_path with k_file_path
-    for (_, file) in k_files.iter().enumerate() {
-        match Path::new(&work_dir).join(file).to_str() {
-            Some(str) => kcl_paths.push(String::from(str)),
-            None => (),
-        }
-    }
-
-    let kcl_paths_str = kcl_paths.iter().map(|s| s.as_str()).collect::<Vec<&str>>();
-
-    let mut program = load_program(kcl_paths_str.as_slice(), Some(opts))?;
-
-    if let Err(err) = apply_overrides(&mut program, &args.overrides, &[], args.print_override_ast) {
-        return Err(err.to_string());
-    }
+pub fn exec_program(args: &ExecProgramArgs, plugin_agent: u64) -> KclvmResult<ExecProgramResult> {
+    let program = load_program_with_args(args)?;
 
     let start_time = SystemTime::now();
     let exec_result = execute(program, plugin_agent, args);
     let escape_time = match SystemTime::now().duration_since(start_time) {
         Ok(dur) => dur.as_secs_f32(),
-        Err(err) => return Err(err.to_string()),
+        Err(err) => return Err(KclvmError::other(&err.to_string())),
     };
     let mut result = ExecProgramResult::default();
     result.escaped_time = escape_time.to_string();
-    let json_result = match exec_result {
-        Ok(res) => res,
-        Err(res) => {
-            if res.is_empty() {
-                return Ok(result);
-            } else {
-                return Err(res);
-            }
-        }
-    };
+    let json_result = exec_result?;
+    if json_result.is_empty() {
+        return Ok(result);
+    }
     let kcl_val = ValueRef::from_json(&json_result).unwrap();
     let (json_result, yaml_result) = kcl_val.plan();
     result.json_result = json_result;
@@ -114,13 +117,115 @@ pub fn exec_program(
     Ok(result)
 }
 
+/// Compile the KCL program ahead of time into a standalone executable at "output", and
+/// returns the absolute path of the executable.
+///
+/// The executable links the KCLVM runtime statically and does not depend on KCLVM at all.
+/// The top-level arguments and the `strict_range_check`, `disable_none` and `debug` options
+/// of "args" are built into the executable, more top-level arguments (`-D key=value`) and
+/// settings files (`-Y settings.yaml`) are accepted when the executable runs, and it prints
+/// the same YAML result as `exec_program`. The output options `sort_keys` and
+/// `include_schema_type_path` are not supported by the executables and return an error.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kclvm_runner::{build_program, ExecProgramArgs};
+///
+/// let mut args = ExecProgramArgs::default();
+/// args.k_filename_list = vec!["./src/test_datas/init_check_order_0/main.k".to_string()];
+///
+/// // Build the executable "./main" and run it with `./main -D key=value`.
+/// let exe_path = build_program(&args, "./main").unwrap();
+/// ```
+pub fn build_program(args: &ExecProgramArgs, output: &str) -> KclvmResult<String> {
+    if args.sort_keys || args.include_schema_type_path {
+        return Err(KclvmError::other(
+            "the executables do not support sorting keys or including schema type paths",
+        ));
+    }
+    let mut program = load_program_with_args(args)?;
+
+    // Resolve ast
+    let scope = resolve_program(&mut program);
+    scope.check_diagnostics()?;
+
+    // Create a temp entry file and the temp dir will be delete automatically
+    let temp_dir = tempdir().map_err(|err| KclvmError::other(&err.to_string()))?;
+    let temp_dir_path = temp_dir.path().to_str().unwrap();
+    let temp_entry_file = temp_file(temp_dir_path);
+
+    // Generate all the packages into one LLVM IR file.
+    let code_file = format!("{}{}", temp_entry_file, LL_FILE_SUFFIX);
+    emit_code(
+        &program,
+        scope.import_names,
+        &EmitOptions {
+            from_path: None,
+            emit_path: Some(&code_file),
+            no_link: false,
+        },
+    )
+    .map_err(|err| KclvmError::other(&err.to_string()))?;
+
+    // Generate the C main function calling the runtime entry `_kcl_main`.
+    let main_file = format!("{}.c", temp_entry_file);
+    let main_code = EXECUTABLE_MAIN_C.replace("BUILD_ARGS", &executable_build_args(args));
+    std::fs::write(&main_file, main_code).map_err(|err| KclvmError::other(&err.to_string()))?;
+
+    // Link the executable
+    linker::KclvmLinker::link_executable(vec![code_file, main_file], output.to_string())
+}
+
+/// Returns the C array items of the command line arguments of the executable
+/// which are equivalent to "args".
+fn executable_build_args(args: &ExecProgramArgs) -> String {
+    let mut build_args = vec![];
+    for arg in &args.args {
+        build_args.push("-D".to_string());
+        build_args.push(format!("{}={}", arg.name, arg.value));
+    }
+    if args.strict_range_check {
+        build_args.push("-r".to_string());
+    }
+    if args.disable_none {
+        build_args.push("-n".to_string());
+    }
+    if args.debug != 0 {
+        build_args.push("-d".to_string());
+    }
+    build_args
+        .iter()
+        .map(|arg| format!("{}, ", c_string_literal(arg)))
+        .collect()
+}
+
+/// Quotes "s" as a C string literal, all the bytes except the printable ASCII
+/// characters are escaped in octal.
+fn c_string_literal(s: &str) -> String {
+    let mut literal = String::from("\"");
+    for byte in s.bytes() {
+        match byte {
+            b'"' | b'\\' | b'?' => {
+                literal.push('\\');
+                literal.push(byte as char);
+            }
+            0x20..=0x7e => literal.push(byte as char),
+            _ => literal.push_str(&format!("\\{:03o}", byte)),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
 /// After the kcl program passed through kclvm-parser in the compiler frontend,
 /// KCLVM needs to resolve ast, generate corresponding LLVM IR, dynamic link library or
 /// executable file for kcl program in the compiler backend.
 ///
 /// Method “execute” is the entry point for the compiler backend.
 ///
-/// It returns the KCL program executing result as Result<a_json_string, an_err_string>,
+/// It returns the KCL program executing result as KclvmResult<a_json_string>, the compile and
+/// runtime errors are returned as [KclvmError::Compile] and [KclvmError::Runtime],
 /// and mainly takes "program" (ast.Program returned by kclvm-parser) as input.
 ///
 /// "args" is the items selected by the user in the KCLVM CLI.
@@ -140,6 +245,10 @@ pub fn exec_program(
 ///
 /// At last, KclvmRunner will be constructed and call method "run" to execute the kcl program.
 ///
+/// When the environment variable `KCLVM_JIT` is set to `1` or `true`, the program is linked in
+/// memory and executed by KclvmJitRunner through the LLVM JIT execution engine instead, which
+/// requires neither the clang toolchain nor the dynamic link libraries.
+///
 /// # Examples
 ///
 /// ```
@@ -164,10 +273,18 @@ pub fn execute(
     mut program: Program,
     plugin_agent: u64,
     args: &ExecProgramArgs,
-) -> Result<String, String> {
+) -> KclvmResult<String> {
     // Resolve ast
     let scope = resolve_program(&mut program);
-    scope.check_scope_diagnostics();
+    scope.check_diagnostics()?;
+
+    // Run in the current process without the linker and the dynamic link libraries.
+    if jit_enabled() {
+        let runner = KclvmJitRunner::new(Some(KclvmRunnerOptions {
+            plugin_agent_ptr: plugin_agent,
+        }));
+        return runner.run(&program, scope.import_names, args);
+    }
 
     // Create a temp entry file and the temp dir will be delete automatically
     let temp_dir = tempdir().unwrap();
@@ -207,7 +324,7 @@ pub fn execute(
 /// `execute_module` constructs `Program` with default pkg name `MAIN_PKG`,
 /// and calls method `execute` with default `plugin_agent` and `ExecProgramArgs`.
 /// For more information, see doc above method `execute`.
-pub fn execute_module(mut m: Module) -> Result<String, String> {
+pub fn execute_module(mut m: Module) -> KclvmResult<String> {
     m.pkg = MAIN_PKG.to_string();
 
     let mut pkgs = HashMap::new();
@@ -219,11 +336,44 @@ pub fn execute_module(mut m: Module) -> Result<String, String> {
         pkgs,
         cmd_args: vec![],
         cmd_overrides: vec![],
+        source_map: Default::default(),
     };
 
     execute(prog, 0, &ExecProgramArgs::default())
 }
 
+/// Load the program of the input files in args and apply the overrides.
+fn load_program_with_args(args: &ExecProgramArgs) -> KclvmResult<Program> {
+    // parse args from json string
+    let opts = args.get_load_program_options();
+    let k_files = &args.k_filename_list;
+    let mut kcl_paths = Vec::<String>::new();
+    let work_dir = args.work_dir.clone().unwrap_or_default();
+
+    // join work_path with k_file_path
+    for (_, file) in k_files.iter().enumerate() {
+        match Path::new(&work_dir).join(file).to_str() {
+            Some(str) => kcl_paths.push(String::from(str)),
+            None => (),
+        }
+    }
+
+    let kcl_paths_str = kcl_paths.iter().map(|s| s.as_str()).collect::<Vec<&str>>();
+
+    let mut program = load_program(kcl_paths_str.as_slice(), Some(opts))?;
+
+    if let Err(err) = apply_overrides(&mut program, &args.overrides, &[], args.print_override_ast) {
+        return Err(KclvmError::load(&err.to_string()));
+    }
+    Ok(program)
+}
+
+/// Whether to execute the program with the JIT runner.
+#[inline]
+fn jit_enabled() -> bool {
+    matches!(std::env::var("KCLVM_JIT").as_deref(), Ok("1") | Ok("true"))
+}
+
 /// Clean all the tmp files generated during lib generating and linking.
 #[inline]
 fn clean_tmp_files(temp_entry_file: &String, lib_suffix: &String) {
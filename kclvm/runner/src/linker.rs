@@ -1,4 +1,5 @@
 use crate::command::Command;
+use kclvm_error::KclvmResult;
 
 /// KclvmLinker is mainly responsible for linking the libs generated by KclvmAssembler.
 pub struct KclvmLinker;
@@ -8,4 +9,11 @@ impl KclvmLinker {
         let mut cmd = Command::new();
         cmd.link_libs(&lib_paths, &lib_path)
     }
+
+    /// Link the program generated by method "emit_code" and the entry file into
+    /// a standalone executable.
+    pub fn link_executable(files: Vec<String>, exe_path: String) -> KclvmResult<String> {
+        let mut cmd = Command::new();
+        cmd.link_executable(&files, &exe_path)
+    }
 }
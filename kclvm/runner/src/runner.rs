@@ -1,7 +1,9 @@
-use kclvm::ValueRef;
+use kclvm::{kcl_run_with_options, KclRunFn};
 use kclvm_ast::ast;
 use kclvm_config::settings::SettingsFile;
+use kclvm_error::{KclvmError, KclvmResult};
 use serde::{Deserialize, Serialize};
+use std::os::raw::c_char;
 
 #[allow(non_camel_case_types)]
 pub type kclvm_char_t = i8;
@@ -127,7 +129,7 @@ impl KclvmRunner {
         }
     }
 
-    pub fn run(&self, args: &ExecProgramArgs) -> Result<String, String> {
+    pub fn run(&self, args: &ExecProgramArgs) -> KclvmResult<String> {
         unsafe {
             Self::lib_kclvm_plugin_init(&self.lib, self.opts.plugin_agent_ptr);
             Self::lib_kcl_run(&self.lib, args)
@@ -135,147 +137,66 @@ impl KclvmRunner {
     }
 }
 
+/// The plugin method registered into the runtime by `kclvm_plugin_init`.
+pub(crate) type PluginMethod = extern "C" fn(
+    method: *const i8,
+    args_json: *const c_char,
+    kwargs_json: *const c_char,
+) -> *const c_char;
+
 impl KclvmRunner {
     unsafe fn lib_kclvm_plugin_init(lib: &libloading::Library, plugin_method_ptr: u64) {
         // get kclvm_plugin_init
-        let kclvm_plugin_init: libloading::Symbol<
-            unsafe extern "C" fn(
-                fn_ptr: extern "C" fn(
-                    method: *const i8,
-                    args_json: *const i8,
-                    kwargs_json: *const i8,
-                ) -> *const i8,
-            ),
-        > = lib.get(b"kclvm_plugin_init").unwrap();
-
-        // get plugin_method
-        let plugin_method_ptr = plugin_method_ptr;
-        let plugin_method_ptr = (plugin_method_ptr as *const u64) as *const ()
-            as *const extern "C" fn(
-                method: *const i8,
-                args: *const i8,
-                kwargs: *const i8,
-            ) -> *const i8;
-        let plugin_method: extern "C" fn(
-            method: *const i8,
-            args: *const i8,
-            kwargs: *const i8,
-        ) -> *const i8 = std::mem::transmute(plugin_method_ptr);
+        let kclvm_plugin_init: libloading::Symbol<unsafe extern "C" fn(fn_ptr: PluginMethod)> =
+            lib.get(b"kclvm_plugin_init").unwrap();
 
         // register plugin agent
-        kclvm_plugin_init(plugin_method);
+        kclvm_plugin_init(plugin_method(plugin_method_ptr));
     }
 
     unsafe fn lib_kcl_run(
         lib: &libloading::Library,
         args: &ExecProgramArgs,
-    ) -> Result<String, String> {
-        let kcl_run: libloading::Symbol<
-            unsafe extern "C" fn(
-                kclvm_main_ptr: u64, // main.k => kclvm_main
-                option_len: kclvm_size_t,
-                option_keys: *const *const kclvm_char_t,
-                option_values: *const *const kclvm_char_t,
-                strict_range_check: i32,
-                disable_none: i32,
-                disable_schema_check: i32,
-                list_option_mode: i32,
-                debug_mode: i32,
-                result_buffer_len: kclvm_size_t,
-                result_buffer: *mut kclvm_char_t,
-                warn_buffer_len: kclvm_size_t,
-                warn_buffer: *mut kclvm_char_t,
-            ) -> kclvm_size_t,
-        > = lib.get(b"_kcl_run").unwrap();
+    ) -> KclvmResult<String> {
+        let kcl_run: libloading::Symbol<KclRunFn> = lib.get(b"_kcl_run").unwrap();
 
         let kclvm_main: libloading::Symbol<u64> = lib.get(b"kclvm_main").unwrap();
         let kclvm_main_ptr = kclvm_main.into_raw().into_raw() as u64;
 
-        let option_len = args.args.len() as kclvm_size_t;
-
-        let cstr_argv: Vec<_> = args
-            .args
-            .iter()
-            .map(|arg| std::ffi::CString::new(arg.name.as_str()).unwrap())
-            .collect();
-
-        let mut p_argv: Vec<_> = cstr_argv
-            .iter() // do NOT into_iter()
-            .map(|arg| arg.as_ptr())
-            .collect();
-        p_argv.push(std::ptr::null());
-
-        let p: *const *const kclvm_char_t = p_argv.as_ptr();
-        let option_keys = p;
-
-        let cstr_argv: Vec<_> = args
-            .args
-            .iter()
-            .map(|arg| std::ffi::CString::new(arg.value.as_str()).unwrap())
-            .collect();
-
-        let mut p_argv: Vec<_> = cstr_argv
-            .iter() // do NOT into_iter()
-            .map(|arg| arg.as_ptr())
-            .collect();
-        p_argv.push(std::ptr::null());
-
-        let p: *const *const kclvm_char_t = p_argv.as_ptr();
-        let option_values = p;
-
-        let strict_range_check = args.strict_range_check as i32;
-        let disable_none = args.disable_none as i32;
-        let disable_schema_check = 0; // todo
-        let list_option_mode = 0; // todo
-        let debug_mode = args.debug as i32;
-
-        let mut result = vec![0u8; 1024 * 1024];
-        let result_buffer_len = result.len() as i32 - 1;
-        let result_buffer = result.as_mut_ptr() as *mut i8;
-
-        let mut warn_data = vec![0u8; 1024 * 1024];
-        let warn_buffer_len = warn_data.len() as i32 - 1;
-        let warn_buffer = warn_data.as_mut_ptr() as *mut i8;
+        kcl_run_main(*kcl_run, kclvm_main_ptr, args)
+    }
+}
 
-        let n = kcl_run(
-            kclvm_main_ptr,
-            option_len,
-            option_keys,
-            option_values,
-            strict_range_check,
-            disable_none,
-            disable_schema_check,
-            list_option_mode,
-            debug_mode,
-            result_buffer_len,
-            result_buffer,
-            warn_buffer_len,
-            warn_buffer,
-        );
+/// Get the plugin method from the plugin agent address.
+pub(crate) unsafe fn plugin_method(plugin_method_ptr: u64) -> PluginMethod {
+    let plugin_method_ptr = (plugin_method_ptr as *const u64) as *const () as *const PluginMethod;
+    std::mem::transmute(plugin_method_ptr)
+}
 
-        if n > 0 {
-            let return_len = n;
-            let s = std::str::from_utf8(&result[0..return_len as usize]).unwrap();
-            wrap_msg_in_result(s)
-        } else {
-            let return_len = 0 - n;
-            let s = std::str::from_utf8(&warn_data[0..return_len as usize]).unwrap();
-            Err(s.to_string())
-        }
-    }
+/// Execute the compiled `kclvm_main` function at the address `kclvm_main_ptr`
+/// through the runtime entry `kcl_run` with the program arguments.
+pub(crate) unsafe fn kcl_run_main(
+    kcl_run: KclRunFn,
+    kclvm_main_ptr: u64,
+    args: &ExecProgramArgs,
+) -> KclvmResult<String> {
+    let options: Vec<(String, String)> = args
+        .args
+        .iter()
+        .map(|arg| (arg.name.clone(), arg.value.clone()))
+        .collect();
+    kcl_run_with_options(
+        kcl_run,
+        kclvm_main_ptr,
+        &options,
+        args.strict_range_check,
+        args.disable_none,
+        args.debug != 0,
+    )
+    .map_err(|msg| runtime_error(&msg))
 }
 
-fn wrap_msg_in_result(msg: &str) -> Result<String, String> {
-    let kcl_val = match ValueRef::from_json(msg) {
-        Ok(msg) => msg,
-        Err(err) => {
-            return Err(err.to_string());
-        }
-    };
-    if let Some(val) = kcl_val.get_by_key("__kcl_PanicInfo__") {
-        if val.is_truthy() {
-            return Err(msg.to_string());
-        }
-    }
-    Ok(msg.to_string())
+/// Returns the runtime error of the panic info JSON string returned by the runtime.
+fn runtime_error(msg: &str) -> KclvmError {
+    KclvmError::Runtime(KclvmError::from_panic_message(msg).into_diagnostics())
 }
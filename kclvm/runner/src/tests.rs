@@ -2,21 +2,25 @@ use crate::assembler::clean_path;
 use crate::assembler::KclvmAssembler;
 use crate::assembler::KclvmLibAssembler;
 use crate::assembler::LibAssembler;
+use crate::jit::KclvmJitRunner;
 use crate::temp_file;
 use crate::Command;
-use crate::{execute, runner::ExecProgramArgs};
+use crate::{build_program, exec_program, executable_build_args, execute, runner::ExecProgramArgs};
 use anyhow::Context;
 use anyhow::Result;
-use kclvm_ast::ast::{Module, Program};
+use kclvm_ast::ast::{CmdArgSpec, Module, Program};
 use kclvm_compiler::codegen::llvm::LL_FILE_SUFFIX;
+use kclvm_config::cache::{get_cache_lock_filename, load_content_cache, CacheOption};
 use kclvm_config::settings::load_file;
-use kclvm_parser::load_program;
-use kclvm_sema::resolver::resolve_program;
+use kclvm_error::KclvmError;
+use kclvm_parser::{load_program, parse_file};
+use kclvm_sema::resolver::{resolve_program, scope::ProgramScope};
 use std::fs::create_dir_all;
 use std::panic::catch_unwind;
 use std::panic::set_hook;
 use std::path::{Path, PathBuf};
 use std::thread;
+use std::time::SystemTime;
 use std::{
     collections::HashMap,
     fs::{self, File},
@@ -91,24 +95,23 @@ fn construct_program(mut module: Module) -> Program {
         pkgs: pkgs_ast,
         cmd_args: vec![],
         cmd_overrides: vec![],
+        source_map: Default::default(),
     }
 }
 
 fn construct_pkg_lib_path(
     prog: &Program,
     assembler: &KclvmAssembler,
-    main_path: &str,
     suffix: String,
-) -> Vec<PathBuf> {
+) -> Vec<String> {
     let cache_dir = assembler.construct_cache_dir(&prog.root);
+    let cache_keys = assembler.gen_cache_keys();
     let mut result = vec![];
-    for (pkgpath, _) in &prog.pkgs {
-        if pkgpath == "__main__" {
-            result.push(PathBuf::from(format!("{}{}", main_path, suffix)));
-        } else {
-            result.push(cache_dir.join(format!("{}{}", pkgpath.clone(), suffix)));
-        }
+    for pkgpath in prog.pkgs.keys() {
+        let lib_path = cache_dir.join(format!("{}{}", cache_keys[pkgpath], suffix));
+        result.push(lib_path.to_str().unwrap().to_string());
     }
+    result.sort();
     result
 }
 
@@ -151,24 +154,22 @@ fn gen_libs_for_test(entry_file: &str, test_kcl_case_path: &str) {
     let expected_pkg_paths = construct_pkg_lib_path(
         &parse_program(test_kcl_case_path),
         &assembler,
-        PathBuf::from(entry_file).to_str().unwrap(),
         Command::get_lib_suffix(),
     );
 
-    let lib_paths = assembler.gen_libs();
+    let mut lib_paths = assembler.gen_libs();
+    lib_paths.sort();
 
-    assert_eq!(lib_paths.len(), expected_pkg_paths.len());
+    assert_eq!(lib_paths, expected_pkg_paths);
 
     for pkg_path in &expected_pkg_paths {
-        assert_eq!(pkg_path.exists(), true);
+        assert_eq!(Path::new(pkg_path).exists(), true);
     }
 
-    let tmp_main_lib_path =
-        fs::canonicalize(format!("{}{}", entry_file, Command::get_lib_suffix())).unwrap();
-    assert_eq!(tmp_main_lib_path.exists(), true);
-
-    clean_path(tmp_main_lib_path.to_str().unwrap());
-    assert_eq!(tmp_main_lib_path.exists(), false);
+    // The libraries of the unchanged program including the main package are reused.
+    let mut cached_lib_paths = gen_assembler(entry_file, test_kcl_case_path).gen_libs();
+    cached_lib_paths.sort();
+    assert_eq!(cached_lib_paths, lib_paths);
 }
 
 fn assemble_lib_for_test(
@@ -213,15 +214,159 @@ fn test_kclvm_runner_execute() {
     }
 }
 
+#[test]
+fn test_kclvm_jit_runner_execute() {
+    for case in TEST_CASES {
+        let kcl_path = &format!("{}/{}/{}", TEST_CASE_PATH, case, KCL_FILE_NAME);
+        let expected_path = &format!("{}/{}/{}", TEST_CASE_PATH, case, EXPECTED_JSON_FILE_NAME);
+        let mut program = load_test_program(kcl_path.to_string());
+        let scope = resolve_program(&mut program);
+        let result = KclvmJitRunner::new(None)
+            .run(&program, scope.import_names, &ExecProgramArgs::default())
+            .unwrap();
+        let expected_result = load_expect_file(expected_path.to_string());
+        assert_eq!(expected_result, format_str_by_json(result));
+    }
+}
+
+#[test]
+fn test_kclvm_jit_runtime_symbols() {
+    // The JIT runner maps all the runtime functions declared in the runtime
+    // module to the symbol table, an unknown one is an error instead of a panic.
+    let runtime_ll = include_str!("../../runtime/src/_kclvm.ll");
+    for line in runtime_ll
+        .lines()
+        .filter(|line| line.starts_with("declare "))
+    {
+        let name = line.split('@').nth(1).unwrap().split('(').next().unwrap();
+        assert_ne!(
+            kclvm::_kclvm_get_fn_ptr_by_name(name),
+            0,
+            "{} not found",
+            name
+        );
+    }
+    assert_eq!(kclvm::_kclvm_get_fn_ptr_by_name("kclvm_not_found"), 0);
+}
+
+#[test]
+fn test_build_program() {
+    let temp_dir = tempdir().unwrap();
+    for case in TEST_CASES {
+        let kcl_path = format!("{}/{}/{}", TEST_CASE_PATH, case, KCL_FILE_NAME);
+        let mut args = ExecProgramArgs::default();
+        args.k_filename_list = vec![kcl_path];
+        let exe_path = temp_dir.path().join(case);
+        let exe_path = build_program(&args, exe_path.to_str().unwrap()).unwrap();
+        let output = std::process::Command::new(&exe_path).output().unwrap();
+        assert!(output.status.success());
+        let expected_result = exec_program(&args, 0).unwrap();
+        assert_eq!(
+            expected_result.yaml_result.trim(),
+            String::from_utf8(output.stdout).unwrap().trim()
+        );
+    }
+}
+
+#[test]
+fn test_build_program_with_args() {
+    let temp_dir = tempdir().unwrap();
+    let kcl_path = temp_dir.path().join("main.k");
+    fs::write(&kcl_path, "a = option(\"a\")\nb = option(\"b\")\n").unwrap();
+    let mut args = ExecProgramArgs::default();
+    args.k_filename_list = vec![kcl_path.to_str().unwrap().to_string()];
+    args.args = vec![
+        CmdArgSpec {
+            name: "a".to_string(),
+            value: "\"x\\ty?\"".to_string(),
+        },
+        CmdArgSpec {
+            name: "b".to_string(),
+            value: "1".to_string(),
+        },
+    ];
+    let exe_path = temp_dir.path().join("main");
+    let exe_path = build_program(&args, exe_path.to_str().unwrap()).unwrap();
+    // The arguments given at build time are built into the executable.
+    let output = std::process::Command::new(&exe_path).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        exec_program(&args, 0).unwrap().yaml_result.trim(),
+        String::from_utf8(output.stdout).unwrap().trim()
+    );
+    // The arguments given at run time take precedence.
+    let output = std::process::Command::new(&exe_path)
+        .args(["-D", "b=2"])
+        .output()
+        .unwrap();
+    args.args[1].value = "2".to_string();
+    assert_eq!(
+        exec_program(&args, 0).unwrap().yaml_result.trim(),
+        String::from_utf8(output.stdout).unwrap().trim()
+    );
+
+    args.sort_keys = true;
+    assert!(build_program(&args, exe_path.as_str()).is_err());
+}
+
+#[test]
+fn test_executable_build_args() {
+    let mut args = ExecProgramArgs::default();
+    args.args = vec![CmdArgSpec {
+        name: "a".to_string(),
+        value: "\"\\中\"".to_string(),
+    }];
+    args.disable_none = true;
+    assert_eq!(
+        executable_build_args(&args),
+        r#""-D", "a=\"\\\344\270\255\"", "-n", "#
+    );
+}
+
+#[test]
+fn test_exec_program_errors() {
+    let exec_code = |code: &str| {
+        let mut args = ExecProgramArgs::default();
+        args.k_filename_list = vec!["main.k".to_string()];
+        args.k_code_list = vec![code.to_string()];
+        exec_program(&args, 0)
+    };
+    let prev_hook = std::panic::take_hook();
+    set_hook(Box::new(|_| {}));
+    let parse_err = exec_code("a = ");
+    let compile_err = exec_code("a: int = \"1\"");
+    let runtime_err = exec_code("assert False, \"failed\"");
+    std::panic::set_hook(prev_hook);
+
+    assert!(matches!(parse_err, Err(KclvmError::Parse(_))));
+    match compile_err {
+        Err(KclvmError::Compile(diags)) => {
+            assert_eq!(diags[0].messages[0].pos.filename, "main.k");
+            assert_eq!(diags[0].messages[0].pos.line, 1);
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+    match runtime_err {
+        Err(err @ KclvmError::Runtime(_)) => assert!(err.message().contains("failed")),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
 #[test]
 fn test_kclvm_runner_execute_timeout() {
+    let kcl_path = "./src/test_datas/multi_file_compilation/import_abs_path/app-main/main.k";
+    // Occupy the lock file path of the main package library with a directory,
+    // so the code generation thread of the main package never sends the result.
+    let assembler = gen_assembler("test/no_exist_path/", kcl_path);
+    let cache_key = &assembler.gen_cache_keys()[MAIN_PKG_NAME];
+    let lock_path = get_cache_lock_filename(&parse_program(kcl_path).root, cache_key, None);
+    create_dir_all(&lock_path).unwrap();
+
     set_hook(Box::new(|_| {}));
     let result_time_out = catch_unwind(|| {
-        gen_libs_for_test(
-            "test/no_exist_path/",
-            "./src/test_datas/multi_file_compilation/import_abs_path/app-main/main.k",
-        );
+        gen_libs_for_test("test/no_exist_path/", kcl_path);
     });
+    fs::remove_dir_all(&lock_path).unwrap();
     let timeout_panic_msg = "called `Result::unwrap()` on an `Err` value: Timeout";
     match result_time_out {
         Err(panic_err) => {
@@ -271,6 +416,40 @@ fn test_gen_libs() {
     }
 }
 
+#[test]
+fn test_gen_libs_without_root() {
+    // A program without kcl.mod has no root and uses the cache in the working directory.
+    let temp_dir = tempdir().unwrap();
+    let kcl_path = temp_dir.path().join("main.k");
+    fs::write(&kcl_path, "a = 1\n").unwrap();
+    let kcl_path = kcl_path.to_str().unwrap().to_string();
+    let entry_file = temp_file(temp_dir.path().to_str().unwrap());
+    let gen_assembler = || {
+        let mut prog = load_program(&[&kcl_path], None).unwrap();
+        assert!(prog.root.is_empty());
+        let scope = resolve_program(&mut prog);
+        KclvmAssembler::new(prog, scope, entry_file.clone(), KclvmLibAssembler::LLVM)
+    };
+    let assembler = gen_assembler();
+    let keys = assembler.gen_cache_keys();
+    let lib_paths = assembler.gen_libs();
+    let modified = |paths: &[String]| -> Vec<SystemTime> {
+        paths
+            .iter()
+            .map(|path| fs::metadata(path).unwrap().modified().unwrap())
+            .collect()
+    };
+    let lib_times = modified(&lib_paths);
+    for key in keys.values() {
+        let cached: Option<String> = load_content_cache("", key, CacheOption::default());
+        assert!(cached.is_some());
+    }
+    // Running the unchanged program again reuses every library.
+    let cached_lib_paths = gen_assembler().gen_libs();
+    assert_eq!(cached_lib_paths, lib_paths);
+    assert_eq!(modified(&cached_lib_paths), lib_times);
+}
+
 #[test]
 fn test_gen_libs_parallel() {
     let gen_lib_1 = thread::spawn(|| {
@@ -289,6 +468,61 @@ fn test_gen_libs_parallel() {
     gen_lib_2.join().unwrap();
 }
 
+#[test]
+fn test_gen_cache_keys() {
+    let kcl_path = "./src/test_datas/multi_file_compilation/import_abs_path/app-main/main.k";
+    let keys = gen_assembler("", kcl_path).gen_cache_keys();
+    assert_eq!(keys, gen_assembler("", kcl_path).gen_cache_keys());
+
+    // Changing a package changes the keys of itself and the packages importing it.
+    let mut prog = parse_program(kcl_path);
+    let scope = resolve_program(&mut prog);
+    let pkgpath = prog
+        .pkgs
+        .keys()
+        .find(|pkgpath| *pkgpath != MAIN_PKG_NAME)
+        .unwrap()
+        .clone();
+    prog.pkgs.get_mut(&pkgpath).unwrap()[0].body[0].line += 1;
+    let changed_keys =
+        KclvmAssembler::new(prog, scope, String::new(), KclvmLibAssembler::LLVM).gen_cache_keys();
+    assert_ne!(keys[&pkgpath], changed_keys[&pkgpath]);
+    assert_ne!(keys[MAIN_PKG_NAME], changed_keys[MAIN_PKG_NAME]);
+}
+
+#[test]
+fn test_gen_cache_keys_import_cycle() {
+    let gen_keys = |code_a: &str| {
+        let module = |pkg: &str, code: &str| {
+            let mut module = parse_file(&format!("{}.k", pkg), Some(code.to_string())).unwrap();
+            module.pkg = pkg.to_string();
+            module
+        };
+        let mut pkgs = HashMap::new();
+        pkgs.insert("a".to_string(), vec![module("a", code_a)]);
+        pkgs.insert("b".to_string(), vec![module("b", "import a\nb = 1\n")]);
+        let prog = Program {
+            root: String::new(),
+            main: "a".to_string(),
+            pkgs,
+            cmd_args: vec![],
+            cmd_overrides: vec![],
+            source_map: Default::default(),
+        };
+        let scope = ProgramScope {
+            scope_map: Default::default(),
+            import_names: Default::default(),
+            diagnostics: Default::default(),
+        };
+        KclvmAssembler::new(prog, scope, String::new(), KclvmLibAssembler::LLVM).gen_cache_keys()
+    };
+    // Both the packages in the cycle depend on the change.
+    let keys = gen_keys("import b\na = 1\n");
+    let changed_keys = gen_keys("import b\na = 2\n");
+    assert_ne!(keys["a"], changed_keys["a"]);
+    assert_ne!(keys["b"], changed_keys["b"]);
+}
+
 #[test]
 fn test_clean_path_for_genlibs() {
     let mut prog =
@@ -375,7 +609,7 @@ fn test_exec_file() {
     std::panic::set_hook(prev_hook);
 }
 
-fn exec(file: &str) -> Result<String, String> {
+fn exec(file: &str) -> kclvm_error::KclvmResult<String> {
     let mut args = ExecProgramArgs::default();
     args.k_filename_list.push(file.to_string());
     let plugin_agent = 0;
@@ -0,0 +1,287 @@
+// Copyright 2021 The KCL Authors. All rights reserved.
+
+//! The entry of the standalone executables built by `kclvm_cli build`. The
+//! arguments given at build time are passed before the ones given at run time,
+//! the options (`-D`) and the settings files (`-Y`) are accepted at run time,
+//! and the YAML result is the same as the one of `exec_program`. The run time
+//! arguments take precedence over the build time ones, see
+//! [MainArgs::parse_with_build_args].
+
+use crate::*;
+
+use std::ffi::CStr;
+
+#[allow(non_camel_case_types)]
+type kclvm_char_t = i8;
+
+const USAGE: &str = "Usage: [-D key=value]... [-Y settings.yaml]... [-r] [-n] [-d]
+
+Options:
+  -D, --argument key=value    Specify the top-level argument
+  -Y, --setting FILE          Sets the settings file
+  -r, --strict-range-check    Do perform strict numeric range checks
+  -n, --disable-none          Disable dumping None values
+  -d, --debug                 Run in debug mode
+  -h, --help                  Print the help message";
+
+/// The run time arguments of a standalone executable.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MainArgs {
+    pub options: Vec<(String, String)>,
+    pub strict_range_check: bool,
+    pub disable_none: bool,
+    pub debug: bool,
+    pub help: bool,
+}
+
+impl MainArgs {
+    /// Parse the command line arguments except the program name, the settings
+    /// files are loaded in order and the `-D` options are appended after the
+    /// options in the settings files.
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut main_args = MainArgs::default();
+        main_args.parse_args(args)?;
+        Ok(main_args)
+    }
+
+    /// Parse the build time arguments and then the run time arguments, so the
+    /// options are applied in the order of the build time options, the options in
+    /// the run time settings files and the run time `-D` options, and the later
+    /// ones take precedence.
+    pub fn parse_with_build_args(build_args: &[String], args: &[String]) -> Result<Self, String> {
+        let mut main_args = MainArgs::default();
+        main_args.parse_args(build_args)?;
+        main_args.parse_args(args)?;
+        Ok(main_args)
+    }
+
+    fn parse_args(&mut self, args: &[String]) -> Result<(), String> {
+        let mut cli_options = vec![];
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-D" | "--argument" => match iter.next() {
+                    Some(option) => cli_options.push(parse_option(option)?),
+                    None => return Err(format!("missing value for '{}'", arg)),
+                },
+                "-Y" | "--setting" => match iter.next() {
+                    Some(file) => self.load_settings_file(file)?,
+                    None => return Err(format!("missing value for '{}'", arg)),
+                },
+                "-r" | "--strict-range-check" => self.strict_range_check = true,
+                "-n" | "--disable-none" => self.disable_none = true,
+                "-d" | "--debug" => self.debug = true,
+                "-h" | "--help" => self.help = true,
+                _ => match arg.strip_prefix("-D") {
+                    Some(option) => cli_options.push(parse_option(option)?),
+                    None => return Err(format!("unexpected argument '{}'", arg)),
+                },
+            }
+        }
+        self.options.extend(cli_options);
+        Ok(())
+    }
+
+    fn load_settings_file(&mut self, file: &str) -> Result<(), String> {
+        let content = std::fs::read_to_string(file)
+            .map_err(|err| format!("failed to read '{}': {}", file, err))?;
+        let settings: serde_yaml::Value = serde_yaml::from_str(&content)
+            .map_err(|err| format!("invalid settings file '{}': {}", file, err))?;
+        let configs = &settings["kcl_cli_configs"];
+        if let Some(value) = configs["strict_range_check"].as_bool() {
+            self.strict_range_check = value;
+        }
+        if let Some(value) = configs["disable_none"].as_bool() {
+            self.disable_none = value;
+        }
+        if let Some(value) = configs["debug"].as_bool() {
+            self.debug = value;
+        }
+        if let Some(options) = settings["kcl_options"].as_sequence() {
+            for option in options {
+                let key = match option["key"].as_str() {
+                    Some(key) => key.to_string(),
+                    None => return Err(format!("invalid kcl_options in '{}'", file)),
+                };
+                let value = match &option["value"] {
+                    serde_yaml::Value::String(value) => value.clone(),
+                    value => serde_yaml::to_string(value)
+                        .map_err(|err| err.to_string())?
+                        .trim_start_matches("---")
+                        .trim()
+                        .to_string(),
+                };
+                self.options.push((key, value));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_option(option: &str) -> Result<(String, String), String> {
+    match option.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("invalid argument '{}', expected key=value", option)),
+    }
+}
+
+/// Run the compiled `kclvm_main` function with the command line arguments and
+/// print the YAML result, returns the process exit code. The first `build_argc`
+/// arguments after the program name are the ones given at build time.
+///
+/// # Safety
+///
+/// `argv` must point to `argc` valid C strings and `kclvm_main_ptr` must be the
+/// address of the compiled `kclvm_main` function.
+#[no_mangle]
+pub unsafe extern "C" fn _kcl_main(
+    argc: i32,
+    argv: *const *const kclvm_char_t,
+    build_argc: i32,
+    kclvm_main_ptr: u64, // main.k => kclvm_main
+) -> i32 {
+    let args: Vec<String> = (1..argc as isize)
+        .map(|i| {
+            CStr::from_ptr(*argv.offset(i))
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect();
+    let (build_args, args) = args.split_at((build_argc.max(0) as usize).min(args.len()));
+    let main_args = match MainArgs::parse_with_build_args(build_args, args) {
+        Ok(main_args) => main_args,
+        Err(err) => {
+            eprintln!("Error: {}\n\n{}", err, USAGE);
+            return 2;
+        }
+    };
+    if main_args.help {
+        println!("{}", USAGE);
+        return 0;
+    }
+    match run_main(&main_args, kclvm_main_ptr) {
+        Ok(yaml_result) => {
+            print!("{}", yaml_result);
+            0
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            1
+        }
+    }
+}
+
+fn run_main(main_args: &MainArgs, kclvm_main_ptr: u64) -> Result<String, String> {
+    let json_result = unsafe {
+        kcl_run_with_options(
+            _kcl_run,
+            kclvm_main_ptr,
+            &main_args.options,
+            main_args.strict_range_check,
+            main_args.disable_none,
+            main_args.debug,
+        )
+    }
+    .map_err(|msg| panic_message(&msg))?;
+    if json_result.is_empty() {
+        return Ok(json_result);
+    }
+    let value = ValueRef::from_json(&json_result).map_err(|err| err.to_string())?;
+    let (_, yaml_result) = value.plan();
+    Ok(yaml_result)
+}
+
+/// Format the panic info JSON string as `file:line:col: message`.
+fn panic_message(msg: &str) -> String {
+    match serde_json::from_str::<PanicInfo>(msg) {
+        Ok(panic_info) if panic_info.kcl_file.is_empty() => panic_info.message,
+        Ok(panic_info) => format!(
+            "{}:{}:{}: {}",
+            panic_info.kcl_file, panic_info.kcl_line, panic_info.kcl_col, panic_info.message
+        ),
+        Err(_) => msg.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test_kcl_main {
+    use crate::*;
+
+    #[test]
+    fn test_main_args_parse() {
+        let settings =
+            std::env::temp_dir().join(format!("kcl_main_settings_{}.yaml", std::process::id()));
+        std::fs::write(
+            &settings,
+            "kcl_cli_configs:\n  disable_none: true\nkcl_options:\n  - key: app\n    value: kcl\n  - key: replicas\n    value: 3\n",
+        )
+        .unwrap();
+        let args: Vec<String> = [
+            "-D",
+            "env=prod",
+            "-Y",
+            settings.to_str().unwrap(),
+            "-Dregion=cn=1",
+            "-r",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        let main_args = MainArgs::parse(&args).unwrap();
+        std::fs::remove_file(&settings).unwrap();
+        assert_eq!(
+            main_args,
+            MainArgs {
+                options: vec![
+                    ("app".to_string(), "kcl".to_string()),
+                    ("replicas".to_string(), "3".to_string()),
+                    ("env".to_string(), "prod".to_string()),
+                    ("region".to_string(), "cn=1".to_string()),
+                ],
+                strict_range_check: true,
+                disable_none: true,
+                debug: false,
+                help: false,
+            }
+        );
+        assert!(MainArgs::parse(&["-D".to_string()]).is_err());
+        assert!(MainArgs::parse(&["-D".to_string(), "env".to_string()]).is_err());
+        assert!(MainArgs::parse(&["main.k".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_main_args_parse_with_build_args() {
+        let settings = std::env::temp_dir().join(format!(
+            "kcl_main_build_settings_{}.yaml",
+            std::process::id()
+        ));
+        std::fs::write(
+            &settings,
+            "kcl_cli_configs:\n  disable_none: false\nkcl_options:\n  - key: env\n    value: test\n",
+        )
+        .unwrap();
+        let to_args =
+            |args: &[&str]| -> Vec<String> { args.iter().map(|arg| arg.to_string()).collect() };
+        let build_args = to_args(&["-D", "env=prod", "-D", "region=cn", "-n"]);
+        let args = to_args(&["-Dregion=us", "-Y", settings.to_str().unwrap()]);
+        let main_args = MainArgs::parse_with_build_args(&build_args, &args).unwrap();
+        std::fs::remove_file(&settings).unwrap();
+        // The build time options are overridden by the run time settings files and
+        // then the run time `-D` options.
+        assert_eq!(
+            main_args,
+            MainArgs {
+                options: vec![
+                    ("env".to_string(), "prod".to_string()),
+                    ("region".to_string(), "cn".to_string()),
+                    ("env".to_string(), "test".to_string()),
+                    ("region".to_string(), "us".to_string()),
+                ],
+                strict_range_check: false,
+                disable_none: false,
+                debug: false,
+                help: false,
+            }
+        );
+    }
+}
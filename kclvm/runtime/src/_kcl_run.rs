@@ -37,6 +37,91 @@ pub type kclvm_float_t = f64;
 
 // const SHOULD_PROFILE: bool = false;
 
+/// The size of the result and the warning buffers of [kcl_run_with_options].
+const RUN_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// The signature of the runtime entry [_kcl_run], which is the one of the
+/// current process or of a loaded KCL program library.
+pub type KclRunFn = unsafe extern "C" fn(
+    kclvm_main_ptr: u64, // main.k => kclvm_main
+    option_len: kclvm_size_t,
+    option_keys: *const *const kclvm_char_t,
+    option_values: *const *const kclvm_char_t,
+    strict_range_check: i32,
+    disable_none: i32,
+    disable_schema_check: i32,
+    list_option_mode: i32,
+    debug_mode: i32,
+    result_buffer_len: kclvm_size_t,
+    result_buffer: *mut kclvm_char_t,
+    warn_buffer_len: kclvm_size_t,
+    warn_buffer: *mut kclvm_char_t,
+) -> kclvm_size_t;
+
+/// Run the compiled `kclvm_main` function at the address `kclvm_main_ptr`
+/// through the runtime entry `kcl_run` with the top-level arguments `options`.
+/// Returns the JSON result, or the panic info JSON string of the runtime error.
+///
+/// # Safety
+///
+/// `kclvm_main_ptr` must be the address of the compiled `kclvm_main` function
+/// which is linked with the runtime of `kcl_run`.
+pub unsafe fn kcl_run_with_options(
+    kcl_run: KclRunFn,
+    kclvm_main_ptr: u64,
+    options: &[(String, String)],
+    strict_range_check: bool,
+    disable_none: bool,
+    debug_mode: bool,
+) -> Result<String, String> {
+    let c_options = options
+        .iter()
+        .map(|(key, value)| {
+            Ok((
+                std::ffi::CString::new(key.as_str()).map_err(|err| err.to_string())?,
+                std::ffi::CString::new(value.as_str()).map_err(|err| err.to_string())?,
+            ))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    // The key and value arrays are terminated by null pointers.
+    let mut option_keys: Vec<*const kclvm_char_t> =
+        c_options.iter().map(|(key, _)| key.as_ptr()).collect();
+    option_keys.push(std::ptr::null());
+    let mut option_values: Vec<*const kclvm_char_t> =
+        c_options.iter().map(|(_, value)| value.as_ptr()).collect();
+    option_values.push(std::ptr::null());
+
+    let mut result = vec![0u8; RUN_BUFFER_SIZE];
+    let mut warn_data = vec![0u8; RUN_BUFFER_SIZE];
+    let n = kcl_run(
+        kclvm_main_ptr,
+        c_options.len() as kclvm_size_t,
+        option_keys.as_ptr(),
+        option_values.as_ptr(),
+        strict_range_check as i32,
+        disable_none as i32,
+        0, // disable_schema_check: todo
+        0, // list_option_mode: todo
+        debug_mode as i32,
+        result.len() as kclvm_size_t - 1,
+        result.as_mut_ptr() as *mut kclvm_char_t,
+        warn_data.len() as kclvm_size_t - 1,
+        warn_data.as_mut_ptr() as *mut kclvm_char_t,
+    );
+    if n == 0 {
+        Ok(String::new())
+    } else if n < 0 {
+        Err(String::from_utf8_lossy(&warn_data[0..(0 - n) as usize]).to_string())
+    } else {
+        let json_result = String::from_utf8_lossy(&result[0..n as usize]).to_string();
+        let value = ValueRef::from_json(&json_result).map_err(|err| err.to_string())?;
+        match value.get_by_key("__kcl_PanicInfo__") {
+            Some(panic_info) if panic_info.is_truthy() => Err(json_result),
+            _ => Ok(json_result),
+        }
+    }
+}
+
 #[no_mangle]
 #[runtime_fn]
 pub extern "C" fn _kcl_run(
@@ -89,6 +89,15 @@ pub enum ApiFunc {
     kclvm_builtin_sum,
     kclvm_builtin_typeof,
     kclvm_builtin_zip,
+    kclvm_collection_deep_diff,
+    kclvm_collection_distinct,
+    kclvm_collection_flatten,
+    kclvm_collection_get,
+    kclvm_collection_group_by,
+    kclvm_collection_json_patch,
+    kclvm_collection_merge_patch,
+    kclvm_collection_set,
+    kclvm_collection_union_all,
     kclvm_config_attr_map,
     kclvm_context_args_clear,
     kclvm_context_args_get,
@@ -117,12 +126,16 @@ pub enum ApiFunc {
     kclvm_context_symbol_num,
     kclvm_context_symbol_value,
     kclvm_convert_collection_value,
+    kclvm_crypto_blake3,
+    kclvm_crypto_filesha256,
+    kclvm_crypto_hmac_sha256,
     kclvm_crypto_md5,
     kclvm_crypto_sha1,
     kclvm_crypto_sha224,
     kclvm_crypto_sha256,
     kclvm_crypto_sha384,
     kclvm_crypto_sha512,
+    kclvm_crypto_uuid,
     kclvm_datetime_date,
     kclvm_datetime_now,
     kclvm_datetime_ticks,
@@ -182,6 +195,7 @@ pub enum ApiFunc {
     kclvm_list_resize,
     kclvm_list_set,
     kclvm_malloc,
+    kclvm_manifests_yaml_stream,
     kclvm_math_ceil,
     kclvm_math_exp,
     kclvm_math_expm1,
@@ -198,10 +212,14 @@ pub enum ApiFunc {
     kclvm_math_modf,
     kclvm_math_pow,
     kclvm_math_sqrt,
+    kclvm_net_CIDR_contains,
+    kclvm_net_CIDR_host,
+    kclvm_net_CIDR_subnets,
     kclvm_net_IP_string,
     kclvm_net_fqdn,
     kclvm_net_is_IP,
     kclvm_net_is_IPv4,
+    kclvm_net_is_IPv6,
     kclvm_net_is_global_unicast_IP,
     kclvm_net_is_interface_local_multicast_IP,
     kclvm_net_is_link_local_multicast_IP,
@@ -210,6 +228,7 @@ pub enum ApiFunc {
     kclvm_net_is_multicast_IP,
     kclvm_net_is_unspecified_IP,
     kclvm_net_join_host_port,
+    kclvm_net_parse_CIDR,
     kclvm_net_parse_IP,
     kclvm_net_split_host_port,
     kclvm_net_to_IP16,
@@ -232,7 +251,12 @@ pub enum ApiFunc {
     kclvm_schema_optional_check,
     kclvm_schema_value_check,
     kclvm_schema_value_new,
+    kclvm_semver_compare,
+    kclvm_semver_is_valid,
+    kclvm_semver_match,
+    kclvm_semver_parse,
     kclvm_strlen,
+    kclvm_template_execute,
     kclvm_testing_arguments,
     kclvm_testing_setting_file,
     kclvm_type_BoolLit_value,
@@ -361,7 +385,6 @@ pub enum ApiFunc {
     kclvm_value_unary_not,
     kclvm_value_unary_plus,
     kclvm_value_union,
-    kclvm_value_union_all,
     kclvm_yaml_decode,
     kclvm_yaml_dump_to_file,
     kclvm_yaml_encode,
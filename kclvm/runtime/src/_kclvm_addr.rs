@@ -68,6 +68,15 @@ pub fn _kclvm_get_fn_ptr_by_name(name: &str) -> u64 {
         "kclvm_builtin_sum" => crate::kclvm_builtin_sum as *const () as u64,
         "kclvm_builtin_typeof" => crate::kclvm_builtin_typeof as *const () as u64,
         "kclvm_builtin_zip" => crate::kclvm_builtin_zip as *const () as u64,
+        "kclvm_collection_deep_diff" => crate::kclvm_collection_deep_diff as *const () as u64,
+        "kclvm_collection_distinct" => crate::kclvm_collection_distinct as *const () as u64,
+        "kclvm_collection_flatten" => crate::kclvm_collection_flatten as *const () as u64,
+        "kclvm_collection_get" => crate::kclvm_collection_get as *const () as u64,
+        "kclvm_collection_group_by" => crate::kclvm_collection_group_by as *const () as u64,
+        "kclvm_collection_json_patch" => crate::kclvm_collection_json_patch as *const () as u64,
+        "kclvm_collection_merge_patch" => crate::kclvm_collection_merge_patch as *const () as u64,
+        "kclvm_collection_set" => crate::kclvm_collection_set as *const () as u64,
+        "kclvm_collection_union_all" => crate::kclvm_collection_union_all as *const () as u64,
         "kclvm_config_attr_map" => crate::kclvm_config_attr_map as *const () as u64,
         "kclvm_context_args_clear" => crate::kclvm_context_args_clear as *const () as u64,
         "kclvm_context_args_get" => crate::kclvm_context_args_get as *const () as u64,
@@ -116,12 +125,16 @@ pub fn _kclvm_get_fn_ptr_by_name(name: &str) -> u64 {
         "kclvm_convert_collection_value" => {
             crate::kclvm_convert_collection_value as *const () as u64
         }
+        "kclvm_crypto_blake3" => crate::kclvm_crypto_blake3 as *const () as u64,
+        "kclvm_crypto_filesha256" => crate::kclvm_crypto_filesha256 as *const () as u64,
+        "kclvm_crypto_hmac_sha256" => crate::kclvm_crypto_hmac_sha256 as *const () as u64,
         "kclvm_crypto_md5" => crate::kclvm_crypto_md5 as *const () as u64,
         "kclvm_crypto_sha1" => crate::kclvm_crypto_sha1 as *const () as u64,
         "kclvm_crypto_sha224" => crate::kclvm_crypto_sha224 as *const () as u64,
         "kclvm_crypto_sha256" => crate::kclvm_crypto_sha256 as *const () as u64,
         "kclvm_crypto_sha384" => crate::kclvm_crypto_sha384 as *const () as u64,
         "kclvm_crypto_sha512" => crate::kclvm_crypto_sha512 as *const () as u64,
+        "kclvm_crypto_uuid" => crate::kclvm_crypto_uuid as *const () as u64,
         "kclvm_datetime_date" => crate::kclvm_datetime_date as *const () as u64,
         "kclvm_datetime_now" => crate::kclvm_datetime_now as *const () as u64,
         "kclvm_datetime_ticks" => crate::kclvm_datetime_ticks as *const () as u64,
@@ -187,6 +200,7 @@ pub fn _kclvm_get_fn_ptr_by_name(name: &str) -> u64 {
         "kclvm_list_resize" => crate::kclvm_list_resize as *const () as u64,
         "kclvm_list_set" => crate::kclvm_list_set as *const () as u64,
         "kclvm_malloc" => crate::kclvm_malloc as *const () as u64,
+        "kclvm_manifests_yaml_stream" => crate::kclvm_manifests_yaml_stream as *const () as u64,
         "kclvm_math_ceil" => crate::kclvm_math_ceil as *const () as u64,
         "kclvm_math_exp" => crate::kclvm_math_exp as *const () as u64,
         "kclvm_math_expm1" => crate::kclvm_math_expm1 as *const () as u64,
@@ -203,10 +217,14 @@ pub fn _kclvm_get_fn_ptr_by_name(name: &str) -> u64 {
         "kclvm_math_modf" => crate::kclvm_math_modf as *const () as u64,
         "kclvm_math_pow" => crate::kclvm_math_pow as *const () as u64,
         "kclvm_math_sqrt" => crate::kclvm_math_sqrt as *const () as u64,
+        "kclvm_net_CIDR_contains" => crate::kclvm_net_CIDR_contains as *const () as u64,
+        "kclvm_net_CIDR_host" => crate::kclvm_net_CIDR_host as *const () as u64,
+        "kclvm_net_CIDR_subnets" => crate::kclvm_net_CIDR_subnets as *const () as u64,
         "kclvm_net_IP_string" => crate::kclvm_net_IP_string as *const () as u64,
         "kclvm_net_fqdn" => crate::kclvm_net_fqdn as *const () as u64,
         "kclvm_net_is_IP" => crate::kclvm_net_is_IP as *const () as u64,
         "kclvm_net_is_IPv4" => crate::kclvm_net_is_IPv4 as *const () as u64,
+        "kclvm_net_is_IPv6" => crate::kclvm_net_is_IPv6 as *const () as u64,
         "kclvm_net_is_global_unicast_IP" => {
             crate::kclvm_net_is_global_unicast_IP as *const () as u64
         }
@@ -223,6 +241,7 @@ pub fn _kclvm_get_fn_ptr_by_name(name: &str) -> u64 {
         "kclvm_net_is_multicast_IP" => crate::kclvm_net_is_multicast_IP as *const () as u64,
         "kclvm_net_is_unspecified_IP" => crate::kclvm_net_is_unspecified_IP as *const () as u64,
         "kclvm_net_join_host_port" => crate::kclvm_net_join_host_port as *const () as u64,
+        "kclvm_net_parse_CIDR" => crate::kclvm_net_parse_CIDR as *const () as u64,
         "kclvm_net_parse_IP" => crate::kclvm_net_parse_IP as *const () as u64,
         "kclvm_net_split_host_port" => crate::kclvm_net_split_host_port as *const () as u64,
         "kclvm_net_to_IP16" => crate::kclvm_net_to_IP16 as *const () as u64,
@@ -247,7 +266,12 @@ pub fn _kclvm_get_fn_ptr_by_name(name: &str) -> u64 {
         "kclvm_schema_optional_check" => crate::kclvm_schema_optional_check as *const () as u64,
         "kclvm_schema_value_check" => crate::kclvm_schema_value_check as *const () as u64,
         "kclvm_schema_value_new" => crate::kclvm_schema_value_new as *const () as u64,
+        "kclvm_semver_compare" => crate::kclvm_semver_compare as *const () as u64,
+        "kclvm_semver_is_valid" => crate::kclvm_semver_is_valid as *const () as u64,
+        "kclvm_semver_match" => crate::kclvm_semver_match as *const () as u64,
+        "kclvm_semver_parse" => crate::kclvm_semver_parse as *const () as u64,
         "kclvm_strlen" => crate::kclvm_strlen as *const () as u64,
+        "kclvm_template_execute" => crate::kclvm_template_execute as *const () as u64,
         "kclvm_testing_arguments" => crate::kclvm_testing_arguments as *const () as u64,
         "kclvm_testing_setting_file" => crate::kclvm_testing_setting_file as *const () as u64,
         "kclvm_type_BoolLit_value" => crate::kclvm_type_BoolLit_value as *const () as u64,
@@ -394,10 +418,9 @@ pub fn _kclvm_get_fn_ptr_by_name(name: &str) -> u64 {
         "kclvm_value_unary_not" => crate::kclvm_value_unary_not as *const () as u64,
         "kclvm_value_unary_plus" => crate::kclvm_value_unary_plus as *const () as u64,
         "kclvm_value_union" => crate::kclvm_value_union as *const () as u64,
-        "kclvm_value_union_all" => crate::kclvm_value_union_all as *const () as u64,
         "kclvm_yaml_decode" => crate::kclvm_yaml_decode as *const () as u64,
         "kclvm_yaml_dump_to_file" => crate::kclvm_yaml_dump_to_file as *const () as u64,
         "kclvm_yaml_encode" => crate::kclvm_yaml_encode as *const () as u64,
-        _ => panic!("unknown {}", name),
+        _ => 0,
     }
 }
@@ -281,7 +281,7 @@ pub struct OptionHelp {
 }
 
 #[allow(non_snake_case)]
-#[derive(PartialEq, Clone, Default, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Hash, Clone, Default, Debug, Serialize, Deserialize)]
 pub struct PanicInfo {
     pub __kcl_PanicInfo__: bool, // "__kcl_PanicInfo__"
 
@@ -369,6 +369,8 @@ pub struct Context {
 
     pub option_helps: Vec<OptionHelp>,
     pub buffer: ContextBuffer,
+    /// The documents emitted by `manifests.yaml_stream`, they replace the program output.
+    pub manifests_documents: Option<ValueRef>,
     /// objects is to store all KCL object pointers.
     pub objects: IndexSet<usize>,
 }
@@ -1,4 +1,13 @@
 //! KCL collection system module
+//! collection.union_all(configs: [{:}]) -> {:}
+//! collection.get(obj: any, path: str, default: any = None) -> any
+//! collection.set(obj: any, path: str, value: any) -> any
+//! collection.merge_patch(target: any, patch: any) -> any
+//! collection.json_patch(obj: any, patch: [{str:}]) -> any
+//! collection.flatten(value: any, sep: str = ".") -> any
+//! collection.group_by(items: [any], key: str) -> {str:[any]}
+//! collection.distinct(items: [any], key: str = "") -> [any]
+//! collection.deep_diff(a: any, b: any) -> [{str:}]
 //!
 //! Copyright 2021 The KCL Authors. All rights reserved.
 
@@ -7,9 +16,11 @@ use crate::*;
 #[allow(non_camel_case_types)]
 type kclvm_value_ref_t = ValueRef;
 
+// union_all(configs: [{:}]) -> {:}
+
 #[no_mangle]
 #[runtime_fn]
-pub extern "C" fn kclvm_value_union_all(
+pub extern "C" fn kclvm_collection_union_all(
     _ctx: *mut kclvm_context_t,
     args: *const kclvm_value_ref_t,
     _kwargs: *const kclvm_value_ref_t,
@@ -33,3 +44,965 @@ pub extern "C" fn kclvm_value_union_all(
     }
     panic!("union_all() takes at least 1 argument (0 given)")
 }
+
+// get(obj: any, path: str, default: any = None) -> any
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_collection_get(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+    let kwargs = ptr_as_ref(kwargs);
+
+    let obj = match args.arg_0().or_else(|| kwargs.kwarg("obj")) {
+        Some(obj) => obj,
+        None => panic!("get() missing 2 required positional arguments: 'obj' and 'path'"),
+    };
+    let path = match args.arg_i(1).or_else(|| kwargs.kwarg("path")) {
+        Some(path) if path.is_str() => path.as_str(),
+        Some(path) => panic!("get() path must be str, not {}", path.type_str()),
+        None => panic!("get() missing 1 required positional argument: 'path'"),
+    };
+    let default = args
+        .arg_i(2)
+        .or_else(|| kwargs.kwarg("default"))
+        .cloned()
+        .unwrap_or_else(ValueRef::none);
+    let keys = parse_path_or_panic("get", &path);
+    match get_by_keys(obj, &keys, Indexing::Path) {
+        Some(value) => value.clone().into_raw(),
+        None => default.into_raw(),
+    }
+}
+
+// set(obj: any, path: str, value: any) -> any
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_collection_set(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+    let kwargs = ptr_as_ref(kwargs);
+
+    let obj = args.arg_0().or_else(|| kwargs.kwarg("obj"));
+    let path = args.arg_i(1).or_else(|| kwargs.kwarg("path"));
+    let value = args.arg_i(2).or_else(|| kwargs.kwarg("value"));
+    match (obj, path, value) {
+        (Some(obj), Some(path), Some(value)) => {
+            if !path.is_str() {
+                panic!("set() path must be str, not {}", path.type_str());
+            }
+            let path = path.as_str();
+            let keys = parse_path_or_panic("set", &path);
+            let mut result = obj.deep_copy();
+            if let Err(err) = set_by_keys(&mut result, &keys, value, true, Indexing::Path) {
+                panic!("set() invalid path '{}': {}", path, err);
+            }
+            result.into_raw()
+        }
+        _ => panic!("set() takes exactly 3 arguments: 'obj', 'path' and 'value'"),
+    }
+}
+
+// merge_patch(target: any, patch: any) -> any
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_collection_merge_patch(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+    let kwargs = ptr_as_ref(kwargs);
+
+    let target = args.arg_0().or_else(|| kwargs.kwarg("target"));
+    let patch = args.arg_i(1).or_else(|| kwargs.kwarg("patch"));
+    match (target, patch) {
+        (Some(target), Some(patch)) => merge_patch(target, patch).into_raw(),
+        _ => panic!("merge_patch() takes exactly 2 arguments: 'target' and 'patch'"),
+    }
+}
+
+// json_patch(obj: any, patch: [{str:}]) -> any
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_collection_json_patch(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+    let kwargs = ptr_as_ref(kwargs);
+
+    let obj = args.arg_0().or_else(|| kwargs.kwarg("obj"));
+    let patch = args.arg_i(1).or_else(|| kwargs.kwarg("patch"));
+    match (obj, patch) {
+        (Some(obj), Some(patch)) => {
+            if !patch.is_list() {
+                panic!(
+                    "json_patch() patch must be a list of operations, not {}",
+                    patch.type_str()
+                );
+            }
+            match json_patch(obj, patch) {
+                Ok(result) => result.into_raw(),
+                Err(err) => panic!("json_patch() {}", err),
+            }
+        }
+        _ => panic!("json_patch() takes exactly 2 arguments: 'obj' and 'patch'"),
+    }
+}
+
+// flatten(value: any, sep: str = ".") -> any
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_collection_flatten(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+    let kwargs = ptr_as_ref(kwargs);
+
+    let value = match args.arg_0().or_else(|| kwargs.kwarg("value")) {
+        Some(value) => value,
+        None => panic!("flatten() missing 1 required positional argument: 'value'"),
+    };
+    let sep = args
+        .arg_i_str(1, None)
+        .or_else(|| kwargs.kwarg_str("sep", None))
+        .unwrap_or_else(|| ".".to_string());
+    if value.is_list() {
+        let mut result = ValueRef::list(None);
+        flatten_list(value, &mut result);
+        result.into_raw()
+    } else if value.is_config() {
+        let mut result = ValueRef::dict(None);
+        flatten_dict(value, "", &sep, &mut result);
+        result.into_raw()
+    } else {
+        panic!(
+            "flatten() value must be a list or dict, not {}",
+            value.type_str()
+        )
+    }
+}
+
+// group_by(items: [any], key: str) -> {str:[any]}
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_collection_group_by(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+    let kwargs = ptr_as_ref(kwargs);
+
+    let items = args.arg_0().or_else(|| kwargs.kwarg("items"));
+    let key = args
+        .arg_i_str(1, None)
+        .or_else(|| kwargs.kwarg_str("key", None));
+    match (items, key) {
+        (Some(items), Some(key)) => {
+            if !items.is_list() {
+                panic!("group_by() items must be a list, not {}", items.type_str());
+            }
+            let keys = parse_path_or_panic("group_by", &key);
+            let mut result = ValueRef::dict(None);
+            for item in &items.as_list_ref().values {
+                let group = group_key(item, &keys);
+                match result.get_mut_by_key(&group) {
+                    Some(list) => list.list_append(item),
+                    None => result.dict_update_key_value(&group, ValueRef::list(Some(&[item]))),
+                }
+            }
+            result.into_raw()
+        }
+        _ => panic!("group_by() takes exactly 2 arguments: 'items' and 'key'"),
+    }
+}
+
+// distinct(items: [any], key: str = "") -> [any]
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_collection_distinct(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+    let kwargs = ptr_as_ref(kwargs);
+
+    let items = match args.arg_0().or_else(|| kwargs.kwarg("items")) {
+        Some(items) if items.is_list() => items,
+        Some(items) => panic!("distinct() items must be a list, not {}", items.type_str()),
+        None => panic!("distinct() missing 1 required positional argument: 'items'"),
+    };
+    let key = args
+        .arg_i_str(1, None)
+        .or_else(|| kwargs.kwarg_str("key", None))
+        .unwrap_or_default();
+    let keys = if key.is_empty() {
+        vec![]
+    } else {
+        parse_path_or_panic("distinct", &key)
+    };
+    let mut result = ValueRef::list(None);
+    let mut seen: Vec<ValueRef> = vec![];
+    for item in &items.as_list_ref().values {
+        let id = if keys.is_empty() {
+            item.clone()
+        } else {
+            get_by_keys(item, &keys, Indexing::Path)
+                .cloned()
+                .unwrap_or_else(ValueRef::none)
+        };
+        if !seen.iter().any(|v| v.cmp_equal(&id)) {
+            seen.push(id);
+            result.list_append(item);
+        }
+    }
+    result.into_raw()
+}
+
+// deep_diff(a: any, b: any) -> [{str:}]
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_collection_deep_diff(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+    let kwargs = ptr_as_ref(kwargs);
+
+    let a = args.arg_0().or_else(|| kwargs.kwarg("a"));
+    let b = args.arg_i(1).or_else(|| kwargs.kwarg("b"));
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let mut ops = ValueRef::list(None);
+            deep_diff(a, b, "", &mut ops);
+            ops.into_raw()
+        }
+        _ => panic!("deep_diff() takes exactly 2 arguments: 'a' and 'b'"),
+    }
+}
+
+/// Parse a path such as `a.b[0].c` or `metadata.labels["app.kubernetes.io/name"]`
+/// into keys, list indexes are kept as integer strings such as `0` and `-1`.
+fn parse_path(path: &str) -> Result<Vec<String>, String> {
+    let chars: Vec<char> = path.chars().collect();
+    let mut keys = vec![];
+    let mut key = String::new();
+    // Whether the previous token is a closed bracket, e.g., `a[0]` or `a[0].b`
+    let mut after_bracket = false;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '.' => {
+                if key.is_empty() && !after_bracket {
+                    return Err(format!("empty key at position {}", i));
+                }
+                if !key.is_empty() {
+                    keys.push(std::mem::take(&mut key));
+                }
+                after_bracket = false;
+                i += 1;
+                if i == chars.len() {
+                    return Err("empty key at the end".to_string());
+                }
+            }
+            '[' => {
+                if !key.is_empty() {
+                    keys.push(std::mem::take(&mut key));
+                }
+                let close = match chars[i..].iter().position(|c| *c == ']') {
+                    Some(close) => i + close,
+                    None => return Err(format!("unclosed '[' at position {}", i)),
+                };
+                let index: String = chars[i + 1..close].iter().collect();
+                let index = index.trim();
+                let quoted = index.len() >= 2
+                    && ((index.starts_with('"') && index.ends_with('"'))
+                        || (index.starts_with('\'') && index.ends_with('\'')));
+                if quoted {
+                    keys.push(index[1..index.len() - 1].to_string());
+                } else if index.parse::<i64>().is_ok() {
+                    keys.push(index.to_string());
+                } else {
+                    return Err(format!(
+                        "invalid index '{}', expected an integer or a quoted key",
+                        index
+                    ));
+                }
+                after_bracket = true;
+                i = close + 1;
+            }
+            c => {
+                if after_bracket {
+                    return Err(format!("expected '.' or '[' at position {}", i));
+                }
+                key.push(c);
+                i += 1;
+            }
+        }
+    }
+    if !key.is_empty() {
+        keys.push(key);
+    }
+    if keys.is_empty() {
+        return Err("empty path".to_string());
+    }
+    Ok(keys)
+}
+
+fn parse_path_or_panic(func_name: &str, path: &str) -> Vec<String> {
+    match parse_path(path) {
+        Ok(keys) => keys,
+        Err(err) => panic!("{}() invalid path '{}': {}", func_name, path, err),
+    }
+}
+
+/// Parse a JSON pointer (RFC 6901) such as `/a/b/0` into keys.
+fn parse_json_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(vec![]);
+    }
+    match pointer.strip_prefix('/') {
+        Some(pointer) => Ok(pointer
+            .split('/')
+            .map(|key| key.replace("~1", "/").replace("~0", "~"))
+            .collect()),
+        None => Err(format!(
+            "invalid JSON pointer '{}', expected a leading '/'",
+            pointer
+        )),
+    }
+}
+
+fn to_json_pointer_key(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// How the list index keys are resolved.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Indexing {
+    /// The keys of dotted paths, negative indexes count from the end of the list.
+    Path,
+    /// The keys of JSON pointers (RFC 6901), which are decimals without signs and leading zeros.
+    Pointer,
+}
+
+/// Resolve a list index key.
+fn list_index(key: &str, len: usize, indexing: Indexing) -> Option<usize> {
+    let index = match indexing {
+        Indexing::Path => {
+            let index = key.parse::<i64>().ok()?;
+            if index < 0 {
+                index + len as i64
+            } else {
+                index
+            }
+        }
+        Indexing::Pointer => pointer_index(key)? as i64,
+    };
+    if index >= 0 && (index as usize) < len {
+        Some(index as usize)
+    } else {
+        None
+    }
+}
+
+/// Parse a JSON pointer array index, which is `0` or digits without a leading zero.
+fn pointer_index(key: &str) -> Option<usize> {
+    let valid = !key.is_empty()
+        && key.bytes().all(|b| b.is_ascii_digit())
+        && (key == "0" || !key.starts_with('0'));
+    if valid {
+        key.parse::<usize>().ok()
+    } else {
+        None
+    }
+}
+
+fn get_by_keys<'a>(
+    value: &'a ValueRef,
+    keys: &[String],
+    indexing: Indexing,
+) -> Option<&'a ValueRef> {
+    let mut value = value;
+    for key in keys {
+        value = if value.is_list() {
+            let list = value.as_list_ref();
+            let index = list_index(key, list.values.len(), indexing)?;
+            value.get_by_key(&index.to_string())?
+        } else {
+            value.get_by_key(key)?
+        };
+    }
+    Some(value)
+}
+
+fn get_mut_by_keys<'a>(
+    value: &'a mut ValueRef,
+    keys: &[String],
+    indexing: Indexing,
+) -> Result<&'a mut ValueRef, String> {
+    let mut value = value;
+    for key in keys {
+        value = if value.is_list() {
+            let index = match list_index(key, value.len(), indexing) {
+                Some(index) => index,
+                None => return Err(format!("list index '{}' out of range", key)),
+            };
+            value.get_mut_by_key(&index.to_string()).unwrap()
+        } else if value.is_config() {
+            match value.get_mut_by_key(key) {
+                Some(value) => value,
+                None => return Err(format!("key '{}' not found", key)),
+            }
+        } else {
+            return Err(format!(
+                "cannot get '{}' from a {} value",
+                key,
+                value.type_str()
+            ));
+        };
+    }
+    Ok(value)
+}
+
+/// Set the value at keys, missing dict keys are created when `create_missing` is true.
+fn set_by_keys(
+    obj: &mut ValueRef,
+    keys: &[String],
+    value: &ValueRef,
+    create_missing: bool,
+    indexing: Indexing,
+) -> Result<(), String> {
+    let (last, parents) = keys.split_last().unwrap();
+    let mut parent = obj;
+    for key in parents {
+        if create_missing && parent.is_config() && parent.get_by_key(key).is_none() {
+            parent.dict_update_key_value(key, ValueRef::dict(None));
+        }
+        parent = get_mut_by_keys(parent, std::slice::from_ref(key), indexing)?;
+    }
+    if parent.is_list() {
+        match list_index(last, parent.len(), indexing) {
+            Some(index) => parent.list_set(index, value),
+            None => return Err(format!("list index '{}' out of range", last)),
+        }
+    } else if parent.is_config() {
+        parent.dict_update_key_value(last, value.clone());
+    } else {
+        return Err(format!(
+            "cannot set '{}' on a {} value",
+            last,
+            parent.type_str()
+        ));
+    }
+    Ok(())
+}
+
+/// Apply a JSON merge patch (RFC 7386) and return the patched value.
+fn merge_patch(target: &ValueRef, patch: &ValueRef) -> ValueRef {
+    if !patch.is_config() {
+        return patch.deep_copy();
+    }
+    let mut result = if target.is_config() {
+        target.deep_copy()
+    } else {
+        ValueRef::dict(None)
+    };
+    for (key, value) in &patch.as_dict_ref().values {
+        if value.is_none_or_undefined() {
+            result.dict_remove(key);
+        } else {
+            let patched = match result.get_by_key(key) {
+                Some(origin) => merge_patch(origin, value),
+                None => merge_patch(&ValueRef::none(), value),
+            };
+            result.dict_update_key_value(key, patched);
+        }
+    }
+    result
+}
+
+/// Apply JSON patch (RFC 6902) operations and return the patched value.
+fn json_patch(obj: &ValueRef, patch: &ValueRef) -> Result<ValueRef, String> {
+    let mut result = obj.deep_copy();
+    for (i, operation) in patch.as_list_ref().values.iter().enumerate() {
+        if !operation.is_config() {
+            return Err(format!("operation {} must be a dict", i));
+        }
+        let field = |name: &str| -> Result<String, String> {
+            match operation.get_by_key(name) {
+                Some(v) if v.is_str() => Ok(v.as_str()),
+                _ => Err(format!("operation {} missing the str member '{}'", i, name)),
+            }
+        };
+        let op = field("op")?;
+        let path = field("path")?;
+        let keys = parse_json_pointer(&path).map_err(|err| format!("operation {}: {}", i, err))?;
+        let value = || -> Result<ValueRef, String> {
+            match operation.get_by_key("value") {
+                Some(v) => Ok(v.deep_copy()),
+                None => Err(format!("operation {} missing the member 'value'", i)),
+            }
+        };
+        let result_of = |err: String| format!("operation {} '{}' at '{}': {}", i, op, path, err);
+        match op.as_str() {
+            "add" => result = patch_add(result, &keys, value()?).map_err(result_of)?,
+            "remove" => {
+                patch_remove(&mut result, &keys).map_err(result_of)?;
+            }
+            "replace" => {
+                let new = value()?;
+                if keys.is_empty() {
+                    result = new;
+                } else if get_by_keys(&result, &keys, Indexing::Pointer).is_none() {
+                    return Err(result_of("path not found".to_string()));
+                } else {
+                    set_by_keys(&mut result, &keys, &new, false, Indexing::Pointer)
+                        .map_err(result_of)?;
+                }
+            }
+            "move" | "copy" => {
+                let from = field("from")?;
+                let from_keys =
+                    parse_json_pointer(&from).map_err(|err| format!("operation {}: {}", i, err))?;
+                let moved = match get_by_keys(&result, &from_keys, Indexing::Pointer) {
+                    Some(v) => v.deep_copy(),
+                    None => return Err(result_of(format!("path '{}' not found", from))),
+                };
+                if op == "move" {
+                    if keys.starts_with(&from_keys) && keys.len() > from_keys.len() {
+                        return Err(result_of(format!(
+                            "cannot move '{}' into one of its children",
+                            from
+                        )));
+                    }
+                    patch_remove(&mut result, &from_keys).map_err(result_of)?;
+                }
+                result = patch_add(result, &keys, moved).map_err(result_of)?;
+            }
+            "test" => {
+                let expected = value()?;
+                match get_by_keys(&result, &keys, Indexing::Pointer) {
+                    Some(v) if v.cmp_equal(&expected) => {}
+                    Some(v) => {
+                        return Err(result_of(format!(
+                            "test failed, expected {}, got {}",
+                            expected.to_json_string(),
+                            v.to_json_string()
+                        )))
+                    }
+                    None => return Err(result_of("path not found".to_string())),
+                }
+            }
+            _ => return Err(format!("operation {} has an invalid op '{}'", i, op)),
+        }
+    }
+    Ok(result)
+}
+
+fn patch_add(mut obj: ValueRef, keys: &[String], value: ValueRef) -> Result<ValueRef, String> {
+    let (last, parents) = match keys.split_last() {
+        Some(split) => split,
+        // An empty path replaces the whole document.
+        None => return Ok(value),
+    };
+    let parent = get_mut_by_keys(&mut obj, parents, Indexing::Pointer)?;
+    if parent.is_list() {
+        let len = parent.len();
+        if last == "-" {
+            parent.list_append(&value);
+        } else {
+            match pointer_index(last) {
+                Some(index) if index <= len => parent.list_insert_at(index, &value),
+                _ => return Err(format!("list index '{}' out of range", last)),
+            }
+        }
+    } else if parent.is_config() {
+        parent.dict_update_key_value(last, value);
+    } else {
+        return Err(format!(
+            "cannot add '{}' to a {} value",
+            last,
+            parent.type_str()
+        ));
+    }
+    Ok(obj)
+}
+
+fn patch_remove(obj: &mut ValueRef, keys: &[String]) -> Result<(), String> {
+    let (last, parents) = match keys.split_last() {
+        Some(split) => split,
+        None => return Err("cannot remove the whole value".to_string()),
+    };
+    let parent = get_mut_by_keys(obj, parents, Indexing::Pointer)?;
+    if parent.is_list() {
+        match pointer_index(last) {
+            Some(index) if index < parent.len() => parent.list_remove_at(index),
+            _ => return Err(format!("list index '{}' out of range", last)),
+        }
+    } else if parent.is_config() {
+        if parent.get_by_key(last).is_none() {
+            return Err(format!("key '{}' not found", last));
+        }
+        parent.dict_remove(last);
+    } else {
+        return Err(format!(
+            "cannot remove '{}' from a {} value",
+            last,
+            parent.type_str()
+        ));
+    }
+    Ok(())
+}
+
+fn flatten_list(value: &ValueRef, result: &mut ValueRef) {
+    for item in &value.as_list_ref().values {
+        if item.is_list() {
+            flatten_list(item, result);
+        } else {
+            result.list_append(item);
+        }
+    }
+}
+
+fn flatten_dict(value: &ValueRef, prefix: &str, sep: &str, result: &mut ValueRef) {
+    for (key, item) in &value.as_dict_ref().values {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}{}{}", prefix, sep, key)
+        };
+        if item.is_config() && !item.is_empty() {
+            flatten_dict(item, &key, sep, result);
+        } else {
+            result.dict_update_key_value(&key, item.clone());
+        }
+    }
+}
+
+fn group_key(item: &ValueRef, keys: &[String]) -> String {
+    match get_by_keys(item, keys, Indexing::Path) {
+        Some(v) if v.is_str() => v.as_str(),
+        Some(v) => v.to_string(),
+        None => ValueRef::none().to_string(),
+    }
+}
+
+/// Append the JSON patch (RFC 6902) operations which transform `a` into `b` to `ops`,
+/// thus `json_patch(a, deep_diff(a, b))` equals to `b`.
+fn deep_diff(a: &ValueRef, b: &ValueRef, path: &str, ops: &mut ValueRef) {
+    let push_op = |ops: &mut ValueRef, op: &str, path: &str, value: Option<&ValueRef>| {
+        let mut operation = ValueRef::dict(None);
+        operation.dict_update_key_value("op", ValueRef::str(op));
+        operation.dict_update_key_value("path", ValueRef::str(path));
+        if let Some(value) = value {
+            operation.dict_update_key_value("value", value.clone());
+        }
+        ops.list_append(&operation);
+    };
+    if a.is_config() && b.is_config() {
+        let (a_values, b_values) = (&a.as_dict_ref().values, &b.as_dict_ref().values);
+        for (key, a_value) in a_values {
+            let key_path = format!("{}/{}", path, to_json_pointer_key(key));
+            match b_values.get(key) {
+                Some(b_value) => deep_diff(a_value, b_value, &key_path, ops),
+                None => push_op(ops, "remove", &key_path, None),
+            }
+        }
+        for (key, b_value) in b_values {
+            if !a_values.contains_key(key) {
+                let key_path = format!("{}/{}", path, to_json_pointer_key(key));
+                push_op(ops, "add", &key_path, Some(b_value));
+            }
+        }
+    } else if a.is_list() && b.is_list() {
+        let (a_values, b_values) = (&a.as_list_ref().values, &b.as_list_ref().values);
+        for (i, (a_value, b_value)) in a_values.iter().zip(b_values.iter()).enumerate() {
+            deep_diff(a_value, b_value, &format!("{}/{}", path, i), ops);
+        }
+        // Remove the redundant items from the end to keep the indexes valid.
+        for i in (b_values.len()..a_values.len()).rev() {
+            push_op(ops, "remove", &format!("{}/{}", path, i), None);
+        }
+        for b_value in b_values.iter().skip(a_values.len()) {
+            push_op(ops, "add", &format!("{}/-", path), Some(b_value));
+        }
+    } else if a.type_str() != b.type_str() || !a.cmp_equal(b) {
+        push_op(ops, "replace", path, Some(b));
+    }
+}
+
+#[cfg(test)]
+mod test_collection {
+    use super::*;
+
+    fn json(s: &str) -> ValueRef {
+        ValueRef::from_json(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_path() {
+        let cases = [
+            ("a", vec!["a"]),
+            ("a.b[0].c", vec!["a", "b", "0", "c"]),
+            ("a[-1]", vec!["a", "-1"]),
+            ("a[0][1]", vec!["a", "0", "1"]),
+            (
+                "labels[\"app.kubernetes.io/name\"]",
+                vec!["labels", "app.kubernetes.io/name"],
+            ),
+            ("[0].a", vec!["0", "a"]),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(parse_path(path).unwrap(), expected, "{}", path);
+        }
+        for path in ["", "a..b", "a.", "a[x]", "a[0", "a[0]b"] {
+            assert!(parse_path(path).is_err(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_get_set_by_keys() {
+        let obj = json(r#"{"a": {"b": [{"c": 1}, {"c": 2}]}}"#);
+        let keys = parse_path("a.b[-1].c").unwrap();
+        assert_eq!(
+            get_by_keys(&obj, &keys, Indexing::Path).unwrap().as_int(),
+            2
+        );
+        assert!(get_by_keys(&obj, &parse_path("a.b[2].c").unwrap(), Indexing::Path).is_none());
+        // JSON pointers do not accept negative indexes.
+        assert!(get_by_keys(
+            &obj,
+            &parse_json_pointer("/a/b/-1/c").unwrap(),
+            Indexing::Pointer
+        )
+        .is_none());
+        assert!(get_by_keys(
+            &obj,
+            &parse_json_pointer("/a/b/01/c").unwrap(),
+            Indexing::Pointer
+        )
+        .is_none());
+        assert_eq!(
+            get_by_keys(
+                &obj,
+                &parse_json_pointer("/a/b/1/c").unwrap(),
+                Indexing::Pointer
+            )
+            .unwrap()
+            .as_int(),
+            2
+        );
+
+        let mut result = obj.deep_copy();
+        set_by_keys(&mut result, &keys, &ValueRef::int(3), true, Indexing::Path).unwrap();
+        set_by_keys(
+            &mut result,
+            &parse_path("x.y").unwrap(),
+            &ValueRef::str("z"),
+            true,
+            Indexing::Path,
+        )
+        .unwrap();
+        assert_eq!(
+            result.to_json_string(),
+            json(r#"{"a": {"b": [{"c": 1}, {"c": 3}]}, "x": {"y": "z"}}"#).to_json_string()
+        );
+        // The origin value is not changed.
+        assert_eq!(
+            get_by_keys(&obj, &keys, Indexing::Path).unwrap().as_int(),
+            2
+        );
+        assert!(set_by_keys(
+            &mut result,
+            &parse_path("a.b[5]").unwrap(),
+            &ValueRef::int(1),
+            true,
+            Indexing::Path,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        // Test cases from the appendix of RFC 7386.
+        let cases = [
+            (r#"{"a":"b"}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+            (r#"{"a":"b"}"#, r#"{"b":"c"}"#, r#"{"a":"b","b":"c"}"#),
+            (r#"{"a":"b"}"#, r#"{"a":null}"#, r#"{}"#),
+            (r#"{"a":"b","b":"c"}"#, r#"{"a":null}"#, r#"{"b":"c"}"#),
+            (r#"{"a":["b"]}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+            (r#"{"a":"c"}"#, r#"{"a":["b"]}"#, r#"{"a":["b"]}"#),
+            (
+                r#"{"a":{"b":"c"}}"#,
+                r#"{"a":{"b":"d","c":null}}"#,
+                r#"{"a":{"b":"d"}}"#,
+            ),
+            (r#"{"a":[{"b":"c"}]}"#, r#"{"a":[1]}"#, r#"{"a":[1]}"#),
+            (r#"["a","b"]"#, r#"["c","d"]"#, r#"["c","d"]"#),
+            (r#"{"a":"b"}"#, r#"["c"]"#, r#"["c"]"#),
+            (r#"{"a":"foo"}"#, r#"null"#, r#"null"#),
+            (r#"{"e":null}"#, r#"{"a":1}"#, r#"{"e":null,"a":1}"#),
+            (r#"[1,2]"#, r#"{"a":"b","c":null}"#, r#"{"a":"b"}"#),
+            (
+                r#"{}"#,
+                r#"{"a":{"bb":{"ccc":null}}}"#,
+                r#"{"a":{"bb":{}}}"#,
+            ),
+        ];
+        for (target, patch, expected) in cases {
+            assert_eq!(
+                merge_patch(&json(target), &json(patch)).to_json_string(),
+                json(expected).to_json_string(),
+                "{} {}",
+                target,
+                patch
+            );
+        }
+    }
+
+    #[test]
+    fn test_json_patch() {
+        let cases = [
+            (
+                r#"{"foo": "bar"}"#,
+                r#"[{"op": "add", "path": "/baz", "value": "qux"}]"#,
+                r#"{"foo": "bar", "baz": "qux"}"#,
+            ),
+            (
+                r#"{"foo": ["bar", "baz"]}"#,
+                r#"[{"op": "add", "path": "/foo/1", "value": "qux"}]"#,
+                r#"{"foo": ["bar", "qux", "baz"]}"#,
+            ),
+            (
+                r#"{"foo": ["bar"]}"#,
+                r#"[{"op": "add", "path": "/foo/-", "value": "qux"}]"#,
+                r#"{"foo": ["bar", "qux"]}"#,
+            ),
+            (
+                r#"{"baz": "qux", "foo": "bar"}"#,
+                r#"[{"op": "remove", "path": "/baz"}]"#,
+                r#"{"foo": "bar"}"#,
+            ),
+            (
+                r#"{"baz": "qux", "foo": "bar"}"#,
+                r#"[{"op": "replace", "path": "/baz", "value": "boo"}]"#,
+                r#"{"baz": "boo", "foo": "bar"}"#,
+            ),
+            (
+                r#"{"foo": {"bar": "baz", "waldo": "fred"}, "qux": {"corge": "grault"}}"#,
+                r#"[{"op": "move", "from": "/foo/waldo", "path": "/qux/thud"}]"#,
+                r#"{"foo": {"bar": "baz"}, "qux": {"corge": "grault", "thud": "fred"}}"#,
+            ),
+            (
+                r#"{"foo": ["all", "grass", "cows", "eat"]}"#,
+                r#"[{"op": "move", "from": "/foo/1", "path": "/foo/3"}]"#,
+                r#"{"foo": ["all", "cows", "eat", "grass"]}"#,
+            ),
+            (
+                r#"{"a/b": {"m~n": 1}}"#,
+                r#"[{"op": "copy", "from": "/a~1b/m~0n", "path": "/c"}, {"op": "test", "path": "/c", "value": 1}]"#,
+                r#"{"a/b": {"m~n": 1}, "c": 1}"#,
+            ),
+        ];
+        for (obj, patch, expected) in cases {
+            assert_eq!(
+                json_patch(&json(obj), &json(patch))
+                    .unwrap()
+                    .to_json_string(),
+                json(expected).to_json_string(),
+                "{}",
+                patch
+            );
+        }
+        let err_cases = [
+            (
+                r#"{"foo": "bar"}"#,
+                r#"[{"op": "test", "path": "/foo", "value": "baz"}]"#,
+            ),
+            (r#"{"foo": "bar"}"#, r#"[{"op": "remove", "path": "/baz"}]"#),
+            (
+                r#"{"foo": [1]}"#,
+                r#"[{"op": "add", "path": "/foo/2", "value": 1}]"#,
+            ),
+            (
+                r#"{"foo": "bar"}"#,
+                r#"[{"op": "invalid", "path": "/foo"}]"#,
+            ),
+            (
+                r#"{"foo": "bar"}"#,
+                r#"[{"op": "add", "path": "foo", "value": 1}]"#,
+            ),
+            (r#"{"foo": "bar"}"#, r#"[{"op": "add", "path": "/foo"}]"#),
+            (
+                r#"{"foo": [1, 2]}"#,
+                r#"[{"op": "replace", "path": "/foo/-1", "value": 3}]"#,
+            ),
+            (
+                r#"{"foo": [1, 2]}"#,
+                r#"[{"op": "remove", "path": "/foo/-1"}]"#,
+            ),
+            (
+                r#"{"foo": [1, 2]}"#,
+                r#"[{"op": "add", "path": "/foo/+1", "value": 3}]"#,
+            ),
+        ];
+        for (obj, patch) in err_cases {
+            assert!(json_patch(&json(obj), &json(patch)).is_err(), "{}", patch);
+        }
+    }
+
+    #[test]
+    fn test_deep_diff() {
+        let cases = [
+            (r#"{"a": 1, "b": 2}"#, r#"{"a": 1, "b": 2}"#),
+            (r#"{"a": 1, "b": [1, 2, 3]}"#, r#"{"a": "1", "c": [1]}"#),
+            (
+                r#"{"a": {"b": [1, {"c": 2}]}}"#,
+                r#"{"a": {"b": [1, {"c": 3}, 4, 5]}}"#,
+            ),
+            (r#"[1, 2, 3]"#, r#"[3]"#),
+            (r#"{"a/b": 1}"#, r#"{"a/b": 2, "m~n": 1}"#),
+            (r#"{"a": 1}"#, r#"[1]"#),
+        ];
+        for (a, b) in cases {
+            let (a, b) = (json(a), json(b));
+            let mut ops = ValueRef::list(None);
+            deep_diff(&a, &b, "", &mut ops);
+            assert_eq!(
+                json_patch(&a, &ops).unwrap().to_json_string(),
+                b.to_json_string(),
+                "{}",
+                ops.to_json_string()
+            );
+        }
+        let mut ops = ValueRef::list(None);
+        deep_diff(&json(r#"{"a": 1}"#), &json(r#"{"a": 1}"#), "", &mut ops);
+        assert_eq!(ops.len(), 0);
+        deep_diff(&json(r#"{"a": 1}"#), &json(r#"{"a": 2}"#), "", &mut ops);
+        assert_eq!(
+            ops.to_json_string(),
+            json(r#"[{"op": "replace", "path": "/a", "value": 2}]"#).to_json_string()
+        );
+    }
+}
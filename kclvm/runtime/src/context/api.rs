@@ -335,7 +335,7 @@ fn _kclvm_context_invoke(
 
     let fn_addr = _kclvm_get_fn_ptr_by_name(method);
     if fn_addr == 0 {
-        panic!("null fn ptr");
+        panic!("unknown runtime function {}", method);
     }
 
     let ptr = (&fn_addr as *const u64) as *const ()
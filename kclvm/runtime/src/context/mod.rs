@@ -65,6 +65,12 @@ impl crate::Context {
     ) -> *mut kclvm_value_ref_t {
         self.output.return_value = return_value;
 
+        // The program output is the list of the documents instead of the dict of
+        // the top-level values, see `ValueRef::plan`.
+        if let Some(documents) = &self.manifests_documents {
+            self.output.return_value = crate::ValueRef::str(&documents.to_json_string()).into_raw();
+        }
+
         if self.cfg.list_option_mode {
             self.output.return_value =
                 crate::ValueRef::str(self.list_option_help().as_str()).into_raw();
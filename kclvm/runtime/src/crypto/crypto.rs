@@ -2,11 +2,16 @@
 //!
 //! Copyright 2021 The KCL Authors. All rights reserved.
 
+extern crate blake3;
+extern crate hmac;
 extern crate md5;
 extern crate sha1;
 extern crate sha2;
+extern crate uuid;
 
+use hmac::{Hmac, Mac, NewMac};
 use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
+use uuid::Uuid;
 
 use crate::*;
 
@@ -160,3 +165,126 @@ pub extern "C" fn kclvm_crypto_sha512(
     }
     panic!("sha512() missing 1 required positional argument: 'value'");
 }
+
+// uuid(name: str = "", namespace: str = "dns") -> str
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_crypto_uuid(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+    let kwargs = ptr_as_ref(kwargs);
+
+    let name = match args.arg_i_str(0, None) {
+        Some(name) => name,
+        None => kwargs.kwarg_str("name", Some("".to_string())).unwrap(),
+    };
+    // Without a name, return a random (version 4) UUID.
+    if name.is_empty() {
+        let id = Uuid::new_v4().to_string();
+        return ValueRef::str(id.as_ref()).into_raw();
+    }
+
+    let namespace = match args.arg_i_str(1, None) {
+        Some(namespace) => namespace,
+        None => kwargs
+            .kwarg_str("namespace", Some("dns".to_string()))
+            .unwrap(),
+    };
+    let namespace = match namespace.as_str() {
+        "dns" => Uuid::NAMESPACE_DNS,
+        "url" => Uuid::NAMESPACE_URL,
+        "oid" => Uuid::NAMESPACE_OID,
+        "x500" => Uuid::NAMESPACE_X500,
+        s => match Uuid::parse_str(s) {
+            Ok(namespace) => namespace,
+            Err(_) => panic!(
+                "uuid() invalid namespace '{}', expected 'dns', 'url', 'oid', 'x500' or a UUID string",
+                s
+            ),
+        },
+    };
+    let id = Uuid::new_v5(&namespace, name.as_bytes()).to_string();
+    ValueRef::str(id.as_ref()).into_raw()
+}
+
+// hmac_sha256(key: str, msg: str) -> str
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_crypto_hmac_sha256(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    _kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+
+    if let Some(key) = args.arg_i_str(0, None) {
+        if let Some(msg) = args.arg_i_str(1, None) {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+                .expect("HMAC can take key of any size");
+            mac.update(msg.as_bytes());
+            let hex = hex_digest(&mac.finalize().into_bytes());
+            return ValueRef::str(hex.as_ref()).into_raw();
+        }
+    }
+    panic!("hmac_sha256() missing 2 required positional arguments: 'key' and 'msg'");
+}
+
+// blake3(value: str) -> str
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_crypto_blake3(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    _kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+
+    if let Some(s) = args.arg_i_str(0, None) {
+        let hex = blake3::hash(s.as_bytes()).to_hex();
+        return ValueRef::str(hex.as_str()).into_raw();
+    }
+    panic!("blake3() missing 1 required positional argument: 'value'");
+}
+
+// filesha256(filepath: str) -> str
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_crypto_filesha256(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    _kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+
+    if let Some(filepath) = args.arg_i_str(0, None) {
+        let mut file = match std::fs::File::open(&filepath) {
+            Ok(file) => file,
+            Err(err) => panic!("filesha256() failed to open file '{}': {}", filepath, err),
+        };
+        let mut hasher = Sha256::new();
+        if let Err(err) = std::io::copy(&mut file, &mut hasher) {
+            panic!("filesha256() failed to read file '{}': {}", filepath, err);
+        }
+        let hex = hex_digest(&hasher.finalize());
+        return ValueRef::str(hex.as_ref()).into_raw();
+    }
+    panic!("filesha256() missing 1 required positional argument: 'filepath'");
+}
+
+/// Returns the lowercase hex string of the digest bytes.
+fn hex_digest(digest: &[u8]) -> String {
+    use std::fmt::Write;
+
+    let mut hex = String::with_capacity(2 * digest.len());
+    for byte in digest {
+        let _ = write!(&mut hex, "{:02x}", byte);
+    }
+    hex
+}
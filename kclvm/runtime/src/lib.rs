@@ -83,6 +83,9 @@ pub use self::datetime::*;
 pub mod json;
 pub use self::json::*;
 
+pub mod manifests;
+pub use self::manifests::*;
+
 pub mod math;
 pub use self::math::*;
 
@@ -92,9 +95,15 @@ pub use self::net::*;
 pub mod regex;
 pub use self::regex::*;
 
+pub mod semver;
+pub use self::semver::*;
+
 pub mod stdlib;
 pub use self::stdlib::*;
 
+pub mod template;
+pub use self::template::*;
+
 pub mod testing;
 pub use self::testing::*;
 
@@ -107,6 +116,9 @@ pub use self::yaml::*;
 pub mod _kcl_run;
 pub use self::_kcl_run::*;
 
+pub mod _kcl_main;
+pub use self::_kcl_main::*;
+
 pub mod _kclvm;
 pub use self::_kclvm::*;
 
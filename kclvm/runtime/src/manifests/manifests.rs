@@ -0,0 +1,128 @@
+//! KCL manifests system module
+//! manifests.yaml_stream(values: [any] = [], opts: {str:} = {sort_keys = False, ignore_private = True, ignore_none = False})
+//!
+//! Copyright 2021 The KCL Authors. All rights reserved.
+
+use crate::*;
+
+#[allow(non_camel_case_types)]
+type kclvm_value_ref_t = ValueRef;
+
+// yaml_stream(values: [any] = [], opts: {str:} = {sort_keys = False, ignore_private = True, ignore_none = False})
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_manifests_yaml_stream(
+    ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+    let kwargs = ptr_as_ref(kwargs);
+    let ctx = mut_ptr_as_ref(ctx);
+
+    let values = match args.arg_0().or_else(|| kwargs.kwarg("values")) {
+        Some(values) if values.is_list() => values.clone(),
+        Some(values) if values.is_none_or_undefined() => ValueRef::list(None),
+        Some(values) => panic!(
+            "yaml_stream() values must be a list, not {}",
+            values.type_str()
+        ),
+        None => ValueRef::list(None),
+    };
+    let mut opts = JsonEncodeOptions {
+        ignore_private: true,
+        ..Default::default()
+    };
+    if let Some(opts_value) = args.arg_i(1).or_else(|| kwargs.kwarg("opts")) {
+        if !opts_value.is_config() {
+            panic!(
+                "yaml_stream() opts must be a dict, not {}",
+                opts_value.type_str()
+            );
+        }
+        if let Some(sort_keys) = opts_value.kwarg_bool("sort_keys", None) {
+            opts.sort_keys = sort_keys;
+        }
+        if let Some(ignore_private) = opts_value.kwarg_bool("ignore_private", None) {
+            opts.ignore_private = ignore_private;
+        }
+        if let Some(ignore_none) = opts_value.kwarg_bool("ignore_none", None) {
+            opts.ignore_none = ignore_none;
+        }
+    }
+    match yaml_documents(&values, &opts) {
+        Ok(documents) => ctx.manifests_documents = Some(documents),
+        Err(err) => panic!("yaml_stream() {}", err),
+    }
+    kclvm_value_None()
+}
+
+/// Returns the list of the documents of the values filtered and sorted with the options.
+pub fn yaml_documents(values: &ValueRef, opts: &JsonEncodeOptions) -> Result<ValueRef, String> {
+    let mut documents = ValueRef::list(None);
+    for value in &values.as_list_ref().values {
+        if value.is_undefined() || value.is_func() {
+            continue;
+        }
+        let json = value.to_json_string_with_option(opts);
+        match ValueRef::from_json(&json) {
+            Ok(document) => documents.list_append(&document),
+            Err(err) => return Err(format!("cannot encode {}: {}", value.type_str(), err)),
+        }
+    }
+    Ok(documents)
+}
+
+/// Encode each value in the list to a YAML document and join them with the `---` delimiter.
+pub fn yaml_stream(values: &ValueRef, opts: &JsonEncodeOptions) -> Result<String, String> {
+    Ok(yaml_documents(values, opts)?.plan().1)
+}
+
+#[cfg(test)]
+mod test_manifests {
+    use crate::*;
+
+    #[test]
+    fn test_yaml_stream() {
+        let values = ValueRef::list(Some(&[
+            &ValueRef::dict(Some(&[
+                ("kind", &ValueRef::str("Service")),
+                ("_private", &ValueRef::int(1)),
+                ("apiVersion", &ValueRef::str("v1")),
+            ])),
+            &ValueRef::dict(Some(&[("kind", &ValueRef::str("Deployment"))])),
+        ]));
+        let opts = JsonEncodeOptions {
+            ignore_private: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            yaml_stream(&values, &opts).unwrap(),
+            "kind: Service\napiVersion: v1\n---\nkind: Deployment\n"
+        );
+        let opts = JsonEncodeOptions {
+            sort_keys: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            yaml_stream(&values, &opts).unwrap(),
+            "_private: 1\napiVersion: v1\nkind: Service\n---\nkind: Deployment\n"
+        );
+        assert_eq!(yaml_stream(&ValueRef::list(None), &opts).unwrap(), "");
+    }
+
+    #[test]
+    fn test_plan_yaml_stream() {
+        let documents = ValueRef::list(Some(&[
+            &ValueRef::dict(Some(&[("kind", &ValueRef::str("Service"))])),
+            &ValueRef::dict(Some(&[("kind", &ValueRef::str("Deployment"))])),
+        ]));
+        let (json_result, yaml_result) = documents.plan();
+        assert_eq!(yaml_result, "kind: Service\n---\nkind: Deployment\n");
+        assert_eq!(
+            json_result,
+            r#"[{"kind": "Service"}, {"kind": "Deployment"}]"#
+        );
+    }
+}
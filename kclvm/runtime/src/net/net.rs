@@ -2,6 +2,7 @@
 //!
 //! Copyright 2021 The KCL Authors. All rights reserved.
 
+use std::net::IpAddr;
 use std::net::Ipv4Addr;
 use std::net::Ipv6Addr;
 use std::str::FromStr;
@@ -162,6 +163,28 @@ pub extern "C" fn kclvm_net_is_IPv4(
     panic!("is_IPv4() missing 1 required positional argument: 'ip'");
 }
 
+// is_IPv6(ip: str) -> bool
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_net_is_IPv6(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    _kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+
+    if let Some(ip) = args.arg_i_str(0, None) {
+        if let Ok(_addr) = Ipv6Addr::from_str(ip.as_ref()) {
+            return kclvm_value_True();
+        }
+
+        return kclvm_value_False();
+    }
+
+    panic!("is_IPv6() missing 1 required positional argument: 'ip'");
+}
+
 // is_IP(ip: str) -> bool
 
 #[no_mangle]
@@ -253,13 +276,13 @@ pub extern "C" fn kclvm_net_is_interface_local_multicast_IP(
     let args = ptr_as_ref(args);
 
     if let Some(ip) = args.arg_i_str(0, None) {
-        if let Ok(addr) = Ipv4Addr::from_str(ip.as_ref()) {
-            let is_site_local = false; // TODO
-            let x = is_site_local && addr.is_multicast();
-            return kclvm_value_Bool(x as i8);
+        if let Ok(_addr) = Ipv4Addr::from_str(ip.as_ref()) {
+            // IPv4 addresses have no interface-local scope.
+            return kclvm_value_False();
         }
-        if let Ok(_addr) = Ipv6Addr::from_str(ip.as_ref()) {
-            todo!("todo");
+        if let Ok(addr) = Ipv6Addr::from_str(ip.as_ref()) {
+            let x = Ipv6Addr_is_interface_local_multicast(&addr);
+            return kclvm_value_Bool(x as i8);
         }
 
         return kclvm_value_False();
@@ -281,12 +304,13 @@ pub extern "C" fn kclvm_net_is_link_local_multicast_IP(
 
     if let Some(ip) = args.arg_i_str(0, None) {
         if let Ok(addr) = Ipv4Addr::from_str(ip.as_ref()) {
-            let x = addr.is_link_local() && addr.is_multicast();
+            // The link-local multicast range 224.0.0.0/24
+            let octets = addr.octets();
+            let x = octets[0] == 224 && octets[1] == 0 && octets[2] == 0;
             return kclvm_value_Bool(x as i8);
         }
         if let Ok(addr) = Ipv6Addr::from_str(ip.as_ref()) {
-            let is_link_local = false; // TODO
-            let x = is_link_local && addr.is_multicast();
+            let x = Ipv6Addr_is_link_local_multicast(&addr);
             return kclvm_value_Bool(x as i8);
         }
 
@@ -296,6 +320,18 @@ pub extern "C" fn kclvm_net_is_link_local_multicast_IP(
     panic!("is_link_local_multicast_IP() missing 1 required positional argument: 'ip'");
 }
 
+#[allow(non_camel_case_types, non_snake_case)]
+fn Ipv6Addr_is_interface_local_multicast(_self: &Ipv6Addr) -> bool {
+    // The multicast addresses ff01::/16 with the interface-local scope
+    _self.is_multicast() && (_self.segments()[0] & 0xff0f) == 0xff01
+}
+
+#[allow(non_camel_case_types, non_snake_case)]
+fn Ipv6Addr_is_link_local_multicast(_self: &Ipv6Addr) -> bool {
+    // The multicast addresses ff02::/16 with the link-local scope
+    _self.is_multicast() && (_self.segments()[0] & 0xff0f) == 0xff02
+}
+
 // is_link_local_unicast_IP(ip: str) -> bool
 
 #[no_mangle]
@@ -416,3 +452,240 @@ pub extern "C" fn kclvm_net_is_unspecified_IP(
     }
     panic!("is_unspecified_IP() missing 1 required positional argument: 'ip'");
 }
+
+// parse_CIDR(cidr: str) -> {str:}
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_net_parse_CIDR(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    _kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+
+    if let Some(cidr) = args.arg_i_str(0, None) {
+        let net = Cidr::parse_or_panic("parse_CIDR", &cidr);
+        let mut dict = ValueRef::dict(None);
+        dict.dict_update_key_value("ip", ValueRef::str(&net.ip().to_string()));
+        dict.dict_update_key_value("prefix", ValueRef::int(net.prefix as i64));
+        dict.dict_update_key_value("network", ValueRef::str(&net.network_string()));
+        dict.dict_update_key_value("netmask", ValueRef::str(&net.netmask().to_string()));
+        dict.dict_update_key_value("first", ValueRef::str(&net.first().to_string()));
+        dict.dict_update_key_value("last", ValueRef::str(&net.last().to_string()));
+        return dict.into_raw();
+    }
+
+    panic!("parse_CIDR() missing 1 required positional argument: 'cidr'");
+}
+
+// CIDR_contains(cidr: str, ip: str) -> bool
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_net_CIDR_contains(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    _kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+
+    if let Some(cidr) = args.arg_i_str(0, None) {
+        if let Some(ip) = args.arg_i_str(1, None) {
+            let net = Cidr::parse_or_panic("CIDR_contains", &cidr);
+            if let Ok(addr) = IpAddr::from_str(ip.as_ref()) {
+                return kclvm_value_Bool(net.contains(&addr) as i8);
+            }
+            return kclvm_value_False();
+        }
+    }
+
+    panic!("CIDR_contains() missing 2 required positional arguments: 'cidr' and 'ip'");
+}
+
+// CIDR_subnets(cidr: str, newbits: int) -> [str]
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_net_CIDR_subnets(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    _kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+
+    if let Some(cidr) = args.arg_i_str(0, None) {
+        if let Some(newbits) = args.arg_i_int(1, None) {
+            let net = Cidr::parse_or_panic("CIDR_subnets", &cidr);
+            if !(0..=CIDR_MAX_SUBNET_NEWBITS).contains(&newbits) {
+                panic!(
+                    "CIDR_subnets() newbits must be in the range [0, {}], got {}",
+                    CIDR_MAX_SUBNET_NEWBITS, newbits
+                );
+            }
+            let prefix = net.prefix + newbits as u32;
+            if prefix > net.width() {
+                panic!(
+                    "CIDR_subnets() insufficient address space to extend prefix of {} by {}",
+                    net.prefix, newbits
+                );
+            }
+            let mut list = ValueRef::list(None);
+            let step = 1u128.checked_shl(net.width() - prefix).unwrap_or(0);
+            let network = net.network();
+            for i in 0..(1u128 << newbits) {
+                let subnet = Cidr {
+                    addr: network + i * step,
+                    prefix,
+                    is_ipv4: net.is_ipv4,
+                };
+                list.list_append(&ValueRef::str(&subnet.network_string()));
+            }
+            return list.into_raw();
+        }
+    }
+
+    panic!("CIDR_subnets() missing 2 required positional arguments: 'cidr' and 'newbits'");
+}
+
+// CIDR_host(cidr: str, n: int) -> str
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_net_CIDR_host(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    _kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+
+    if let Some(cidr) = args.arg_i_str(0, None) {
+        if let Some(n) = args.arg_i_int(1, None) {
+            let net = Cidr::parse_or_panic("CIDR_host", &cidr);
+            // A negative host number counts backwards from the end of the range.
+            let host = if n >= 0 {
+                net.network().checked_add(n as u128)
+            } else {
+                net.last_bits().checked_sub(n.unsigned_abs() as u128 - 1)
+            };
+            match host {
+                Some(host) if host >= net.network() && host <= net.last_bits() => {
+                    let ip = net.to_ip(host);
+                    return ValueRef::str(&ip.to_string()).into_raw();
+                }
+                _ => panic!(
+                    "CIDR_host() prefix of {} does not accommodate a host numbered {}",
+                    net.prefix, n
+                ),
+            }
+        }
+    }
+
+    panic!("CIDR_host() missing 2 required positional arguments: 'cidr' and 'n'");
+}
+
+/// The max number of additional prefix bits of `CIDR_subnets`,
+/// which limits the result to 65536 subnets.
+const CIDR_MAX_SUBNET_NEWBITS: i64 = 16;
+
+/// Cidr denotes an IPv4 or IPv6 address with a prefix length
+/// such as `192.168.0.1/24` or `2001:db8::/32`.
+struct Cidr {
+    /// The address bits, IPv4 addresses only use the lower 32 bits.
+    addr: u128,
+    prefix: u32,
+    is_ipv4: bool,
+}
+
+impl Cidr {
+    /// Parse a CIDR string, return `None` when the string is not a valid CIDR notation.
+    fn parse(cidr: &str) -> Option<Self> {
+        let (ip, prefix) = cidr.split_once('/')?;
+        let prefix = prefix.parse::<u32>().ok()?;
+        let net = match IpAddr::from_str(ip).ok()? {
+            IpAddr::V4(addr) => Cidr {
+                addr: u32::from(addr) as u128,
+                prefix,
+                is_ipv4: true,
+            },
+            IpAddr::V6(addr) => Cidr {
+                addr: u128::from(addr),
+                prefix,
+                is_ipv4: false,
+            },
+        };
+        if net.prefix > net.width() {
+            return None;
+        }
+        Some(net)
+    }
+
+    fn parse_or_panic(func_name: &str, cidr: &str) -> Self {
+        match Self::parse(cidr) {
+            Some(net) => net,
+            None => panic!("{}() invalid CIDR address: '{}'", func_name, cidr),
+        }
+    }
+
+    /// Return the address bit width, 32 for IPv4 and 128 for IPv6.
+    fn width(&self) -> u32 {
+        if self.is_ipv4 {
+            32
+        } else {
+            128
+        }
+    }
+
+    /// Return the bits of the host part.
+    fn host_mask(&self) -> u128 {
+        u128::MAX
+            .checked_shr(128 - self.width() + self.prefix)
+            .unwrap_or(0)
+    }
+
+    fn network(&self) -> u128 {
+        self.addr & !self.host_mask()
+    }
+
+    fn last_bits(&self) -> u128 {
+        self.network() | self.host_mask()
+    }
+
+    fn to_ip(&self, bits: u128) -> IpAddr {
+        if self.is_ipv4 {
+            IpAddr::V4(Ipv4Addr::from(bits as u32))
+        } else {
+            IpAddr::V6(Ipv6Addr::from(bits))
+        }
+    }
+
+    fn ip(&self) -> IpAddr {
+        self.to_ip(self.addr)
+    }
+
+    fn first(&self) -> IpAddr {
+        self.to_ip(self.network())
+    }
+
+    fn last(&self) -> IpAddr {
+        self.to_ip(self.last_bits())
+    }
+
+    fn netmask(&self) -> IpAddr {
+        let width_mask = u128::MAX.checked_shr(128 - self.width()).unwrap_or(0);
+        self.to_ip(width_mask & !self.host_mask())
+    }
+
+    fn network_string(&self) -> String {
+        format!("{}/{}", self.first(), self.prefix)
+    }
+
+    fn contains(&self, ip: &IpAddr) -> bool {
+        let bits = match ip {
+            IpAddr::V4(addr) if self.is_ipv4 => u32::from(*addr) as u128,
+            IpAddr::V6(addr) if !self.is_ipv4 => u128::from(*addr),
+            _ => return false,
+        };
+        bits & !self.host_mask() == self.network()
+    }
+}
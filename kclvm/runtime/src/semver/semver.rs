@@ -0,0 +1,126 @@
+//! KCL semver system module
+//! semver.parse(version: str) -> {str:}
+//! semver.is_valid(version: str) -> bool
+//! semver.compare(v1: str, v2: str) -> int
+//! semver.match(version: str, constraint: str) -> bool
+//!
+//! Copyright 2021 The KCL Authors. All rights reserved.
+
+use std::cmp::Ordering;
+
+use ::semver::{Version, VersionReq};
+
+use crate::*;
+
+#[allow(non_camel_case_types)]
+type kclvm_value_ref_t = ValueRef;
+
+/// Parse a semantic version string, an optional leading `v` such as `v1.2.3` is allowed.
+fn parse_version(version: &str) -> Result<Version, ::semver::Error> {
+    Version::parse(version.strip_prefix('v').unwrap_or(version))
+}
+
+fn parse_version_or_panic(func_name: &str, version: &str) -> Version {
+    match parse_version(version) {
+        Ok(v) => v,
+        Err(err) => panic!(
+            "{}() invalid semantic version '{}': {}",
+            func_name, version, err
+        ),
+    }
+}
+
+// parse(version: str) -> {str:}
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_semver_parse(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    _kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+
+    if let Some(version) = args.arg_i_str(0, None) {
+        let v = parse_version_or_panic("parse", &version);
+        let mut dict = ValueRef::dict(None);
+        dict.dict_update_key_value("major", ValueRef::int(v.major as i64));
+        dict.dict_update_key_value("minor", ValueRef::int(v.minor as i64));
+        dict.dict_update_key_value("patch", ValueRef::int(v.patch as i64));
+        dict.dict_update_key_value("pre", ValueRef::str(v.pre.as_str()));
+        dict.dict_update_key_value("build", ValueRef::str(v.build.as_str()));
+        return dict.into_raw();
+    }
+    panic!("parse() missing 1 required positional argument: 'version'");
+}
+
+// is_valid(version: str) -> bool
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_semver_is_valid(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    _kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+
+    if let Some(version) = args.arg_i_str(0, None) {
+        return kclvm_value_Bool(parse_version(&version).is_ok() as i8);
+    }
+    panic!("is_valid() missing 1 required positional argument: 'version'");
+}
+
+// compare(v1: str, v2: str) -> int
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_semver_compare(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    _kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+
+    if let Some(v1) = args.arg_i_str(0, None) {
+        if let Some(v2) = args.arg_i_str(1, None) {
+            let v1 = parse_version_or_panic("compare", &v1);
+            let v2 = parse_version_or_panic("compare", &v2);
+            // Build metadata does not figure into the version precedence.
+            let result = match v1.cmp_precedence(&v2) {
+                Ordering::Less => -1,
+                Ordering::Equal => 0,
+                Ordering::Greater => 1,
+            };
+            return ValueRef::int(result).into_raw();
+        }
+    }
+    panic!("compare() missing 2 required positional arguments: 'v1' and 'v2'");
+}
+
+// match(version: str, constraint: str) -> bool
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_semver_match(
+    _ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    _kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+
+    if let Some(version) = args.arg_i_str(0, None) {
+        if let Some(constraint) = args.arg_i_str(1, None) {
+            let v = parse_version_or_panic("match", &version);
+            let req = match VersionReq::parse(&constraint) {
+                Ok(req) => req,
+                Err(err) => panic!(
+                    "match() invalid version constraint '{}': {}",
+                    constraint, err
+                ),
+            };
+            return kclvm_value_Bool(req.matches(&v) as i8);
+        }
+    }
+    panic!("match() missing 2 required positional arguments: 'version' and 'constraint'");
+}
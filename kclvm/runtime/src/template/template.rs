@@ -0,0 +1,1302 @@
+//! KCL template system module
+//! template.execute(template: str, data: {str:} = {}) -> str
+//!
+//! The template syntax is a Handlebars style with Jinja style filters:
+//!
+//! - `{{ name }}`, `{{ server.port }}`, `{{ labels["app.kubernetes.io/name"] }}` output values.
+//! - `{{ name | upper }}`, `{{ port | default(80) }}` apply filters.
+//! - `{{#if cond}} ... {{else if cond}} ... {{else}} ... {{/if}}` and `{{#unless cond}}`.
+//! - `{{#each items as |item index|}} ... {{else}} ... {{/each}}` with `this`, `@index`,
+//!   `@key`, `@first` and `@last` in the loop body.
+//! - `{{#with value as |v|}} ... {{/with}}` changes the current context.
+//! - `{{! comment }}`, `{{!-- comment --}}` and `{{~ ... ~}}` whitespace control.
+//!
+//! Copyright 2021 The KCL Authors. All rights reserved.
+
+use std::fmt;
+
+use crate::*;
+
+#[allow(non_camel_case_types)]
+type kclvm_value_ref_t = ValueRef;
+
+// execute(template: str, data: {str:} = {}) -> str
+
+#[no_mangle]
+#[runtime_fn]
+pub extern "C" fn kclvm_template_execute(
+    ctx: *mut kclvm_context_t,
+    args: *const kclvm_value_ref_t,
+    kwargs: *const kclvm_value_ref_t,
+) -> *const kclvm_value_ref_t {
+    let args = ptr_as_ref(args);
+    let kwargs = ptr_as_ref(kwargs);
+
+    if let Some(template) = args.arg_i_str(0, None) {
+        let data = match args.arg_i(1) {
+            Some(data) => data.clone(),
+            None => match kwargs.kwarg("data") {
+                Some(data) => data.clone(),
+                None => ValueRef::dict(None),
+            },
+        };
+        match execute(&template, &data) {
+            Ok(result) => return ValueRef::str(&result).into_raw(),
+            Err(err) => {
+                let ctx = mut_ptr_as_ref(ctx);
+                ctx.set_err_type(&ErrType::EvaluationError_TYPE);
+                panic!("{}", err)
+            }
+        }
+    }
+    panic!("execute() missing 1 required positional argument: 'template'");
+}
+
+/// Render the template string with the data value.
+pub fn execute(template: &str, data: &ValueRef) -> Result<String, TemplateError> {
+    let tokens = lex(template)?;
+    let nodes = TemplateParser::new(tokens).parse()?;
+    let mut out = String::new();
+    Renderer::new(data).render(&nodes, &mut out)?;
+    Ok(out)
+}
+
+/// The position in the template source, line and column both start from 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TemplatePos {
+    pub line: usize,
+    pub col: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateError {
+    pub pos: TemplatePos,
+    pub message: String,
+}
+
+impl TemplateError {
+    fn new(pos: TemplatePos, message: &str) -> Self {
+        TemplateError {
+            pos,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "template error at line {}, column {}: {}",
+            self.pos.line, self.pos.col, self.message
+        )
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Lexer
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq)]
+enum TagKind {
+    /// `{{ expr }}`
+    Output(String),
+    /// `{{#name args}}`
+    Open(String, String),
+    /// `{{else}}` or `{{else if cond}}`
+    Else(String),
+    /// `{{/name}}`
+    Close(String),
+    /// `{{! comment }}`
+    Comment,
+}
+
+impl TagKind {
+    /// Whether the tag is removed with its line when it is the only content of the line.
+    fn is_standalone_candidate(&self) -> bool {
+        !matches!(self, TagKind::Output(_))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Tag {
+    kind: TagKind,
+    pos: TemplatePos,
+    trim_left: bool,
+    trim_right: bool,
+}
+
+/// Split the template into texts and tags, there is always a text
+/// (may be empty) before and after each tag.
+fn lex(src: &str) -> Result<(Vec<String>, Vec<Tag>), TemplateError> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(src.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let pos_of = |offset: usize| -> TemplatePos {
+        let line = match line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+        TemplatePos {
+            line: line + 1,
+            col: src[line_starts[line]..offset].chars().count() + 1,
+        }
+    };
+
+    let mut texts = vec![];
+    let mut tags = vec![];
+    let mut start = 0;
+    while let Some(open) = src[start..].find("{{").map(|i| i + start) {
+        let pos = pos_of(open);
+        texts.push(src[start..open].to_string());
+        let mut inner_start = open + 2;
+        let trim_left = src[inner_start..].starts_with('~');
+        if trim_left {
+            inner_start += 1;
+        }
+        let (inner_end, next) = if src[inner_start..].starts_with("!--") {
+            let close = src[inner_start..]
+                .find("--}}")
+                .map(|i| (i + inner_start, 0));
+            let trim_close = src[inner_start..]
+                .find("--~}}")
+                .map(|i| (i + inner_start, 1));
+            match close.into_iter().chain(trim_close).min() {
+                Some((close, trim)) => (close + 2 + trim, close + 4 + trim),
+                None => return Err(TemplateError::new(pos, "unclosed comment")),
+            }
+        } else {
+            match src[inner_start..].find("}}").map(|i| i + inner_start) {
+                Some(close) => (close, close + 2),
+                None => return Err(TemplateError::new(pos, "unclosed tag, expected '}}'")),
+            }
+        };
+        let mut content = &src[inner_start..inner_end];
+        let trim_right = content.ends_with('~');
+        if trim_right {
+            content = &content[..content.len() - 1];
+        }
+        let content = content.trim();
+        let kind = if content.starts_with('!') {
+            TagKind::Comment
+        } else if let Some(rest) = content.strip_prefix('#') {
+            let (name, args) = split_first_word(rest);
+            TagKind::Open(name.to_string(), args.to_string())
+        } else if let Some(rest) = content.strip_prefix('/') {
+            TagKind::Close(rest.trim().to_string())
+        } else if content == "else" {
+            TagKind::Else("".to_string())
+        } else if let Some(rest) = content.strip_prefix("else ") {
+            TagKind::Else(rest.trim().to_string())
+        } else if content.is_empty() {
+            return Err(TemplateError::new(pos, "empty tag"));
+        } else {
+            TagKind::Output(content.to_string())
+        };
+        tags.push(Tag {
+            kind,
+            pos,
+            trim_left,
+            trim_right,
+        });
+        start = next;
+    }
+    texts.push(src[start..].to_string());
+    strip_standalone_lines(&mut texts, &tags);
+    for (i, tag) in tags.iter().enumerate() {
+        if tag.trim_left {
+            texts[i] = texts[i].trim_end().to_string();
+        }
+        if tag.trim_right {
+            texts[i + 1] = texts[i + 1].trim_start().to_string();
+        }
+    }
+    Ok((texts, tags))
+}
+
+/// Remove the whitespaces and the line break around block tags and comments
+/// which stand alone on their line.
+fn strip_standalone_lines(texts: &mut [String], tags: &[Tag]) {
+    let last = texts.len() - 1;
+    let standalone: Vec<bool> = tags
+        .iter()
+        .enumerate()
+        .map(|(i, tag)| {
+            let prev = &texts[i];
+            let next = &texts[i + 1];
+            let prev_ok = match prev.rfind('\n') {
+                Some(n) => prev[n + 1..].trim().is_empty(),
+                None => i == 0 && prev.trim().is_empty(),
+            };
+            let next_ok = match next.find('\n') {
+                Some(n) => next[..n].trim().is_empty(),
+                None => i + 1 == last && next.trim().is_empty(),
+            };
+            tag.kind.is_standalone_candidate() && prev_ok && next_ok
+        })
+        .collect();
+    for (i, is_standalone) in standalone.iter().enumerate() {
+        if *is_standalone {
+            texts[i] = match texts[i].rfind('\n') {
+                Some(n) => texts[i][..n + 1].to_string(),
+                None => "".to_string(),
+            };
+            texts[i + 1] = match texts[i + 1].find('\n') {
+                Some(n) => texts[i + 1][n + 1..].to_string(),
+                None => "".to_string(),
+            };
+        }
+    }
+}
+
+fn split_first_word(s: &str) -> (&str, &str) {
+    let s = s.trim();
+    match s.find(char::is_whitespace) {
+        Some(i) => (&s[..i], s[i..].trim()),
+        None => (s, ""),
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Parser
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CmpOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtE,
+    Gt,
+    GtE,
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    Literal(ValueRef),
+    Var(Vec<String>),
+    Index(Box<Expr>, Box<Expr>),
+    Filter(Box<Expr>, String, Vec<Expr>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(CmpOp, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Return the expression name used in error messages.
+    fn describe(&self) -> String {
+        match self {
+            Expr::Var(path) => path.join("."),
+            Expr::Index(value, index) => format!("{}[{}]", value.describe(), index.describe()),
+            Expr::Filter(value, name, _) => format!("{} | {}", value.describe(), name),
+            Expr::Literal(value) => value_to_quoted_string(value),
+            _ => "expression".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Text(String),
+    Output(Expr, TemplatePos),
+    If(Vec<(Expr, TemplatePos, Vec<Node>)>, Vec<Node>),
+    Each(Block),
+    With(Block),
+}
+
+#[derive(Clone, Debug)]
+struct Block {
+    expr: Expr,
+    params: Vec<String>,
+    body: Vec<Node>,
+    else_body: Vec<Node>,
+    pos: TemplatePos,
+}
+
+/// The tag which stops parsing a block body.
+enum Stop {
+    End,
+    Else(String, TemplatePos),
+    Close(String, TemplatePos),
+}
+
+struct TemplateParser {
+    texts: Vec<String>,
+    tags: Vec<Tag>,
+    /// The index of the next tag, the text before it has the same index.
+    index: usize,
+    text_consumed: bool,
+}
+
+impl TemplateParser {
+    fn new((texts, tags): (Vec<String>, Vec<Tag>)) -> Self {
+        TemplateParser {
+            texts,
+            tags,
+            index: 0,
+            text_consumed: false,
+        }
+    }
+
+    fn parse(&mut self) -> Result<Vec<Node>, TemplateError> {
+        let (nodes, stop) = self.parse_body()?;
+        match stop {
+            Stop::End => Ok(nodes),
+            Stop::Else(_, pos) => Err(TemplateError::new(
+                pos,
+                "unexpected '{{else}}' outside of a block",
+            )),
+            Stop::Close(name, pos) => Err(TemplateError::new(
+                pos,
+                &format!(
+                    "unexpected '{{{{/{}}}}}' without a matching open block",
+                    name
+                ),
+            )),
+        }
+    }
+
+    fn parse_body(&mut self) -> Result<(Vec<Node>, Stop), TemplateError> {
+        let mut nodes = vec![];
+        loop {
+            if !self.text_consumed {
+                let text = &self.texts[self.index];
+                if !text.is_empty() {
+                    nodes.push(Node::Text(text.clone()));
+                }
+                self.text_consumed = true;
+            }
+            if self.index >= self.tags.len() {
+                return Ok((nodes, Stop::End));
+            }
+            let tag = self.tags[self.index].clone();
+            self.index += 1;
+            self.text_consumed = false;
+            match tag.kind {
+                TagKind::Comment => {}
+                TagKind::Output(src) => {
+                    nodes.push(Node::Output(parse_expr(&src, tag.pos)?, tag.pos))
+                }
+                TagKind::Else(rest) => return Ok((nodes, Stop::Else(rest, tag.pos))),
+                TagKind::Close(name) => return Ok((nodes, Stop::Close(name, tag.pos))),
+                TagKind::Open(name, args) => nodes.push(self.parse_block(&name, &args, tag.pos)?),
+            }
+        }
+    }
+
+    fn parse_block(
+        &mut self,
+        name: &str,
+        args: &str,
+        pos: TemplatePos,
+    ) -> Result<Node, TemplateError> {
+        if args.is_empty() {
+            return Err(TemplateError::new(
+                pos,
+                &format!("block '{{{{#{}}}}}' requires an argument", name),
+            ));
+        }
+        match name {
+            "if" | "unless" => {
+                let mut cond = parse_expr(args, pos)?;
+                if name == "unless" {
+                    cond = Expr::Not(Box::new(cond));
+                }
+                let mut cond_pos = pos;
+                let mut branches = vec![];
+                loop {
+                    let (body, stop) = self.parse_body()?;
+                    branches.push((cond, cond_pos, body));
+                    match stop {
+                        Stop::Else(rest, else_pos) => match rest.strip_prefix("if ") {
+                            Some(rest) => {
+                                cond = parse_expr(rest, else_pos)?;
+                                cond_pos = else_pos;
+                            }
+                            None if rest.is_empty() => {
+                                let (else_body, stop) = self.parse_body()?;
+                                self.expect_close(name, pos, stop)?;
+                                return Ok(Node::If(branches, else_body));
+                            }
+                            None => {
+                                return Err(TemplateError::new(
+                                    else_pos,
+                                    &format!("invalid else tag '{{{{else {}}}}}'", rest),
+                                ))
+                            }
+                        },
+                        stop => {
+                            self.expect_close(name, pos, stop)?;
+                            return Ok(Node::If(branches, vec![]));
+                        }
+                    }
+                }
+            }
+            "each" | "with" => {
+                let (expr, params) = match args.split_once(" as ") {
+                    Some((expr, params)) => (expr, parse_block_params(params, pos)?),
+                    None => (args, vec![]),
+                };
+                let expr = parse_expr(expr, pos)?;
+                let (body, stop) = self.parse_body()?;
+                let else_body = match stop {
+                    Stop::Else(rest, else_pos) => {
+                        if !rest.is_empty() {
+                            return Err(TemplateError::new(
+                                else_pos,
+                                &format!("invalid else tag '{{{{else {}}}}}'", rest),
+                            ));
+                        }
+                        let (else_body, stop) = self.parse_body()?;
+                        self.expect_close(name, pos, stop)?;
+                        else_body
+                    }
+                    stop => {
+                        self.expect_close(name, pos, stop)?;
+                        vec![]
+                    }
+                };
+                let block = Block {
+                    expr,
+                    params,
+                    body,
+                    else_body,
+                    pos,
+                };
+                Ok(if name == "each" {
+                    Node::Each(block)
+                } else {
+                    Node::With(block)
+                })
+            }
+            _ => Err(TemplateError::new(
+                pos,
+                &format!("unknown block '{{{{#{}}}}}'", name),
+            )),
+        }
+    }
+
+    fn expect_close(&self, name: &str, pos: TemplatePos, stop: Stop) -> Result<(), TemplateError> {
+        match stop {
+            Stop::Close(close, _) if close == name => Ok(()),
+            Stop::Close(close, close_pos) => Err(TemplateError::new(
+                close_pos,
+                &format!(
+                    "'{{{{/{}}}}}' does not match the open block '{{{{#{}}}}}'",
+                    close, name
+                ),
+            )),
+            Stop::Else(_, else_pos) => Err(TemplateError::new(
+                else_pos,
+                &format!("unexpected '{{{{else}}}}' in the block '{{{{#{}}}}}'", name),
+            )),
+            Stop::End => Err(TemplateError::new(
+                pos,
+                &format!("unclosed block '{{{{#{}}}}}'", name),
+            )),
+        }
+    }
+}
+
+/// Parse block params such as `|item index|`.
+fn parse_block_params(params: &str, pos: TemplatePos) -> Result<Vec<String>, TemplateError> {
+    let params = params.trim();
+    match params
+        .strip_prefix('|')
+        .and_then(|params| params.strip_suffix('|'))
+    {
+        Some(params) => {
+            let params: Vec<String> = params.split_whitespace().map(|p| p.to_string()).collect();
+            if params.is_empty() || params.len() > 2 {
+                return Err(TemplateError::new(
+                    pos,
+                    "block params expect one or two names such as '|item index|'",
+                ));
+            }
+            Ok(params)
+        }
+        None => Err(TemplateError::new(
+            pos,
+            &format!("invalid block params '{}', expected '|name|'", params),
+        )),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum ExprToken {
+    Name(String),
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Op(&'static str),
+}
+
+const EXPR_OPS: [&str; 12] = [
+    "==", "!=", "<=", ">=", "<", ">", "|", "(", ")", ",", "[", "]",
+];
+
+fn tokenize_expr(src: &str, pos: TemplatePos) -> Result<Vec<ExprToken>, TemplateError> {
+    let mut tokens = vec![];
+    let chars: Vec<char> = src.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '"' || c == '\'' {
+            let mut s = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    Some(ch) if *ch == c => break,
+                    Some('\\') => {
+                        i += 1;
+                        match chars.get(i) {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(ch) => s.push(*ch),
+                            None => break,
+                        }
+                    }
+                    Some(ch) => s.push(*ch),
+                    None => return Err(TemplateError::new(pos, "unterminated string literal")),
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(ExprToken::Str(s));
+        } else if c.is_ascii_digit()
+            || (c == '-' && matches!(chars.get(i + 1), Some(d) if d.is_ascii_digit()))
+        {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let s: String = chars[start..i].iter().collect();
+            if s.contains('.') {
+                match s.parse::<f64>() {
+                    Ok(v) => tokens.push(ExprToken::Float(v)),
+                    Err(_) => {
+                        return Err(TemplateError::new(pos, &format!("invalid number '{}'", s)))
+                    }
+                }
+            } else {
+                match s.parse::<i64>() {
+                    Ok(v) => tokens.push(ExprToken::Int(v)),
+                    Err(_) => {
+                        return Err(TemplateError::new(pos, &format!("invalid number '{}'", s)))
+                    }
+                }
+            }
+        } else if c.is_alphanumeric() || c == '_' || c == '@' || c == '$' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '@' | '$' | '.'))
+            {
+                i += 1;
+            }
+            tokens.push(ExprToken::Name(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..].iter().collect();
+            match EXPR_OPS.iter().find(|op| rest.starts_with(*op)) {
+                Some(op) => {
+                    tokens.push(ExprToken::Op(op));
+                    i += op.len();
+                }
+                None => {
+                    return Err(TemplateError::new(
+                        pos,
+                        &format!("unexpected character '{}' in '{}'", c, src),
+                    ))
+                }
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_expr(src: &str, pos: TemplatePos) -> Result<Expr, TemplateError> {
+    let tokens = tokenize_expr(src, pos)?;
+    let mut parser = ExprParser {
+        tokens,
+        index: 0,
+        src,
+        pos,
+    };
+    let expr = parser.parse_or()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(_) => Err(parser.error("unexpected token")),
+    }
+}
+
+struct ExprParser<'a> {
+    tokens: Vec<ExprToken>,
+    index: usize,
+    src: &'a str,
+    pos: TemplatePos,
+}
+
+impl<'a> ExprParser<'a> {
+    fn peek(&self) -> Option<&ExprToken> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<ExprToken> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        if matches!(self.peek(), Some(ExprToken::Op(o)) if *o == op) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_name(&mut self, name: &str) -> bool {
+        if matches!(self.peek(), Some(ExprToken::Name(n)) if n == name) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, msg: &str) -> TemplateError {
+        TemplateError::new(self.pos, &format!("{} in '{}'", msg, self.src))
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, TemplateError> {
+        let mut expr = self.parse_and()?;
+        while self.eat_name("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, TemplateError> {
+        let mut expr = self.parse_not()?;
+        while self.eat_name("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, TemplateError> {
+        if self.eat_name("not") {
+            Ok(Expr::Not(Box::new(self.parse_not()?)))
+        } else {
+            self.parse_compare()
+        }
+    }
+
+    fn parse_compare(&mut self) -> Result<Expr, TemplateError> {
+        let left = self.parse_filter()?;
+        let op = match self.peek() {
+            Some(ExprToken::Op("==")) => CmpOp::Eq,
+            Some(ExprToken::Op("!=")) => CmpOp::NotEq,
+            Some(ExprToken::Op("<")) => CmpOp::Lt,
+            Some(ExprToken::Op("<=")) => CmpOp::LtE,
+            Some(ExprToken::Op(">")) => CmpOp::Gt,
+            Some(ExprToken::Op(">=")) => CmpOp::GtE,
+            _ => return Ok(left),
+        };
+        self.index += 1;
+        let right = self.parse_filter()?;
+        Ok(Expr::Compare(op, Box::new(left), Box::new(right)))
+    }
+
+    fn parse_filter(&mut self) -> Result<Expr, TemplateError> {
+        let mut expr = self.parse_postfix()?;
+        while self.eat_op("|") {
+            let name = match self.next() {
+                Some(ExprToken::Name(name)) => name,
+                _ => return Err(self.error("expected a filter name after '|'")),
+            };
+            let mut args = vec![];
+            if self.eat_op("(") && !self.eat_op(")") {
+                loop {
+                    args.push(self.parse_or()?);
+                    if self.eat_op(")") {
+                        break;
+                    }
+                    if !self.eat_op(",") {
+                        return Err(self.error("expected ',' or ')' in the filter arguments"));
+                    }
+                }
+            }
+            expr = Expr::Filter(Box::new(expr), name, args);
+        }
+        Ok(expr)
+    }
+
+    fn parse_postfix(&mut self) -> Result<Expr, TemplateError> {
+        let mut expr = self.parse_primary()?;
+        while self.eat_op("[") {
+            let index = self.parse_or()?;
+            if !self.eat_op("]") {
+                return Err(self.error("expected ']'"));
+            }
+            expr = Expr::Index(Box::new(expr), Box::new(index));
+        }
+        Ok(expr)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, TemplateError> {
+        match self.next() {
+            Some(ExprToken::Str(s)) => Ok(Expr::Literal(ValueRef::str(&s))),
+            Some(ExprToken::Int(v)) => Ok(Expr::Literal(ValueRef::int(v))),
+            Some(ExprToken::Float(v)) => Ok(Expr::Literal(ValueRef::float(v))),
+            Some(ExprToken::Name(name)) => Ok(match name.as_str() {
+                "true" | "True" => Expr::Literal(ValueRef::bool(true)),
+                "false" | "False" => Expr::Literal(ValueRef::bool(false)),
+                "none" | "None" => Expr::Literal(ValueRef::none()),
+                _ => Expr::Var(name.split('.').map(|s| s.to_string()).collect()),
+            }),
+            Some(ExprToken::Op("(")) => {
+                let expr = self.parse_or()?;
+                if !self.eat_op(")") {
+                    return Err(self.error("expected ')'"));
+                }
+                Ok(expr)
+            }
+            _ => Err(self.error("expected an expression")),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Renderer
+// ----------------------------------------------------------------------------
+
+struct Frame {
+    this: ValueRef,
+    locals: IndexMap<String, ValueRef>,
+}
+
+struct Renderer {
+    root: ValueRef,
+    frames: Vec<Frame>,
+}
+
+impl Renderer {
+    fn new(data: &ValueRef) -> Self {
+        Renderer {
+            root: data.clone(),
+            frames: vec![Frame {
+                this: data.clone(),
+                locals: IndexMap::default(),
+            }],
+        }
+    }
+
+    fn render(&mut self, nodes: &[Node], out: &mut String) -> Result<(), TemplateError> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Output(expr, pos) => {
+                    let value = self.eval(expr, *pos)?;
+                    if value.is_undefined() {
+                        return Err(TemplateError::new(
+                            *pos,
+                            &format!("'{}' is undefined", expr.describe()),
+                        ));
+                    }
+                    out.push_str(&value.to_string());
+                }
+                Node::If(branches, else_body) => {
+                    let mut matched = false;
+                    for (cond, pos, body) in branches {
+                        if self.eval(cond, *pos)?.is_truthy() {
+                            self.render(body, out)?;
+                            matched = true;
+                            break;
+                        }
+                    }
+                    if !matched {
+                        self.render(else_body, out)?;
+                    }
+                }
+                Node::Each(block) => self.render_each(block, out)?,
+                Node::With(block) => {
+                    let value = self.eval(&block.expr, block.pos)?;
+                    if value.is_truthy() {
+                        let mut locals = IndexMap::default();
+                        if let Some(name) = block.params.first() {
+                            locals.insert(name.clone(), value.clone());
+                        }
+                        self.render_in_frame(&block.body, value, locals, out)?;
+                    } else {
+                        self.render(&block.else_body, out)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn render_each(&mut self, block: &Block, out: &mut String) -> Result<(), TemplateError> {
+        let value = self.eval(&block.expr, block.pos)?;
+        // Items are (key, value) pairs, keys are None for lists.
+        let items: Vec<(Option<String>, ValueRef)> = match &*value.rc {
+            Value::undefined | Value::none => vec![],
+            Value::list_value(list) => list.values.iter().map(|v| (None, v.clone())).collect(),
+            Value::dict_value(_) | Value::schema_value(_) => value
+                .as_dict_ref()
+                .values
+                .iter()
+                .map(|(k, v)| (Some(k.clone()), v.clone()))
+                .collect(),
+            _ => {
+                return Err(TemplateError::new(
+                    block.pos,
+                    &format!(
+                        "'{}' of type '{}' is not iterable",
+                        block.expr.describe(),
+                        value.type_str()
+                    ),
+                ))
+            }
+        };
+        if items.is_empty() {
+            return self.render(&block.else_body, out);
+        }
+        let len = items.len();
+        for (i, (key, item)) in items.into_iter().enumerate() {
+            let mut locals = IndexMap::default();
+            locals.insert("@index".to_string(), ValueRef::int(i as i64));
+            locals.insert("@first".to_string(), ValueRef::bool(i == 0));
+            locals.insert("@last".to_string(), ValueRef::bool(i + 1 == len));
+            let key_or_index = match &key {
+                Some(key) => ValueRef::str(key),
+                None => ValueRef::int(i as i64),
+            };
+            if let Some(key) = key {
+                locals.insert("@key".to_string(), ValueRef::str(&key));
+            }
+            if let Some(name) = block.params.first() {
+                locals.insert(name.clone(), item.clone());
+            }
+            if let Some(name) = block.params.get(1) {
+                locals.insert(name.clone(), key_or_index);
+            }
+            self.render_in_frame(&block.body, item, locals, out)?;
+        }
+        Ok(())
+    }
+
+    fn render_in_frame(
+        &mut self,
+        nodes: &[Node],
+        this: ValueRef,
+        locals: IndexMap<String, ValueRef>,
+        out: &mut String,
+    ) -> Result<(), TemplateError> {
+        self.frames.push(Frame { this, locals });
+        let result = self.render(nodes, out);
+        self.frames.pop();
+        result
+    }
+
+    /// Lookup the first name of a variable path, names are resolved from the innermost
+    /// frame to the outermost frame.
+    fn lookup(&self, name: &str) -> ValueRef {
+        match name {
+            "this" => return self.frames.last().unwrap().this.clone(),
+            "@root" => return self.root.clone(),
+            _ => {}
+        }
+        for frame in self.frames.iter().rev() {
+            if let Some(value) = frame.locals.get(name) {
+                return value.clone();
+            }
+            if !name.starts_with('@') && (frame.this.is_dict() || frame.this.is_schema()) {
+                if let Some(value) = frame.this.get_by_key(name) {
+                    return value.clone();
+                }
+            }
+        }
+        ValueRef::undefined()
+    }
+
+    fn eval(&self, expr: &Expr, pos: TemplatePos) -> Result<ValueRef, TemplateError> {
+        match expr {
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Var(path) => {
+                let mut value = self.lookup(&path[0]);
+                for key in &path[1..] {
+                    value = match value.get_by_key(key) {
+                        Some(v) => v.clone(),
+                        None => return Ok(ValueRef::undefined()),
+                    };
+                }
+                Ok(value)
+            }
+            Expr::Index(value, index) => {
+                let value = self.eval(value, pos)?;
+                let index = self.eval(index, pos)?;
+                let key = if index.is_str() {
+                    index.as_str()
+                } else {
+                    index.to_string()
+                };
+                Ok(value
+                    .get_by_key(&key)
+                    .cloned()
+                    .unwrap_or_else(ValueRef::undefined))
+            }
+            Expr::Filter(value_expr, name, args) => {
+                let value = self.eval(value_expr, pos)?;
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval(arg, pos))
+                    .collect::<Result<Vec<ValueRef>, TemplateError>>()?;
+                if value.is_undefined() && name != "default" {
+                    return Err(TemplateError::new(
+                        pos,
+                        &format!("'{}' is undefined", value_expr.describe()),
+                    ));
+                }
+                apply_filter(name, &value, &args).map_err(|msg| TemplateError::new(pos, &msg))
+            }
+            Expr::Not(value) => Ok(ValueRef::bool(!self.eval(value, pos)?.is_truthy())),
+            Expr::And(left, right) => {
+                let left = self.eval(left, pos)?;
+                if !left.is_truthy() {
+                    return Ok(left);
+                }
+                self.eval(right, pos)
+            }
+            Expr::Or(left, right) => {
+                let left = self.eval(left, pos)?;
+                if left.is_truthy() {
+                    return Ok(left);
+                }
+                self.eval(right, pos)
+            }
+            Expr::Compare(op, left, right) => {
+                let left = self.eval(left, pos)?;
+                let right = self.eval(right, pos)?;
+                Ok(ValueRef::bool(match op {
+                    CmpOp::Eq => left.cmp_equal(&right),
+                    CmpOp::NotEq => left.cmp_not_equal(&right),
+                    CmpOp::Lt => left.cmp_less_than(&right),
+                    CmpOp::LtE => left.cmp_less_than_or_equal(&right),
+                    CmpOp::Gt => left.cmp_greater_than(&right),
+                    CmpOp::GtE => left.cmp_greater_than_or_equal(&right),
+                }))
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Filters
+// ----------------------------------------------------------------------------
+
+fn apply_filter(name: &str, value: &ValueRef, args: &[ValueRef]) -> Result<ValueRef, String> {
+    let expect_str = || -> Result<String, String> {
+        if value.is_str() {
+            Ok(value.as_str())
+        } else {
+            Err(format!(
+                "filter '{}' expects a str value, got '{}'",
+                name,
+                value.type_str()
+            ))
+        }
+    };
+    let int_arg = |i: usize, default: Option<i64>| -> Result<i64, String> {
+        match args.get(i) {
+            Some(arg) if arg.is_int() => Ok(arg.as_int()),
+            Some(arg) => Err(format!(
+                "filter '{}' expects an int argument, got '{}'",
+                name,
+                arg.type_str()
+            )),
+            None => default.ok_or_else(|| format!("filter '{}' missing an int argument", name)),
+        }
+    };
+    let str_arg = |i: usize, default: Option<&str>| -> Result<String, String> {
+        match args.get(i) {
+            Some(arg) if arg.is_str() => Ok(arg.as_str()),
+            Some(arg) => Err(format!(
+                "filter '{}' expects a str argument, got '{}'",
+                name,
+                arg.type_str()
+            )),
+            None => default
+                .map(|s| s.to_string())
+                .ok_or_else(|| format!("filter '{}' missing a str argument", name)),
+        }
+    };
+    let result = match name {
+        "default" => {
+            if value.is_none_or_undefined() {
+                args.first().cloned().unwrap_or_else(|| ValueRef::str(""))
+            } else {
+                value.clone()
+            }
+        }
+        "upper" => ValueRef::str(&expect_str()?.to_uppercase()),
+        "lower" => ValueRef::str(&expect_str()?.to_lowercase()),
+        "title" => {
+            expect_str()?;
+            value.str_title()
+        }
+        "capitalize" => {
+            expect_str()?;
+            value.str_capitalize()
+        }
+        "trim" => ValueRef::str(expect_str()?.trim()),
+        "replace" => {
+            let (old, new) = (str_arg(0, None)?, str_arg(1, None)?);
+            ValueRef::str(&expect_str()?.replace(&old, &new))
+        }
+        "quote" => {
+            let s = if value.is_str() {
+                value.as_str()
+            } else {
+                value.to_string()
+            };
+            ValueRef::str(&serde_json::to_string(&s).unwrap())
+        }
+        "json" => match args.first() {
+            Some(_) => {
+                let opt = JsonEncodeOptions {
+                    indent: int_arg(0, None)?,
+                    ..Default::default()
+                };
+                ValueRef::str(&value.to_json_string_with_option(&opt))
+            }
+            None => ValueRef::str(&value.to_json_string()),
+        },
+        "yaml" => ValueRef::str(value.to_yaml_string().trim_end()),
+        "indent" | "nindent" => {
+            let width = int_arg(0, None)?;
+            let s = if value.is_str() {
+                value.as_str()
+            } else {
+                value.to_string()
+            };
+            let indent = " ".repeat(width.max(0) as usize);
+            let indent_first = name == "nindent" || matches!(args.get(1), Some(a) if a.is_truthy());
+            let lines: Vec<String> = s
+                .split('\n')
+                .enumerate()
+                .map(|(i, line)| {
+                    if line.is_empty() || (i == 0 && !indent_first) {
+                        line.to_string()
+                    } else {
+                        format!("{}{}", indent, line)
+                    }
+                })
+                .collect();
+            let result = lines.join("\n");
+            if name == "nindent" {
+                ValueRef::str(&format!("\n{}", result))
+            } else {
+                ValueRef::str(&result)
+            }
+        }
+        "length" => match &*value.rc {
+            Value::str_value(s) => ValueRef::int(s.chars().count() as i64),
+            Value::list_value(_) | Value::dict_value(_) | Value::schema_value(_) => {
+                ValueRef::int(value.len() as i64)
+            }
+            _ => {
+                return Err(format!(
+                    "filter 'length' expects a str, list or dict value, got '{}'",
+                    value.type_str()
+                ))
+            }
+        },
+        "join" => {
+            if !value.is_list() {
+                return Err(format!(
+                    "filter 'join' expects a list value, got '{}'",
+                    value.type_str()
+                ));
+            }
+            let sep = str_arg(0, Some(""))?;
+            let items: Vec<String> = value
+                .as_list_ref()
+                .values
+                .iter()
+                .map(|v| v.to_string())
+                .collect();
+            ValueRef::str(&items.join(&sep))
+        }
+        "first" | "last" => {
+            if !value.is_list() {
+                return Err(format!(
+                    "filter '{}' expects a list value, got '{}'",
+                    name,
+                    value.type_str()
+                ));
+            }
+            let values = &value.as_list_ref().values;
+            let item = if name == "first" {
+                values.first()
+            } else {
+                values.last()
+            };
+            item.cloned().unwrap_or_else(ValueRef::undefined)
+        }
+        "keys" | "values" => {
+            if !value.is_dict() && !value.is_schema() {
+                return Err(format!(
+                    "filter '{}' expects a dict value, got '{}'",
+                    name,
+                    value.type_str()
+                ));
+            }
+            let mut list = ValueRef::list(None);
+            for (k, v) in &value.as_dict_ref().values {
+                if name == "keys" {
+                    list.list_append(&ValueRef::str(k));
+                } else {
+                    list.list_append(v);
+                }
+            }
+            list
+        }
+        _ => return Err(format!("unknown filter '{}'", name)),
+    };
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test_template {
+    use crate::*;
+
+    fn data() -> ValueRef {
+        ValueRef::from_json(
+            r#"{
+                "name": "nginx",
+                "port": 80,
+                "enabled": true,
+                "servers": [{"host": "a.com", "port": 8080}, {"host": "b.com", "port": 8081}],
+                "labels": {"app": "web", "app.kubernetes.io/name": "nginx"}
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_execute_output() {
+        let cases = [
+            ("name: {{ name }}", "name: nginx"),
+            ("{{ name | upper }}:{{port}}", "NGINX:80"),
+            ("{{ labels.app }}", "web"),
+            ("{{ labels[\"app.kubernetes.io/name\"] }}", "nginx"),
+            ("{{ servers.1.host }}", "b.com"),
+            ("{{ missing | default(\"none\") }}", "none"),
+            ("{{ servers | length }}", "2"),
+            (
+                "{{ labels | keys | join(\",\") }}",
+                "app,app.kubernetes.io/name",
+            ),
+            ("{{ name | quote }}", "\"nginx\""),
+            ("a\n  {{~ name ~}}  \nb", "anginxb"),
+        ];
+        for (template, expected) in cases {
+            assert_eq!(
+                template::execute(template, &data()).unwrap(),
+                expected,
+                "{}",
+                template
+            );
+        }
+    }
+
+    #[test]
+    fn test_execute_blocks() {
+        let template = r#"{{#each servers as |server i|}}
+server {{ i }} {{ server.host }}:{{ port }}/{{ @root.port }}{{#if @last}};{{else}},{{/if}}
+{{/each}}
+{{#if port == 80 and enabled}}
+http
+{{else if port == 443}}
+https
+{{/if}}
+{{#unless missing}}
+no missing
+{{/unless}}
+{{#each labels}}{{@key}}={{this}}{{#unless @last}} {{/unless}}{{/each}}
+{{#each missing}}x{{else}}empty{{/each}}
+{{#with servers.0}}{{ host }}{{/with}}
+"#;
+        let expected = "server 0 a.com:8080/80,
+server 1 b.com:8081/80;
+http
+no missing
+app=web app.kubernetes.io/name=nginx
+empty
+a.com
+";
+        assert_eq!(template::execute(template, &data()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_execute_filters() {
+        let data = ValueRef::from_json(r#"{"config": "a: 1\nb: 2", "list": [1, 2]}"#).unwrap();
+        assert_eq!(
+            template::execute("x:{{ config | nindent(2) }}", &data).unwrap(),
+            "x:\n  a: 1\n  b: 2"
+        );
+        assert_eq!(
+            template::execute("{{ config | indent(4) }}", &data).unwrap(),
+            "a: 1\n    b: 2"
+        );
+        assert_eq!(
+            template::execute("{{ list | json }}", &data).unwrap(),
+            "[1, 2]"
+        );
+    }
+
+    #[test]
+    fn test_execute_errors() {
+        let cases = [
+            ("a\n  {{ missing }}", 2, 3, "'missing' is undefined"),
+            ("{{#if name}}", 1, 1, "unclosed block '{{#if}}'"),
+            (
+                "{{#if name}}{{/each}}",
+                1,
+                13,
+                "'{{/each}}' does not match the open block '{{#if}}'",
+            ),
+            (
+                "{{/if}}",
+                1,
+                1,
+                "unexpected '{{/if}}' without a matching open block",
+            ),
+            ("{{ name | unknown }}", 1, 1, "unknown filter 'unknown'"),
+            (
+                "{{ port | upper }}",
+                1,
+                1,
+                "filter 'upper' expects a str value, got 'int'",
+            ),
+            (
+                "{{#each port}}{{/each}}",
+                1,
+                1,
+                "'port' of type 'int' is not iterable",
+            ),
+            ("{{ name ", 1, 1, "unclosed tag, expected '}}'"),
+        ];
+        for (template, line, col, msg) in cases {
+            let err = template::execute(template, &data()).unwrap_err();
+            assert_eq!(err.pos, template::TemplatePos { line, col }, "{}", template);
+            assert_eq!(err.message, msg, "{}", template);
+        }
+    }
+}
@@ -181,6 +181,18 @@ impl ValueRef {
 
     /// Plan the value to JSON and YAML strings
     pub fn plan(&self) -> (String, String) {
+        // The top-level values are a dict, and a list is the documents emitted by
+        // `manifests.yaml_stream` which are planned as they are.
+        if self.is_list() {
+            let yaml_result = self
+                .as_list_ref()
+                .values
+                .iter()
+                .map(|r| r.to_yaml_string())
+                .collect::<Vec<String>>()
+                .join("---\n");
+            return (self.to_json_string(), yaml_result);
+        }
         let results = filter_results(self);
         let yaml_result = results
             .iter()
@@ -366,7 +366,7 @@ pub fn _kclvm_get_fn_ptr_by_name(name: &str) -> u64 {
 		{{- range $_, $spec := $specList -}}{{if (not $spec.IsType)}}
 		"{{$spec.Name}}" => crate::{{$spec.Name}} as *const () as u64,
 		{{- end}}{{end}}
-		_ => panic!("unknown {}", name),
+		_ => 0,
 	}
 }
 `
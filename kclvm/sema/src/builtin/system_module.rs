@@ -1,10 +1,37 @@
 // Copyright 2021 The KCL Authors. All rights reserved.
 
+use std::rc::Rc;
+
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+
+use crate::ty::{Parameter, Type};
+
+macro_rules! register_system_module_member {
+    ($map:ident => { $($name:literal => $ty:expr)* }) => (
+        // System module member function map.
+        pub const $map: Lazy<IndexMap<String, Type>> = Lazy::new(|| {
+            let mut builtin_mapping = IndexMap::default();
+            $( builtin_mapping.insert($name.to_string(), $ty); )*
+            builtin_mapping
+        });
+    )
+}
+
+/// Return a system module function parameter.
+fn param(name: &str, ty: Type, has_default: bool) -> Parameter {
+    Parameter {
+        name: name.to_string(),
+        ty: Rc::new(ty),
+        has_default,
+    }
+}
+
 pub const BASE64: &str = "base64";
 pub const BASE64_FUNCTION_NAMES: [&str; 2] = ["encode", "decode"];
 
 pub const NET: &str = "net";
-pub const NET_FUNCTION_NAMES: [&str; 16] = [
+pub const NET_FUNCTION_NAMES: [&str; 21] = [
     "split_host_port",
     "join_host_port",
     "fqdn",
@@ -13,6 +40,7 @@ pub const NET_FUNCTION_NAMES: [&str; 16] = [
     "to_IP16",
     "IP_string",
     "is_IPv4",
+    "is_IPv6",
     "is_IP",
     "is_loopback_IP",
     "is_multicast_IP",
@@ -21,8 +49,66 @@ pub const NET_FUNCTION_NAMES: [&str; 16] = [
     "is_link_local_unicast_IP",
     "is_global_unicast_IP",
     "is_unspecified_IP",
+    "parse_CIDR",
+    "CIDR_contains",
+    "CIDR_subnets",
+    "CIDR_host",
 ];
 
+register_system_module_member! {
+    NET_FUNCTION_TYPES => {
+        "is_IPv6" => Type::function(
+            None,
+            Rc::new(Type::BOOL),
+            &[param("ip", Type::STR, false)],
+            "Whether `ip` is a valid IPv6 address.",
+            false,
+            None,
+        )
+        "parse_CIDR" => Type::function(
+            None,
+            Type::dict_ref(Rc::new(Type::STR), Rc::new(Type::ANY)),
+            &[param("cidr", Type::STR, false)],
+            "Parse the CIDR notation `cidr` such as \"192.168.0.1/24\" and return a dict with the keys `ip`, `prefix`, `network`, `netmask`, `first` and `last`.",
+            false,
+            None,
+        )
+        "CIDR_contains" => Type::function(
+            None,
+            Rc::new(Type::BOOL),
+            &[
+                param("cidr", Type::STR, false),
+                param("ip", Type::STR, false),
+            ],
+            "Whether the network `cidr` contains the IP address `ip`.",
+            false,
+            None,
+        )
+        "CIDR_subnets" => Type::function(
+            None,
+            Type::list_ref(Rc::new(Type::STR)),
+            &[
+                param("cidr", Type::STR, false),
+                param("newbits", Type::INT, false),
+            ],
+            "Split the network `cidr` into all subnets whose prefix is extended by `newbits` bits.",
+            false,
+            None,
+        )
+        "CIDR_host" => Type::function(
+            None,
+            Rc::new(Type::STR),
+            &[
+                param("cidr", Type::STR, false),
+                param("n", Type::INT, false),
+            ],
+            "Return the `n`-th host IP address in the network `cidr`, a negative `n` counts backwards from the last address.",
+            false,
+            None,
+        )
+    }
+}
+
 pub const MATH: &str = "math";
 pub const MATH_FUNCTION_NAMES: [&str; 16] = [
     "ceil",
@@ -57,8 +143,166 @@ pub const JSON: &str = "json";
 pub const JSON_FUNCTION_NAMES: [&str; 3] = ["encode", "decode", "dump_to_file"];
 
 pub const CRYPTO: &str = "crypto";
-pub const CRYPTO_FUNCTION_NAMES: [&str; 6] =
-    ["md5", "sha1", "sha224", "sha256", "sha384", "sha512"];
+pub const CRYPTO_FUNCTION_NAMES: [&str; 10] = [
+    "md5",
+    "sha1",
+    "sha224",
+    "sha256",
+    "sha384",
+    "sha512",
+    "uuid",
+    "hmac_sha256",
+    "blake3",
+    "filesha256",
+];
+
+/// Return the type of the crypto digest functions such as `md5(value: str, encoding: str = "utf-8") -> str`.
+fn crypto_digest_function_ty(doc: &str) -> Type {
+    Type::function(
+        None,
+        Rc::new(Type::STR),
+        &[
+            param("value", Type::STR, false),
+            param("encoding", Type::STR, true),
+        ],
+        doc,
+        false,
+        None,
+    )
+}
+
+register_system_module_member! {
+    CRYPTO_FUNCTION_TYPES => {
+        "md5" => crypto_digest_function_ty("Encrypt the string `value` using `MD5` and return the hex digest.")
+        "sha1" => crypto_digest_function_ty("Encrypt the string `value` using `SHA1` and return the hex digest.")
+        "sha224" => crypto_digest_function_ty("Encrypt the string `value` using `SHA224` and return the hex digest.")
+        "sha256" => crypto_digest_function_ty("Encrypt the string `value` using `SHA256` and return the hex digest.")
+        "sha384" => crypto_digest_function_ty("Encrypt the string `value` using `SHA384` and return the hex digest.")
+        "sha512" => crypto_digest_function_ty("Encrypt the string `value` using `SHA512` and return the hex digest.")
+        "uuid" => Type::function(
+            None,
+            Rc::new(Type::STR),
+            &[
+                param("name", Type::STR, true),
+                param("namespace", Type::STR, true),
+            ],
+            r#"Generate a random UUID (version 4) when `name` is empty, otherwise generate a name-based UUID (version 5) of `name` in `namespace`. `namespace` is one of "dns", "url", "oid", "x500" or a UUID string, defaults to "dns"."#,
+            false,
+            None,
+        )
+        "hmac_sha256" => Type::function(
+            None,
+            Rc::new(Type::STR),
+            &[
+                param("key", Type::STR, false),
+                param("msg", Type::STR, false),
+            ],
+            "Return the hex digest of the HMAC of `msg` using `SHA256` and the secret `key`.",
+            false,
+            None,
+        )
+        "blake3" => Type::function(
+            None,
+            Rc::new(Type::STR),
+            &[param("value", Type::STR, false)],
+            "Encrypt the string `value` using `BLAKE3` and return the hex digest.",
+            false,
+            None,
+        )
+        "filesha256" => Type::function(
+            None,
+            Rc::new(Type::STR),
+            &[param("filepath", Type::STR, false)],
+            "Return the `SHA256` hex digest of the content of the file `filepath`.",
+            false,
+            None,
+        )
+    }
+}
+
+pub const MANIFESTS: &str = "manifests";
+pub const MANIFESTS_FUNCTION_NAMES: [&str; 1] = ["yaml_stream"];
+
+register_system_module_member! {
+    MANIFESTS_FUNCTION_TYPES => {
+        "yaml_stream" => Type::function(
+            None,
+            Rc::new(Type::NONE),
+            &[
+                param("values", Type::list(Rc::new(Type::ANY)), true),
+                param("opts", Type::dict(Rc::new(Type::STR), Rc::new(Type::ANY)), true),
+            ],
+            "Encode the list `values` to a YAML stream with one document per element and use it as the program output. `opts` supports the keys `sort_keys`, `ignore_private` and `ignore_none`.",
+            false,
+            None,
+        )
+    }
+}
+
+pub const SEMVER: &str = "semver";
+pub const SEMVER_FUNCTION_NAMES: [&str; 4] = ["parse", "is_valid", "compare", "match"];
+
+register_system_module_member! {
+    SEMVER_FUNCTION_TYPES => {
+        "parse" => Type::function(
+            None,
+            Type::dict_ref(Rc::new(Type::STR), Rc::new(Type::ANY)),
+            &[param("version", Type::STR, false)],
+            "Parse the semantic version `version` and return a dict with the keys `major`, `minor`, `patch`, `pre` and `build`.",
+            false,
+            None,
+        )
+        "is_valid" => Type::function(
+            None,
+            Rc::new(Type::BOOL),
+            &[param("version", Type::STR, false)],
+            "Whether `version` is a valid semantic version.",
+            false,
+            None,
+        )
+        "compare" => Type::function(
+            None,
+            Rc::new(Type::INT),
+            &[
+                param("v1", Type::STR, false),
+                param("v2", Type::STR, false),
+            ],
+            "Compare the semantic versions `v1` and `v2`, return -1, 0 or 1 when `v1` is lower than, equal to or greater than `v2`.",
+            false,
+            None,
+        )
+        "match" => Type::function(
+            None,
+            Rc::new(Type::BOOL),
+            &[
+                param("version", Type::STR, false),
+                param("constraint", Type::STR, false),
+            ],
+            "Whether the semantic version `version` satisfies the version `constraint` such as \">=1.2.0, <2.0.0\".",
+            false,
+            None,
+        )
+    }
+}
+
+pub const TEMPLATE: &str = "template";
+pub const TEMPLATE_FUNCTION_NAMES: [&str; 1] = ["execute"];
+
+register_system_module_member! {
+    TEMPLATE_FUNCTION_TYPES => {
+        "execute" => Type::function(
+            None,
+            Rc::new(Type::STR),
+            &[
+                param("template", Type::STR, false),
+                param("data", Type::dict(Rc::new(Type::STR), Rc::new(Type::ANY)), true),
+            ],
+            "Render the `template` string with the `data` dict, the template supports `{{ expr }}` outputs with filters, `{{#if}}`, `{{#unless}}`, `{{#each}}` and `{{#with}}` blocks.",
+            false,
+            None,
+        )
+    }
+}
 
 pub const TESTING: &str = "testing";
 pub const TESTING_FUNCTION_NAMES: [&str; 2] = ["arguments", "setting_file"];
@@ -88,13 +332,131 @@ pub const UNITS_FIELD_NAMES: [&str; 15] = [
 ];
 
 pub const COLLECTION: &str = "collection";
-pub const COLLECTION_FUNCTION_NAMES: [&str; 1] = ["union_all"];
+pub const COLLECTION_FUNCTION_NAMES: [&str; 9] = [
+    "union_all",
+    "get",
+    "set",
+    "merge_patch",
+    "json_patch",
+    "flatten",
+    "group_by",
+    "distinct",
+    "deep_diff",
+];
+
+register_system_module_member! {
+    COLLECTION_FUNCTION_TYPES => {
+        "union_all" => Type::function(
+            None,
+            Rc::new(Type::ANY),
+            &[param("configs", Type::list(Rc::new(Type::ANY)), false)],
+            "Union all dicts or schemas in the list `configs` in order.",
+            false,
+            None,
+        )
+        "get" => Type::function(
+            None,
+            Rc::new(Type::ANY),
+            &[
+                param("obj", Type::ANY, false),
+                param("path", Type::STR, false),
+                param("default", Type::ANY, true),
+            ],
+            "Get the value in `obj` at `path` such as \"a.b[0].c\", return `default` when the path does not exist.",
+            false,
+            None,
+        )
+        "set" => Type::function(
+            None,
+            Rc::new(Type::ANY),
+            &[
+                param("obj", Type::ANY, false),
+                param("path", Type::STR, false),
+                param("value", Type::ANY, false),
+            ],
+            "Return a copy of `obj` with `value` set at `path` such as \"a.b[0].c\", missing dict keys on the path are created.",
+            false,
+            None,
+        )
+        "merge_patch" => Type::function(
+            None,
+            Rc::new(Type::ANY),
+            &[
+                param("target", Type::ANY, false),
+                param("patch", Type::ANY, false),
+            ],
+            "Apply the JSON merge patch (RFC 7386) `patch` to `target` and return the patched value.",
+            false,
+            None,
+        )
+        "json_patch" => Type::function(
+            None,
+            Rc::new(Type::ANY),
+            &[
+                param("obj", Type::ANY, false),
+                param(
+                    "patch",
+                    Type::list(Type::dict_ref(Rc::new(Type::STR), Rc::new(Type::ANY))),
+                    false,
+                ),
+            ],
+            "Apply the JSON patch (RFC 6902) operations `patch` to `obj` and return the patched value.",
+            false,
+            None,
+        )
+        "flatten" => Type::function(
+            None,
+            Rc::new(Type::ANY),
+            &[
+                param("value", Type::ANY, false),
+                param("sep", Type::STR, true),
+            ],
+            "Flatten nested lists into one list, or nested dicts into one dict whose keys are joined with `sep`.",
+            false,
+            None,
+        )
+        "group_by" => Type::function(
+            None,
+            Type::dict_ref(Rc::new(Type::STR), Type::list_ref(Rc::new(Type::ANY))),
+            &[
+                param("items", Type::list(Rc::new(Type::ANY)), false),
+                param("key", Type::STR, false),
+            ],
+            "Group `items` into a dict of lists by the value at the path `key` of each item.",
+            false,
+            None,
+        )
+        "distinct" => Type::function(
+            None,
+            Type::list_ref(Rc::new(Type::ANY)),
+            &[
+                param("items", Type::list(Rc::new(Type::ANY)), false),
+                param("key", Type::STR, true),
+            ],
+            "Remove the duplicated `items` in order, items are compared by the value at the path `key` when it is not empty.",
+            false,
+            None,
+        )
+        "deep_diff" => Type::function(
+            None,
+            Type::list_ref(Type::dict_ref(Rc::new(Type::STR), Rc::new(Type::ANY))),
+            &[
+                param("a", Type::ANY, false),
+                param("b", Type::ANY, false),
+            ],
+            "Return the JSON patch (RFC 6902) operations which transform `a` into `b`.",
+            false,
+            None,
+        )
+    }
+}
 
-pub const STANDARD_SYSTEM_MODULES: [&str; 11] = [
-    COLLECTION, NET, MATH, DATETIME, REGEX, YAML, JSON, CRYPTO, BASE64, TESTING, UNITS,
+pub const STANDARD_SYSTEM_MODULES: [&str; 14] = [
+    COLLECTION, NET, MATH, DATETIME, REGEX, YAML, JSON, CRYPTO, BASE64, TESTING, UNITS, SEMVER,
+    TEMPLATE, MANIFESTS,
 ];
 
-pub const STANDARD_SYSTEM_MODULE_NAMES_WITH_AT: [&str; 11] = [
+pub const STANDARD_SYSTEM_MODULE_NAMES_WITH_AT: [&str; 14] = [
     "@collection",
     "@net",
     "@math",
@@ -106,6 +468,9 @@ pub const STANDARD_SYSTEM_MODULE_NAMES_WITH_AT: [&str; 11] = [
     "@base64",
     "@testing",
     "@units",
+    "@semver",
+    "@template",
+    "@manifests",
 ];
 
 /// Get the system module members
@@ -126,6 +491,24 @@ pub fn get_system_module_members(name: &str) -> Vec<&str> {
             members
         }
         COLLECTION => COLLECTION_FUNCTION_NAMES.to_vec(),
+        SEMVER => SEMVER_FUNCTION_NAMES.to_vec(),
+        TEMPLATE => TEMPLATE_FUNCTION_NAMES.to_vec(),
+        MANIFESTS => MANIFESTS_FUNCTION_NAMES.to_vec(),
         _ => bug!("invalid system module name '{}'", name),
     }
 }
+
+/// Get the system module member function type, return `None` when the member
+/// has no registered type.
+pub fn get_system_member_function_ty(name: &str, func: &str) -> Option<Rc<Type>> {
+    let optional_ty = match name {
+        COLLECTION => COLLECTION_FUNCTION_TYPES.get(func).cloned(),
+        NET => NET_FUNCTION_TYPES.get(func).cloned(),
+        CRYPTO => CRYPTO_FUNCTION_TYPES.get(func).cloned(),
+        SEMVER => SEMVER_FUNCTION_TYPES.get(func).cloned(),
+        TEMPLATE => TEMPLATE_FUNCTION_TYPES.get(func).cloned(),
+        MANIFESTS => MANIFESTS_FUNCTION_TYPES.get(func).cloned(),
+        _ => None,
+    };
+    optional_ty.map(Rc::new)
+}
@@ -11,6 +11,7 @@ mod para;
 pub mod pos;
 mod schema;
 pub mod scope;
+pub mod session;
 mod ty;
 mod ty_alias;
 mod var;
@@ -2,7 +2,7 @@ use indexmap::IndexMap;
 use indexmap::IndexSet;
 use kclvm_ast::{ast, MAIN_PKG};
 use kclvm_error::Diagnostic;
-use kclvm_error::Handler;
+use kclvm_error::{KclvmError, KclvmResult};
 
 use std::{
     cell::RefCell,
@@ -211,11 +211,18 @@ impl ProgramScope {
         self.scope_map.get(MAIN_PKG)
     }
 
-    pub fn check_scope_diagnostics(&self) {
-        if self.diagnostics.len() > 0 {
-            let mut err_handler = Handler::default();
-            err_handler.diagnostics = self.diagnostics.clone();
-            err_handler.alert_if_any_errors();
+    /// Returns the resolve errors as [KclvmError::Compile], the warnings are not errors.
+    pub fn check_diagnostics(&self) -> KclvmResult<()> {
+        let errors: Vec<Diagnostic> = self
+            .diagnostics
+            .iter()
+            .filter(|diag| diag.is_error())
+            .cloned()
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(KclvmError::Compile(errors))
         }
     }
 }
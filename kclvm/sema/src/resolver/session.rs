@@ -0,0 +1,266 @@
+//! ResolverSession is the incremental resolver kept by the long-lived tools
+//! such as the language server and the watch mode.
+//!
+//! The resolved package scopes are cached by the content hash of the package
+//! sources and the hashes of all the imported packages, so a package is
+//! checked again only when itself or one of its dependencies is changed.
+use indexmap::{IndexMap, IndexSet};
+use std::{cell::RefCell, rc::Rc};
+
+use kclvm_ast::ast::{Program, Stmt};
+use kclvm_ast::MAIN_PKG;
+use kclvm_error::Diagnostic;
+use kclvm_utils::hash::ContentHasher;
+
+use super::scope::{builtin_scope, ProgramScope, Scope};
+use super::ty_alias::process_program_type_alias;
+use super::{Options, Resolver};
+use crate::pre_process::pre_process_program;
+use crate::ty::SchemaType;
+
+/// The resolved result of a package.
+#[derive(Debug)]
+struct PackageEntry {
+    key: String,
+    /// The user packages imported by the package.
+    imports: IndexSet<String>,
+    scope: Rc<RefCell<Scope>>,
+    schema_mapping: IndexMap<String, Rc<RefCell<SchemaType>>>,
+    import_names: IndexMap<String, IndexMap<String, String>>,
+    type_alias_mapping: Option<IndexMap<String, String>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// ResolverSession resolves the programs loaded again and again from the same
+/// workspace and reuses the resolved packages which are not changed.
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut session = ResolverSession::default();
+/// let scope = session.resolve(&mut load_program(&["main.k"], None)?);
+/// // Only the changed packages and their dependents are checked again.
+/// let scope = session.resolve(&mut load_program(&["main.k"], None)?);
+/// ```
+#[derive(Debug)]
+pub struct ResolverSession {
+    options: Options,
+    /// The package scopes only hold a weak reference to the builtin scope, so it
+    /// is shared by all the resolutions of the session.
+    builtin_scope: Rc<RefCell<Scope>>,
+    entries: IndexMap<String, PackageEntry>,
+    /// The packages checked in the last resolution.
+    checked_pkgpaths: Vec<String>,
+}
+
+impl Default for ResolverSession {
+    fn default() -> Self {
+        Self::new(Options {
+            raise_err: true,
+            config_auto_fix: false,
+            lint_check: true,
+        })
+    }
+}
+
+impl ResolverSession {
+    pub fn new(options: Options) -> Self {
+        Self {
+            options,
+            builtin_scope: Rc::new(RefCell::new(builtin_scope())),
+            entries: IndexMap::default(),
+            checked_pkgpaths: vec![],
+        }
+    }
+
+    /// Resolve the program like [super::resolve_program], the unchanged packages
+    /// resolved before are reused instead of being checked again.
+    pub fn resolve(&mut self, program: &mut Program) -> ProgramScope {
+        let keys = gen_pkg_keys(program);
+        pre_process_program(program);
+        let mut resolver = Resolver::new(program, self.options.clone());
+        resolver.builtin_scope = Rc::clone(&self.builtin_scope);
+        resolver.scope = Rc::clone(&self.builtin_scope);
+        // Restore the unchanged packages, the main package is always checked again.
+        let mut reused = IndexSet::new();
+        for (pkgpath, entry) in &self.entries {
+            if keys.get(pkgpath) != Some(&entry.key) {
+                continue;
+            }
+            resolver
+                .scope_map
+                .insert(pkgpath.to_string(), Rc::clone(&entry.scope));
+            resolver
+                .ctx
+                .schema_mapping
+                .extend(entry.schema_mapping.clone());
+            resolver.ctx.import_names.extend(entry.import_names.clone());
+            if let Some(mapping) = &entry.type_alias_mapping {
+                resolver
+                    .ctx
+                    .type_alias_mapping
+                    .insert(pkgpath.to_string(), mapping.clone());
+            }
+            resolver
+                .handler
+                .diagnostics
+                .extend(entry.diagnostics.iter().cloned());
+            for dep in &entry.imports {
+                resolver.ctx.ty_ctx.add_dependencies(pkgpath, dep);
+            }
+            reused.insert(pkgpath.to_string());
+        }
+        resolver.resolve_import();
+        let scope = resolver.check_and_lint(MAIN_PKG);
+        // Update the entries of the packages checked this time.
+        self.entries.retain(|pkgpath, _| reused.contains(pkgpath));
+        self.checked_pkgpaths.clear();
+        for (pkgpath, pkg_scope) in &scope.scope_map {
+            if reused.contains(pkgpath) {
+                continue;
+            }
+            self.checked_pkgpaths.push(pkgpath.to_string());
+            if pkgpath == MAIN_PKG {
+                continue;
+            }
+            let key = match keys.get(pkgpath) {
+                Some(key) => key.clone(),
+                None => continue,
+            };
+            let filenames: IndexSet<&str> = program.pkgs[pkgpath]
+                .iter()
+                .map(|module| module.filename.as_str())
+                .collect();
+            let entry = PackageEntry {
+                key,
+                imports: pkg_imports(program, pkgpath),
+                scope: Rc::clone(pkg_scope),
+                schema_mapping: resolver
+                    .ctx
+                    .schema_mapping
+                    .iter()
+                    .filter(|(_, schema_ty)| &schema_ty.borrow().pkgpath == pkgpath)
+                    .map(|(name, schema_ty)| (name.clone(), Rc::clone(schema_ty)))
+                    .collect(),
+                import_names: resolver
+                    .ctx
+                    .import_names
+                    .iter()
+                    .filter(|(filename, _)| filenames.contains(filename.as_str()))
+                    .map(|(filename, names)| (filename.clone(), names.clone()))
+                    .collect(),
+                type_alias_mapping: resolver.ctx.type_alias_mapping.get(pkgpath).cloned(),
+                diagnostics: scope
+                    .diagnostics
+                    .iter()
+                    .filter(|diag| match diag.messages.first() {
+                        Some(msg) => filenames.contains(msg.pos.filename.as_str()),
+                        None => false,
+                    })
+                    .cloned()
+                    .collect(),
+            };
+            self.entries.insert(pkgpath.to_string(), entry);
+        }
+        let type_alias_mapping = resolver.ctx.type_alias_mapping.clone();
+        process_program_type_alias(program, type_alias_mapping);
+        scope
+    }
+
+    /// Returns the packages checked in the last resolution, the other packages
+    /// in the program scope are reused from the session.
+    pub fn checked_pkgpaths(&self) -> &[String] {
+        &self.checked_pkgpaths
+    }
+
+    /// Remove the cached package and all the packages depending on it are
+    /// checked in the next resolution.
+    pub fn invalidate(&mut self, pkgpath: &str) {
+        self.entries.remove(pkgpath);
+        let mut invalidated = vec![pkgpath.to_string()];
+        while let Some(pkgpath) = invalidated.pop() {
+            let dependents: Vec<String> = self
+                .entries
+                .iter()
+                .filter(|(_, entry)| entry.imports.contains(&pkgpath))
+                .map(|(dependent, _)| dependent.clone())
+                .collect();
+            for dependent in dependents {
+                self.entries.remove(&dependent);
+                invalidated.push(dependent);
+            }
+        }
+    }
+
+    /// Remove all the cached packages.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.checked_pkgpaths.clear();
+    }
+}
+
+/// Returns the user packages imported by the package.
+fn pkg_imports(program: &Program, pkgpath: &str) -> IndexSet<String> {
+    let mut imports = IndexSet::new();
+    if let Some(modules) = program.pkgs.get(pkgpath) {
+        for module in modules {
+            for stmt in &module.body {
+                if let Stmt::Import(import_stmt) = &stmt.node {
+                    if program.pkgs.contains_key(&import_stmt.path) {
+                        imports.insert(import_stmt.path.clone());
+                    }
+                }
+            }
+        }
+    }
+    imports
+}
+
+/// Generate the cache keys of all the packages, the key of a package covers
+/// its sources and the keys of the imported packages, so a change of a package
+/// also changes the keys of all the packages depending on it. The packages in
+/// or depending on an import cycle have no key and are always checked again.
+fn gen_pkg_keys(program: &Program) -> IndexMap<String, String> {
+    let mut keys = IndexMap::default();
+    let mut visited = IndexSet::new();
+    let mut pkgpaths: Vec<&String> = program.pkgs.keys().collect();
+    pkgpaths.sort();
+    for pkgpath in pkgpaths {
+        gen_pkg_key(program, pkgpath, &mut keys, &mut visited);
+    }
+    keys
+}
+
+fn gen_pkg_key(
+    program: &Program,
+    pkgpath: &str,
+    keys: &mut IndexMap<String, String>,
+    visited: &mut IndexSet<String>,
+) {
+    if !visited.insert(pkgpath.to_string()) {
+        return;
+    }
+    let mut hasher = ContentHasher::new();
+    hasher.input_str(pkgpath);
+    for module in &program.pkgs[pkgpath] {
+        hasher.input_str(&module.filename);
+        // Prefer the source code in the program source map, which is much cheaper
+        // than formatting the AST.
+        match program
+            .source_map
+            .get_file_id(&module.filename)
+            .and_then(|id| program.source_map.get_file(id))
+        {
+            Some(file) => hasher.input_str(&file.src),
+            None => hasher.input_str(&format!("{:?}", module)),
+        };
+    }
+    for dep in pkg_imports(program, pkgpath) {
+        gen_pkg_key(program, &dep, keys, visited);
+        match keys.get(&dep) {
+            Some(key) => hasher.input_str(&dep).input_str(key),
+            None => return,
+        };
+    }
+    keys.insert(pkgpath.to_string(), hasher.result());
+}
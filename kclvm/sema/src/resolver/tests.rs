@@ -4,11 +4,13 @@ use crate::builtin::BUILTIN_FUNCTION_NAMES;
 use crate::pre_process::pre_process_program;
 use crate::resolver::resolve_program;
 use crate::resolver::scope::*;
+use crate::resolver::session::ResolverSession;
 use crate::ty::Type;
 use kclvm_ast::ast;
 use kclvm_error::*;
-use kclvm_parser::{load_program, parse_program};
+use kclvm_parser::{load_program, parse_program, LoadProgramOptions, MemoryFileSystem};
 use std::rc::Rc;
+use std::sync::Arc;
 
 #[test]
 fn test_scope() {
@@ -32,6 +34,7 @@ fn test_scope() {
 fn test_resolve_program() {
     let mut program = parse_program("./src/resolver/test_data/assign.k").unwrap();
     let scope = resolve_program(&mut program);
+    assert!(scope.check_diagnostics().is_ok());
     assert_eq!(scope.pkgpaths(), vec!["__main__".to_string()]);
     let main_scope = scope.main_scope().unwrap();
     let main_scope = main_scope.borrow_mut();
@@ -81,6 +84,10 @@ fn test_resolve_program_fail() {
     assert_eq!(diag.code, Some(DiagnosticId::Error(ErrorKind::TypeError)));
     assert_eq!(diag.messages.len(), 1);
     assert_eq!(diag.messages[0].message, "expect int, got {str:int(1)}");
+    match scope.check_diagnostics() {
+        Err(KclvmError::Compile(diags)) => assert_eq!(diags, vec![diag.clone()]),
+        other => panic!("unexpected result {:?}", other),
+    }
 }
 
 #[test]
@@ -231,3 +238,72 @@ fn test_lint() {
         assert_eq!(d1, d2);
     }
 }
+
+#[test]
+fn test_resolve_program_system_module_member_fail() {
+    let mut program =
+        parse_program("./src/resolver/test_fail_data/system_module_member.k").unwrap();
+    let scope = resolve_program(&mut program);
+    assert_eq!(scope.diagnostics.len(), 1);
+    let diag = &scope.diagnostics[0];
+    assert_eq!(diag.code, Some(DiagnosticId::Error(ErrorKind::TypeError)));
+    assert_eq!(diag.messages.len(), 1);
+    assert_eq!(diag.messages[0].pos.line, 3);
+    assert_eq!(diag.messages[0].message, "expect str, got int(1)");
+}
+
+#[test]
+fn test_resolver_session() {
+    let fs = MemoryFileSystem::default();
+    fs.insert("/konfig/kcl.mod", "");
+    fs.insert(
+        "/konfig/main.k",
+        "import app\nimport base\nb = base.Base {name = app.name}",
+    );
+    fs.insert("/konfig/app/app.k", "import base\nname = base.default_name");
+    fs.insert(
+        "/konfig/base/base.k",
+        "default_name = \"kcl\"\nschema Base:\n    name: str",
+    );
+    let fs = Arc::new(fs);
+    let load = || {
+        let opts = LoadProgramOptions {
+            fs: Some(fs.clone()),
+            ..Default::default()
+        };
+        load_program(&["/konfig/main.k"], Some(opts)).unwrap()
+    };
+    let mut session = ResolverSession::default();
+    let checked = |session: &ResolverSession| {
+        let mut pkgpaths = session.checked_pkgpaths().to_vec();
+        pkgpaths.sort();
+        pkgpaths
+    };
+    let scope = session.resolve(&mut load());
+    assert_eq!(scope.diagnostics.len(), 0);
+    assert_eq!(checked(&session), vec!["__main__", "app", "base"]);
+    // Nothing is changed, only the main package is checked.
+    let scope = session.resolve(&mut load());
+    assert_eq!(scope.diagnostics.len(), 0);
+    assert_eq!(checked(&session), vec!["__main__"]);
+    let base_scope = scope.scope_map["base"].borrow();
+    assert!(base_scope.lookup("Base").unwrap().borrow().ty.is_schema());
+    drop(base_scope);
+    // The dependents of the changed package are checked again.
+    fs.insert(
+        "/konfig/app/app.k",
+        "import base\nname = base.default_name + \"-app\"",
+    );
+    session.resolve(&mut load());
+    assert_eq!(checked(&session), vec!["__main__", "app"]);
+    fs.insert(
+        "/konfig/base/base.k",
+        "default_name = 1\nschema Base:\n    name: str",
+    );
+    let scope = session.resolve(&mut load());
+    assert_eq!(checked(&session), vec!["__main__", "app", "base"]);
+    assert!(!scope.diagnostics.is_empty());
+    session.invalidate("base");
+    session.resolve(&mut load());
+    assert_eq!(checked(&session), vec!["__main__", "app", "base"]);
+}
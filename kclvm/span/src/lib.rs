@@ -8,6 +8,7 @@
 //! Reference: https://github.com/rust-lang/rust/blob/master/compiler/rustc_span/src/lib.rs
 
 mod session_globals;
+pub mod source_map;
 pub mod span;
 pub mod symbol;
 
@@ -16,6 +17,7 @@ mod tests;
 
 pub use session_globals::create_session_globals_then;
 use session_globals::with_session_globals;
+pub use source_map::{FileId, ProgramLoc, ProgramSourceFile, ProgramSourceMap};
 pub use span::{BytePos, Span, DUMMY_SP};
 pub use symbol::{Ident, Symbol};
 
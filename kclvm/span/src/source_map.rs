@@ -0,0 +1,155 @@
+//! The program-wide source map shared by all the compiler stages.
+
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use rustc_span::Pos;
+
+use crate::{BytePos, SourceMap};
+
+/// The index of a source file in the [ProgramSourceMap].
+pub type FileId = usize;
+
+/// ProgramSourceMap records all the source files of a program in the load order. Every file
+/// gets a fixed [FileId] and address range, so a [BytePos] or a diagnostic is resolved to the
+/// same file and line by every compiler stage.
+///
+/// Different from [SourceMap], it is `Send` and `Sync` and can be shared among the parsing
+/// threads. [ProgramSourceMap::to_source_map] builds an equivalent [SourceMap] in which every
+/// file has the same start position.
+///
+/// # Examples
+///
+/// ```
+/// use kclvm_span::ProgramSourceMap;
+///
+/// let sm = ProgramSourceMap::default();
+/// let main = sm.add_file("main.k", "a = 1\nb = 2\n".to_string());
+/// let base = sm.add_file("base.k", "c = 3\n".to_string());
+/// let loc = sm.lookup_char_pos(sm.get_file(base).unwrap().start_pos).unwrap();
+/// assert_eq!((loc.filename.as_str(), loc.line, loc.col), ("base.k", 1, 0));
+/// assert_eq!(sm.add_file("main.k", "".to_string()), main);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ProgramSourceMap {
+    files: Arc<RwLock<ProgramSourceFiles>>,
+}
+
+#[derive(Debug, Default)]
+struct ProgramSourceFiles {
+    files: Vec<ProgramSourceFile>,
+    /// The address space below this value is used by the files, see [SourceMap].
+    used_address_space: u32,
+}
+
+/// A source file in the [ProgramSourceMap].
+#[derive(Debug, Clone)]
+pub struct ProgramSourceFile {
+    pub id: FileId,
+    pub name: String,
+    pub src: Arc<String>,
+    pub start_pos: BytePos,
+    /// The byte offsets of the line beginnings in the source.
+    lines: Arc<Vec<usize>>,
+}
+
+/// The file, line (1-based) and column (0-based, in chars) of a [BytePos].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramLoc {
+    pub file: FileId,
+    pub filename: String,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl ProgramSourceMap {
+    /// Add a source file and returns its id. A file already in the source map is not
+    /// added again and its id is returned.
+    pub fn add_file(&self, name: &str, src: String) -> FileId {
+        let src = normalize_src(src);
+        let mut files = self.files.write().unwrap();
+        if let Some(file) = files.files.iter().find(|file| file.name == name) {
+            return file.id;
+        }
+        // Allocate the address space in the same way as the [SourceMap], one more byte
+        // is used to distinguish the positions of the adjacent files.
+        let start_pos = files.used_address_space;
+        files.used_address_space = start_pos + src.len() as u32 + 1;
+        let mut lines = vec![0];
+        lines.extend(src.match_indices('\n').map(|(i, _)| i + 1));
+        let id = files.files.len();
+        files.files.push(ProgramSourceFile {
+            id,
+            name: name.to_string(),
+            src: Arc::new(src),
+            start_pos: BytePos::from_u32(start_pos),
+            lines: Arc::new(lines),
+        });
+        id
+    }
+
+    pub fn get_file(&self, id: FileId) -> Option<ProgramSourceFile> {
+        self.files.read().unwrap().files.get(id).cloned()
+    }
+
+    pub fn get_file_id(&self, name: &str) -> Option<FileId> {
+        let files = self.files.read().unwrap();
+        files
+            .files
+            .iter()
+            .find(|file| file.name == name)
+            .map(|file| file.id)
+    }
+
+    /// Returns all the source files in the load order.
+    pub fn files(&self) -> Vec<ProgramSourceFile> {
+        self.files.read().unwrap().files.clone()
+    }
+
+    /// Looks up the file, line and column of a [BytePos].
+    pub fn lookup_char_pos(&self, pos: BytePos) -> Option<ProgramLoc> {
+        let files = self.files.read().unwrap();
+        let index = files
+            .files
+            .partition_point(|file| file.start_pos <= pos)
+            .checked_sub(1)?;
+        let file = &files.files[index];
+        let offset = (pos - file.start_pos).to_usize();
+        if offset > file.src.len() {
+            return None;
+        }
+        let line = file.lines.partition_point(|start| *start <= offset);
+        let line_start = file.lines[line - 1];
+        let col = file.src.get(line_start..offset)?.chars().count();
+        Some(ProgramLoc {
+            file: file.id,
+            filename: file.name.clone(),
+            line,
+            col,
+        })
+    }
+
+    /// Builds a [SourceMap] on the current thread, the files are added in the load order and
+    /// have the same start positions as the ones in the program source map.
+    pub fn to_source_map(&self) -> SourceMap {
+        let sm = SourceMap::new(crate::FilePathMapping::empty());
+        for file in self.files() {
+            sm.new_source_file(PathBuf::from(&file.name).into(), file.src.as_ref().clone());
+        }
+        sm
+    }
+}
+
+/// Removes the UTF-8 BOM and replaces `\r\n` with `\n` in the same way as the
+/// [SourceMap], so the positions of the source files match the parsed ones.
+fn normalize_src(src: String) -> String {
+    let src = match src.strip_prefix('\u{feff}') {
+        Some(src) => src.to_string(),
+        None => src,
+    };
+    if src.contains("\r\n") {
+        src.replace("\r\n", "\n")
+    } else {
+        src
+    }
+}
@@ -25,3 +25,54 @@ fn interner_symbols() {
         assert_eq!(symbol2.as_u32(), symbol1.as_u32() + 1);
     });
 }
+
+#[test]
+fn program_source_map() {
+    use rustc_span::Pos;
+
+    let sm = ProgramSourceMap::default();
+    let main = sm.add_file("main.k", "a = 1\nb = 'ä'\n".to_string());
+    let empty = sm.add_file("empty.k", "".to_string());
+    let base = sm.add_file("base.k", "c = 3".to_string());
+    assert_eq!((main, empty, base), (0, 1, 2));
+    assert_eq!(sm.get_file_id("base.k"), Some(base));
+
+    let main_start = sm.get_file(main).unwrap().start_pos;
+    let loc = sm
+        .lookup_char_pos(main_start + BytePos::from_u32(13))
+        .unwrap();
+    assert_eq!((loc.file, loc.line, loc.col), (main, 2, 6));
+    let loc = sm
+        .lookup_char_pos(sm.get_file(empty).unwrap().start_pos)
+        .unwrap();
+    assert_eq!((loc.file, loc.line, loc.col), (empty, 1, 0));
+
+    // The files have the same start positions in the built source map.
+    let source_map = sm.to_source_map();
+    for file in sm.files() {
+        let loc = source_map.lookup_char_pos(file.start_pos);
+        assert_eq!(loc.file.start_pos, file.start_pos);
+    }
+}
+
+#[test]
+fn program_source_map_normalize_src() {
+    use rustc_span::Pos;
+
+    let sm = ProgramSourceMap::default();
+    let main = sm.add_file("main.k", "\u{feff}a = 1\r\nb = 2\r\n".to_string());
+    let file = sm.get_file(main).unwrap();
+    assert_eq!(file.src.as_str(), "a = 1\nb = 2\n");
+    let loc = sm
+        .lookup_char_pos(file.start_pos + BytePos::from_u32(10))
+        .unwrap();
+    assert_eq!((loc.file, loc.line, loc.col), (main, 2, 4));
+
+    // The normalized source is the same as the one in the built source map.
+    let source_map = sm.to_source_map();
+    let loc = source_map.lookup_char_pos(file.start_pos);
+    assert_eq!(
+        loc.file.end_pos,
+        file.start_pos + BytePos::from_usize(file.src.len())
+    );
+}
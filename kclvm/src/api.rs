@@ -0,0 +1,258 @@
+//! The high-level API to embed KCL in Rust programs.
+//!
+//! [Api] builds the arguments of a KCL program, executes it and returns the
+//! result as a [ValueRef], a [serde_json::Value] or any user type implementing
+//! [serde::Deserialize]. The KCL parse, compile and runtime errors are returned
+//! as [Error::Kcl] with their categories and positions.
+//!
+//! The library of the `kclvm` package is named `kclvm_cli_cdylib` after the
+//! shared library loaded by the KCL CLI, rename the dependency to use it as
+//! `kclvm` in Rust programs:
+//!
+//! ```toml
+//! [dependencies]
+//! kclvm = { package = "kclvm", git = "https://github.com/KusionStack/KCLVM" }
+//! ```
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # extern crate kclvm_cli_cdylib as kclvm;
+//! use kclvm::Api;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct App {
+//!     name: String,
+//!     replicas: i64,
+//! }
+//!
+//! let result = Api::new()
+//!     .source("main.k", "app = {name = option(\"name\"), replicas = 2}")
+//!     .option("name", "nginx")
+//!     .override_spec("app.replicas=3")
+//!     .run()
+//!     .unwrap();
+//! let app: App = result.get("app").unwrap();
+//! assert_eq!(app.name, "nginx");
+//! assert_eq!(app.replicas, 3);
+//! ```
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+pub use kclvm::ValueRef;
+use kclvm_ast::ast;
+use kclvm_error::KclvmError;
+use kclvm_query::r#override::parse_override_spec;
+use kclvm_runner::{exec_program, ExecProgramArgs};
+use serde::de::DeserializeOwned;
+
+/// The errors of executing a KCL program.
+#[derive(Debug)]
+pub enum Error {
+    /// A KCL load, compile or runtime error with its diagnostics.
+    Kcl(KclvmError),
+    /// An error without a KCL position, e.g. a missing file or an invalid override spec.
+    Other(String),
+    /// The result can not be deserialized into the user type.
+    Deserialize(serde_json::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Kcl(err) => write!(f, "{}", err),
+            Error::Other(msg) => write!(f, "{}", msg),
+            Error::Deserialize(err) => write!(f, "failed to deserialize the result: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<KclvmError> for Error {
+    fn from(err: KclvmError) -> Self {
+        Error::Kcl(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Deserialize(err)
+    }
+}
+
+/// The builder of a KCL program execution.
+#[derive(Debug, Default, Clone)]
+pub struct Api {
+    args: ExecProgramArgs,
+    /// The in-memory sources, they are loaded before the files.
+    sources: Vec<(String, String)>,
+    override_specs: Vec<String>,
+    plugin_agent: u64,
+}
+
+impl Api {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the directory the files are relative to.
+    pub fn work_dir(mut self, work_dir: &str) -> Self {
+        self.args.work_dir = Some(work_dir.to_string());
+        self
+    }
+
+    /// Adds a KCL file or package directory to execute.
+    pub fn file(mut self, file: &str) -> Self {
+        self.args.k_filename_list.push(file.to_string());
+        self
+    }
+
+    /// Adds the KCL files or package directories to execute.
+    pub fn files<I, S>(mut self, files: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.args
+            .k_filename_list
+            .extend(files.into_iter().map(|file| file.as_ref().to_string()));
+        self
+    }
+
+    /// Adds an in-memory KCL source with its file name, which needs not exist on disk.
+    pub fn source(mut self, filename: &str, code: &str) -> Self {
+        self.sources.push((filename.to_string(), code.to_string()));
+        self
+    }
+
+    /// Sets the top-level argument read by `option(name)`, the same as `-D name=value`.
+    pub fn option(mut self, name: &str, value: &str) -> Self {
+        self.args.args.push(ast::CmdArgSpec {
+            name: name.to_string(),
+            value: value.to_string(),
+        });
+        self
+    }
+
+    /// Adds an override spec such as `app.replicas=3` or `app.labels-`, the same as `-O`.
+    pub fn override_spec(mut self, spec: &str) -> Self {
+        self.override_specs.push(spec.to_string());
+        self
+    }
+
+    pub fn strict_range_check(mut self, strict_range_check: bool) -> Self {
+        self.args.strict_range_check = strict_range_check;
+        self
+    }
+
+    /// Omits the None values in the result.
+    pub fn disable_none(mut self, disable_none: bool) -> Self {
+        self.args.disable_none = disable_none;
+        self
+    }
+
+    pub fn sort_keys(mut self, sort_keys: bool) -> Self {
+        self.args.sort_keys = sort_keys;
+        self
+    }
+
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.args.debug = debug as i32;
+        self
+    }
+
+    /// Sets the plugin agent function pointer to call the KCL plugins.
+    pub fn plugin_agent(mut self, plugin_agent: u64) -> Self {
+        self.plugin_agent = plugin_agent;
+        self
+    }
+
+    /// Returns the arguments passed to [exec_program].
+    pub fn exec_args(&self) -> Result<ExecProgramArgs, Error> {
+        let mut args = self.args.clone();
+        // The code list is matched with the first files.
+        args.k_filename_list = self
+            .sources
+            .iter()
+            .map(|(filename, _)| filename.clone())
+            .chain(self.args.k_filename_list.iter().cloned())
+            .collect();
+        args.k_code_list = self.sources.iter().map(|(_, code)| code.clone()).collect();
+        for spec in &self.override_specs {
+            args.overrides
+                .push(parse_override_spec(spec).map_err(|err| Error::Other(err.to_string()))?);
+        }
+        Ok(args)
+    }
+
+    /// Executes the program and returns its result.
+    pub fn run(&self) -> Result<ExecResult, Error> {
+        let args = self.exec_args()?;
+        if args.k_filename_list.is_empty() {
+            return Err(Error::Other("no KCL files or sources".to_string()));
+        }
+        // The compile errors are raised as panics with the JSON encoded panic info.
+        let result = catch_unwind(AssertUnwindSafe(|| exec_program(&args, self.plugin_agent)));
+        let result = match result {
+            Ok(result) => result?,
+            Err(err) => return Err(Error::Kcl(KclvmError::from_panic(err))),
+        };
+        let value = if result.json_result.is_empty() {
+            ValueRef::dict(None)
+        } else {
+            ValueRef::from_json(&result.json_result).map_err(Error::Deserialize)?
+        };
+        Ok(ExecResult {
+            value,
+            json_result: result.json_result,
+            yaml_result: result.yaml_result,
+        })
+    }
+}
+
+/// The result of a KCL program, which is the dict of its top-level values.
+#[derive(Debug, Clone)]
+pub struct ExecResult {
+    value: ValueRef,
+    json_result: String,
+    yaml_result: String,
+}
+
+impl ExecResult {
+    /// Returns the result value.
+    pub fn value(&self) -> &ValueRef {
+        &self.value
+    }
+
+    pub fn json_result(&self) -> &str {
+        &self.json_result
+    }
+
+    pub fn yaml_result(&self) -> &str {
+        &self.yaml_result
+    }
+
+    /// Returns the result as a JSON value.
+    pub fn to_json_value(&self) -> Result<serde_json::Value, Error> {
+        self.deserialize()
+    }
+
+    /// Deserializes the result into the user type.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        if self.json_result.is_empty() {
+            return Ok(serde_json::from_str("{}")?);
+        }
+        Ok(serde_json::from_str(&self.json_result)?)
+    }
+
+    /// Deserializes the top-level value `key` into the user type.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<T, Error> {
+        let mut value = self.to_json_value()?;
+        match value.get_mut(key) {
+            Some(value) => Ok(serde_json::from_value(value.take())?),
+            None => Err(Error::Other(format!("no top-level value '{}'", key))),
+        }
+    }
+}
@@ -1,5 +1,12 @@
 extern crate serde;
 
+pub mod api;
+pub use api::{Api, Error, ExecResult, ValueRef};
+
+#[cfg(test)]
+mod tests;
+
+use kclvm_error::{KclvmError, KclvmResult};
 use kclvm_parser::load_program;
 use kclvm_query::apply_overrides;
 use kclvm_runner::execute;
@@ -23,8 +30,9 @@ pub extern "C" fn kclvm_cli_run(args: *const i8, plugin_agent: *const i8) -> *co
                 let ptr = c_string.into_raw();
                 ptr as *const i8
             }
-            Err(result) => {
-                let result = format!("ERROR:{}", result);
+            Err(err) => {
+                // The callers parse the panic info JSON string after the prefix.
+                let result = format!("ERROR:{}", err.to_panic_info().to_json_string());
                 let c_string =
                     std::ffi::CString::new(result.as_str()).expect("CString::new failed");
                 let ptr = c_string.into_raw();
@@ -50,7 +58,7 @@ pub extern "C" fn kclvm_cli_run(args: *const i8, plugin_agent: *const i8) -> *co
     }
 }
 
-pub fn kclvm_cli_run_unsafe(args: *const i8, plugin_agent: *const i8) -> Result<String, String> {
+pub fn kclvm_cli_run_unsafe(args: *const i8, plugin_agent: *const i8) -> KclvmResult<String> {
     let args = ExecProgramArgs::from_str(kclvm::c2str(args));
     let plugin_agent = plugin_agent as u64;
 
@@ -60,7 +68,7 @@ pub fn kclvm_cli_run_unsafe(args: *const i8, plugin_agent: *const i8) -> Result<
     // Parse AST program.
     let mut program = load_program(&files, Some(opts))?;
     if let Err(msg) = apply_overrides(&mut program, &args.overrides, &[], args.print_override_ast) {
-        return Err(msg.to_string());
+        return Err(KclvmError::load(&msg.to_string()));
     }
 
     // Resolve AST program, generate libs, link libs and execute.
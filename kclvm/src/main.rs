@@ -4,11 +4,29 @@
 extern crate clap;
 
 use clap::ArgMatches;
-use kclvm::PanicInfo;
+use kclvm_capi::service::server::{Server, DEFAULT_SERVER_THREADS};
+use kclvm_capi::service::service::KclvmService;
+use kclvm_config::cache::{
+    clean_cache, format_size, get_cache_dir, get_cache_root, get_cache_stats, parse_size,
+    prune_cache, CacheOption,
+};
 use kclvm_config::settings::{load_file, merge_settings, SettingsFile};
-use kclvm_error::Handler;
-use kclvm_runner::{exec_program, ExecProgramArgs};
-use kclvm_tools::lint::lint_files;
+use kclvm_error::{Handler, KclvmError};
+use kclvm_parser::deps::load_dep_graph;
+use kclvm_runner::{build_program, exec_program, ExecProgramArgs};
+use kclvm_sema::resolver::session::ResolverSession;
+use kclvm_tools::affected::{
+    affected_targets, changed_files, load_mod_targets, load_settings_target,
+};
+use kclvm_tools::doc::{gen_doc, DocFormat, DocOptions};
+use kclvm_tools::jsonschema::{
+    import_file, infer_files, ImportFormat, ImportOptions, InferOptions,
+};
+use kclvm_tools::lint::{lint_files, lint_files_with_session};
+use kclvm_tools::testing::{check_snapshot, diff_yaml, run_tests, SnapshotResult, TestOptions};
+use kclvm_tools::watch::{watch_paths, WatchOptions, Watcher};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
 
 fn main() {
     let matches = clap_app!(kcl =>
@@ -21,6 +39,14 @@ fn main() {
             (@arg debug: -d --debug "Run in debug mode (for developers only)")
             (@arg sort_key: -k --sort "Sort result keys")
             (@arg ARGUMENT: ... -D --argument "Specify the top-level argument")
+            (@arg SNAPSHOT: --snapshot +takes_value "Compare the YAML result with the snapshot file")
+            (@arg update: --update requires[SNAPSHOT] "Rewrite the snapshot file with the YAML result")
+            (@arg watch: --watch "Run again and print the YAML result diff after the source files change")
+        )
+        (@subcommand build =>
+            (@arg INPUT: ... "Sets the input file to use")
+            (@arg OUTPUT: -o --output +takes_value "Sets the executable output file path, defaults to a.out")
+            (@arg SETTING: ... -Y --setting +takes_value "Sets the input file to use")
         )
         (@subcommand lint =>
             (@arg INPUT: ... "Sets the input file to use")
@@ -32,6 +58,59 @@ fn main() {
             (@arg sort_key: -k --sort "Sort result keys")
             (@arg ARGUMENT: ... -D --argument "Specify the top-level argument")
             (@arg EMIT_WARNING: --emit_warning "Emit warning message")
+            (@arg watch: --watch "Lint again after the source files change")
+        )
+        (@subcommand deps =>
+            (@arg INPUT: ... "Sets the input file to use")
+            (@arg SETTING: ... -Y --setting +takes_value "Sets the input file to use")
+            (@arg PKG: -p --pkg +takes_value "Sets the package path to query, defaults to the main package")
+            (@arg reverse: -r --reverse "List the packages which import the package instead")
+            (@arg cycles: --cycles "List the import cycles and fail if there are any")
+            (@arg dot: --dot "Print the dependency graph in the DOT format")
+        )
+        (@subcommand affected =>
+            (@arg ROOT: "Sets the directory of the kcl.mod with the targets, defaults to the current directory")
+            (@arg BASE: --base +takes_value +required "Sets the git revision to diff with or the file listing the changed files")
+            (@arg SETTING: ... -Y --setting +takes_value "Sets the settings files of the targets")
+            (@arg verbose: -v --verbose "Print the changed files affecting each target")
+        )
+        (@subcommand doc =>
+            (@arg INPUT: ... "Sets the input file to use")
+            (@arg OUTPUT: -o --output +takes_value "Sets the documents output directory, defaults to docs")
+            (@arg SETTING: ... -Y --setting +takes_value "Sets the input file to use")
+            (@arg FORMAT: -f --format +takes_value "Sets the documents format, md or html, defaults to md")
+            (@arg include_private: --include_private "Document the private schemas and attributes")
+        )
+        (@subcommand import =>
+            (@arg INPUT: ... +required "Sets the JSON Schema, OpenAPI, CRD or sample data files to import")
+            (@arg OUTPUT: -o --output +takes_value "Sets the KCL output file path, defaults to stdout")
+            (@arg MODE: -m --mode +takes_value "Sets the input format, jsonschema, openapi or crd, defaults to jsonschema")
+            (@arg NAME: -n --name +takes_value "Sets the root schema name of the JSON Schema or the sample data")
+            (@arg infer_schema: --infer-schema "Infer the schemas from the sample JSON or YAML data")
+        )
+        (@subcommand test =>
+            (@arg INPUT: "Sets the package directory or the test file, defaults to the current directory")
+            (@arg SETTING: ... -Y --setting +takes_value "Sets the input file to use")
+            (@arg RUN: --run +takes_value "Run only the tests whose names match the regex")
+            (@arg fail_fast: --fail-fast "Stop running the tests after the first failure")
+            (@arg JUNIT: --junit +takes_value "Sets the JUnit XML report output file path")
+            (@arg verbose: -v --verbose "Print the passed tests")
+        )
+        (@subcommand server =>
+            (@arg LISTEN: --listen +takes_value "Sets the address to listen on, defaults to 127.0.0.1:2021")
+            (@arg THREADS: --threads +takes_value "Sets the number of threads to handle the requests, defaults to 8")
+        )
+        (@subcommand cache =>
+            (@subcommand info =>
+                (@arg ROOT: "Sets the program root directory, defaults to the current directory")
+            )
+            (@subcommand clean =>
+                (@arg ROOT: "Sets the program root directory, defaults to the current directory")
+            )
+            (@subcommand prune =>
+                (@arg ROOT: "Sets the program root directory, defaults to the current directory")
+                (@arg MAX_SIZE: --max-size +takes_value "Sets the max cache size such as 512M, defaults to 1G")
+            )
         )
     )
     .arg_required_else_help(true)
@@ -40,18 +119,76 @@ fn main() {
         let (files, setting) = (matches.values_of("INPUT"), matches.values_of("SETTING"));
         match (files, setting) {
             (None, None) => println!("Error: no KCL files"),
+            (_, _) if matches.occurrences_of("watch") > 0 => {
+                let args: ExecProgramArgs = build_settings(matches).into();
+                let mut last_yaml_result: Option<String> = None;
+                watch(&args, || match exec_program(&args, 1) {
+                    Ok(result) => {
+                        match &last_yaml_result {
+                            Some(last) => match diff_yaml(last, &result.yaml_result) {
+                                Ok(diffs) if diffs.is_empty() => println!("no changes"),
+                                Ok(diffs) => {
+                                    for diff in diffs {
+                                        println!("  {}", diff);
+                                    }
+                                }
+                                Err(_) => println!("{}", result.yaml_result),
+                            },
+                            None => println!("{}", result.yaml_result),
+                        }
+                        last_yaml_result = Some(result.yaml_result);
+                    }
+                    Err(err) => emit_error(&err),
+                });
+            }
             (_, _) => {
                 // Config settings build
                 let settings = build_settings(matches);
                 match exec_program(&settings.into(), 1) {
-                    Ok(result) => {
-                        println!("{}", result.yaml_result);
+                    Ok(result) => match matches.value_of("SNAPSHOT") {
+                        Some(snapshot) => {
+                            let update = matches.occurrences_of("update") > 0;
+                            match check_snapshot(snapshot, &result.yaml_result, update) {
+                                Ok(SnapshotResult::Matched) => {
+                                    println!("snapshot {} matched", snapshot)
+                                }
+                                Ok(SnapshotResult::Updated) => {
+                                    println!("snapshot {} updated", snapshot)
+                                }
+                                Ok(SnapshotResult::Mismatched(diffs)) => {
+                                    println!("snapshot {} mismatched:", snapshot);
+                                    for diff in diffs {
+                                        println!("  {}", diff);
+                                    }
+                                    std::process::exit(1);
+                                }
+                                Err(err) => {
+                                    println!("Error: {}", err);
+                                    std::process::exit(1);
+                                }
+                            }
+                        }
+                        None => println!("{}", result.yaml_result),
+                    },
+                    Err(err) => {
+                        emit_error(&err);
+                        std::process::exit(1);
                     }
-                    Err(msg) => {
-                        let mut handler = Handler::default();
-                        handler
-                            .add_panic_info(&PanicInfo::from_json_string(&msg))
-                            .abort_if_any_errors();
+                }
+            }
+        }
+    } else if let Some(matches) = matches.subcommand_matches("build") {
+        let (files, setting) = (matches.values_of("INPUT"), matches.values_of("SETTING"));
+        match (files, setting) {
+            (None, None) => println!("Error: no KCL files"),
+            (_, _) => {
+                let settings = build_settings(matches);
+                let output = matches.value_of("OUTPUT").unwrap_or("a.out");
+                match build_program(&settings.into(), output) {
+                    Ok(exe_path) => println!("{}", exe_path),
+                    Err(err) => {
+                        println!("Error: {}", err);
+                        std::process::exit(1);
                     }
                 }
             }
@@ -74,16 +211,362 @@ fn main() {
                 } else {
                     args.get_files()
                 };
+                let emit_warning = matches.occurrences_of("EMIT_WARNING") > 0;
                 let (mut err_handler, mut warning_handler) =
                     (Handler::default(), Handler::default());
+                if matches.occurrences_of("watch") > 0 {
+                    let mut session = ResolverSession::default();
+                    watch(&args, || {
+                        (err_handler.diagnostics, warning_handler.diagnostics) =
+                            lint_files_with_session(
+                                &files,
+                                Some(args.get_load_program_options()),
+                                &mut session,
+                            );
+                        err_handler.emit();
+                        if emit_warning {
+                            warning_handler.emit();
+                        }
+                    });
+                }
                 (err_handler.diagnostics, warning_handler.diagnostics) =
                     lint_files(&files, Some(args.get_load_program_options()));
                 err_handler.emit();
-                if matches.occurrences_of("EMIT_WARNING") > 0 {
+                if emit_warning {
                     warning_handler.emit();
                 }
             }
         }
+    } else if let Some(matches) = matches.subcommand_matches("deps") {
+        let (files, setting) = (matches.values_of("INPUT"), matches.values_of("SETTING"));
+        match (files, setting) {
+            (None, None) => println!("Error: no KCL files"),
+            (_, _) => {
+                // Config settings build
+                let settings = build_settings(matches);
+                // Convert settings into execute arguments.
+                let args: ExecProgramArgs = settings.into();
+                let graph = match load_dep_graph(
+                    &args.get_files(),
+                    Some(args.get_load_program_options()),
+                ) {
+                    Ok(graph) => graph,
+                    Err(err) => {
+                        emit_error(&err);
+                        std::process::exit(1);
+                    }
+                };
+                let pkgpath = matches.value_of("PKG").unwrap_or(kclvm_ast::MAIN_PKG);
+                if matches.occurrences_of("dot") > 0 {
+                    print!("{}", graph.to_dot());
+                } else if matches.occurrences_of("cycles") > 0 {
+                    let cycles = graph.cycles();
+                    for cycle in &cycles {
+                        println!("{}", cycle.join(" -> "));
+                    }
+                    if !cycles.is_empty() {
+                        std::process::exit(1);
+                    }
+                } else if !graph.pkgs.contains_key(pkgpath) {
+                    println!("Error: package '{}' not found", pkgpath);
+                    std::process::exit(1);
+                } else if matches.occurrences_of("reverse") > 0 {
+                    let importers = graph.importers(pkgpath);
+                    for dependent in graph.dependents(pkgpath) {
+                        println!("{}", dependent);
+                        // The positions of the direct imports.
+                        for edge in importers.iter().filter(|edge| edge.from == dependent) {
+                            println!("  {}:{}:{}", edge.filename, edge.line, edge.column);
+                        }
+                    }
+                } else {
+                    for file in graph.dep_files(pkgpath) {
+                        println!("{}", file);
+                    }
+                }
+            }
+        }
+    } else if let Some(matches) = matches.subcommand_matches("affected") {
+        let root = matches.value_of("ROOT").unwrap_or(".");
+        let verbose = matches.occurrences_of("verbose") > 0;
+        let mut targets = load_mod_targets(root);
+        for settings_file in matches.values_of("SETTING").into_iter().flatten() {
+            match load_settings_target(settings_file) {
+                Ok(target) => targets.push(target),
+                Err(err) => {
+                    println!("Error: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        if targets.is_empty() {
+            println!("Error: no targets in the settings files or kcl.mod");
+            std::process::exit(1);
+        }
+        let changed_files = match changed_files(matches.value_of("BASE").unwrap_or_default()) {
+            Ok(files) => files,
+            Err(err) => {
+                println!("Error: {}", err);
+                std::process::exit(1);
+            }
+        };
+        for affected in affected_targets(&targets, &changed_files) {
+            println!("{}", affected.target.name);
+            if verbose {
+                for file in &affected.changed_files {
+                    println!("  {}", file);
+                }
+                if let Some(err) = &affected.error {
+                    println!("  failed to load: {}", err);
+                }
+            }
+        }
+    } else if let Some(matches) = matches.subcommand_matches("doc") {
+        let (files, setting) = (matches.values_of("INPUT"), matches.values_of("SETTING"));
+        match (files, setting) {
+            (None, None) => println!("Error: no KCL files"),
+            (_, _) => {
+                let format = match DocFormat::try_from(matches.value_of("FORMAT").unwrap_or("md")) {
+                    Ok(format) => format,
+                    Err(err) => {
+                        println!("Error: {}", err);
+                        std::process::exit(1);
+                    }
+                };
+                let opts = DocOptions {
+                    format,
+                    output_dir: matches.value_of("OUTPUT").unwrap_or("docs").to_string(),
+                    include_private: matches.occurrences_of("include_private") > 0,
+                };
+                // Config settings build
+                let settings = build_settings(matches);
+                // Convert settings into execute arguments.
+                let args: ExecProgramArgs = settings.into();
+                match gen_doc(
+                    &args.get_files(),
+                    &opts,
+                    Some(args.get_load_program_options()),
+                ) {
+                    Ok(files) => {
+                        for file in files {
+                            println!("{}", file);
+                        }
+                    }
+                    Err(err) => {
+                        println!("Error: {}", err);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+    } else if let Some(matches) = matches.subcommand_matches("import") {
+        let files: Vec<&str> = match matches.values_of("INPUT") {
+            Some(files) => files.collect(),
+            None => vec![],
+        };
+        let name = matches.value_of("NAME").unwrap_or_default().to_string();
+        let result = if matches.occurrences_of("infer_schema") > 0 {
+            infer_files(&files, &InferOptions { name })
+        } else if files.len() > 1 {
+            println!(
+                "Error: only the sample data inference with --infer-schema accepts multiple inputs"
+            );
+            std::process::exit(1);
+        } else {
+            ImportFormat::try_from(matches.value_of("MODE").unwrap_or("jsonschema"))
+                .and_then(|format| import_file(files[0], &ImportOptions { format, name }))
+        };
+        match result {
+            Ok(code) => match matches.value_of("OUTPUT") {
+                Some(output) => {
+                    if let Err(err) = std::fs::write(output, code) {
+                        println!("Error: {}", err);
+                        std::process::exit(1);
+                    }
+                }
+                None => print!("{}", code),
+            },
+            Err(err) => {
+                println!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+    } else if let Some(matches) = matches.subcommand_matches("test") {
+        let path = matches.value_of("INPUT").unwrap_or(".");
+        let verbose = matches.occurrences_of("verbose") > 0;
+        let mut args: ExecProgramArgs = build_settings(matches).into();
+        // The test files are discovered from the path instead of the settings.
+        args.k_filename_list.clear();
+        let opts = TestOptions {
+            run: matches.value_of("RUN").map(|run| run.to_string()),
+            fail_fast: matches.occurrences_of("fail_fast") > 0,
+            exec_args: args,
+        };
+        let result = run_tests(path, &opts, |case| match &case.error {
+            Some(err) => {
+                println!(
+                    "--- FAIL: {} ({:.2}s)",
+                    case.case.name,
+                    case.duration.as_secs_f64()
+                );
+                println!("    {}", err);
+            }
+            None if verbose => {
+                println!(
+                    "--- PASS: {} ({:.2}s)",
+                    case.case.name,
+                    case.duration.as_secs_f64()
+                );
+            }
+            None => {}
+        });
+        match result {
+            Ok(result) => {
+                for suite in &result.suites {
+                    let status = if suite.failures() > 0 { "FAIL" } else { "ok" };
+                    println!(
+                        "{}\t{}\t{:.2}s",
+                        status,
+                        suite.pkg_dir,
+                        suite.duration.as_secs_f64()
+                    );
+                }
+                if let Some(junit) = matches.value_of("JUNIT") {
+                    if let Err(err) = std::fs::write(junit, result.to_junit_xml()) {
+                        println!("Error: {}", err);
+                        std::process::exit(1);
+                    }
+                }
+                if !result.is_ok() {
+                    std::process::exit(1);
+                }
+            }
+            Err(err) => {
+                println!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+    } else if let Some(matches) = matches.subcommand_matches("server") {
+        let listen = matches.value_of("LISTEN").unwrap_or("127.0.0.1:2021");
+        let threads = match matches.value_of("THREADS") {
+            Some(threads) => match threads.parse::<usize>() {
+                Ok(threads) if threads > 0 => threads,
+                _ => {
+                    println!("Error: invalid thread count '{}'", threads);
+                    std::process::exit(1);
+                }
+            },
+            None => DEFAULT_SERVER_THREADS,
+        };
+        let server = match Server::bind(listen, KclvmService::default(), threads) {
+            Ok(server) => server,
+            Err(err) => {
+                println!("Error: failed to listen on {}: {}", listen, err);
+                std::process::exit(1);
+            }
+        };
+        let handle = server.shutdown_handle();
+        // Stop accepting requests and wait for the handling ones on Ctrl-C or SIGTERM.
+        if let Err(err) = ctrlc::set_handler(move || handle.shutdown()) {
+            println!("Error: {}", err);
+            std::process::exit(1);
+        }
+        match server.local_addr() {
+            Ok(addr) => println!("KCL server listening on http://{}", addr),
+            Err(_) => println!("KCL server listening on http://{}", listen),
+        }
+        if let Err(err) = server.serve() {
+            println!("Error: {}", err);
+            std::process::exit(1);
+        }
+    } else if let Some(matches) = matches.subcommand_matches("cache") {
+        let option = CacheOption::default();
+        if let Some(matches) = matches.subcommand_matches("info") {
+            let root = matches.value_of("ROOT").unwrap_or(".");
+            println!("cache directory: {}", get_cache_root(root, None));
+            let current_dir = get_cache_dir(root, None);
+            for stat in get_cache_stats(root, &option) {
+                println!(
+                    "{}\t{} entries\t{}{}",
+                    stat.version,
+                    stat.entries,
+                    format_size(stat.size),
+                    if stat.path == current_dir {
+                        "\t(current)"
+                    } else {
+                        ""
+                    }
+                );
+            }
+        } else if let Some(matches) = matches.subcommand_matches("clean") {
+            let root = matches.value_of("ROOT").unwrap_or(".");
+            match clean_cache(root, &option) {
+                Ok(size) => println!("removed {}", format_size(size)),
+                Err(err) => {
+                    println!("Error: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        } else if let Some(matches) = matches.subcommand_matches("prune") {
+            let root = matches.value_of("ROOT").unwrap_or(".");
+            let option = match matches.value_of("MAX_SIZE").map(parse_size) {
+                Some(Ok(max_size)) => option.with_max_size(max_size),
+                Some(Err(err)) => {
+                    println!("Error: {}", err);
+                    std::process::exit(1);
+                }
+                None => option,
+            };
+            match prune_cache(root, &option) {
+                Ok(size) => println!("removed {}", format_size(size)),
+                Err(err) => {
+                    println!("Error: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+/// Call `run` once and again after every change of the program source files, until
+/// the process is interrupted. The errors and panics in `run` are printed and do not
+/// stop watching.
+fn watch<F: FnMut()>(args: &ExecProgramArgs, mut run: F) -> ! {
+    // The compile errors are reported by `emit_error` instead of the panic hook.
+    std::panic::set_hook(Box::new(|_| {}));
+    let files = args.get_files();
+    let mut watcher = Watcher::new(WatchOptions::default());
+    loop {
+        // Discover the paths before running, so the changes during the run are not missed.
+        let paths = match watch_paths(&files, Some(args.get_load_program_options())) {
+            Ok(paths) => paths,
+            // Watch the previous paths until the program can be loaded again.
+            Err(_) if !watcher.paths().is_empty() => watcher.paths().to_vec(),
+            Err(_) => files.iter().map(PathBuf::from).collect(),
+        };
+        watcher.set_paths(paths);
+        if let Err(err) = catch_unwind(AssertUnwindSafe(&mut run)) {
+            emit_error(&KclvmError::from_panic(err));
+        }
+        let changed = watcher.wait();
+        println!(
+            "\n{} changed, running again",
+            changed
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+    }
+}
+
+/// Print the diagnostics of the KCL error or the plain error message.
+fn emit_error(err: &KclvmError) {
+    match err {
+        KclvmError::Other(_) => println!("Error: {}", err),
+        _ => {
+            Handler::default().add_kclvm_error(err).emit();
+        }
     }
 }
 
@@ -0,0 +1,65 @@
+use crate::{Api, Error};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct App {
+    name: String,
+    replicas: i64,
+}
+
+#[test]
+fn test_api_run() {
+    let result = Api::new()
+        .source("main.k", "app = {name = option(\"name\"), replicas = 2}")
+        .option("name", "nginx")
+        .override_spec("app.replicas=3")
+        .run()
+        .unwrap();
+    assert_eq!(
+        result.get::<App>("app").unwrap(),
+        App {
+            name: "nginx".to_string(),
+            replicas: 3,
+        }
+    );
+    assert_eq!(result.yaml_result(), "app:\n  name: nginx\n  replicas: 3\n");
+    assert!(result.value().get_by_key("app").unwrap().is_dict());
+    assert!(matches!(result.get::<App>("web"), Err(Error::Other(_))));
+    assert!(matches!(
+        result.get::<String>("app"),
+        Err(Error::Deserialize(_))
+    ));
+}
+
+#[test]
+fn test_api_run_errors() {
+    assert!(matches!(Api::new().run(), Err(Error::Other(_))));
+    assert!(matches!(
+        Api::new()
+            .source("main.k", "a = 1")
+            .override_spec("app.replicas")
+            .run(),
+        Err(Error::Other(_))
+    ));
+    // The compile errors are returned with their positions.
+    match Api::new().source("main.k", "a: int = \"1\"").run() {
+        Err(Error::Kcl(err)) => {
+            let diag = &err.diagnostics()[0];
+            assert_eq!(diag.messages[0].pos.filename, "main.k");
+            assert_eq!(diag.messages[0].pos.line, 1);
+        }
+        other => panic!(
+            "unexpected result {:?}",
+            other.map(|r| r.yaml_result().to_string())
+        ),
+    }
+    // The runtime errors are returned, and the next runs are not affected.
+    assert!(matches!(
+        Api::new()
+            .source("main.k", "assert False, \"failed\"")
+            .run(),
+        Err(Error::Kcl(_))
+    ));
+    let result = Api::new().source("main.k", "a = 1").run().unwrap();
+    assert_eq!(result.yaml_result(), "a: 1\n");
+}
@@ -11,6 +11,9 @@ import kclvm_runtime
 # sha256(value: str, encoding: str = "utf-8") -> str
 # sha384(value: str, encoding: str = "utf-8") -> str
 # sha512(value: str, encoding: str = "utf-8") -> str
+# uuid(name: str = "", namespace: str = "dns") -> str
+# hmac_sha256(key: str, msg: str) -> str
+# blake3(value: str, encoding: str = "utf-8") -> str
 
 _Dylib = kclvm_runtime.KclvmRuntimeDylib()
 
@@ -38,6 +41,15 @@ class BaseTest(unittest.TestCase):
     def sha512(self, value: str) -> str:
         return self.dylib.Invoke(f"crypto.sha512", value)
 
+    def uuid(self, *args) -> str:
+        return self.dylib.Invoke(f"crypto.uuid", *args)
+
+    def hmac_sha256(self, key: str, msg: str) -> str:
+        return self.dylib.Invoke(f"crypto.hmac_sha256", key, msg)
+
+    def blake3(self, value: str) -> str:
+        return self.dylib.Invoke(f"crypto.blake3", value)
+
     def test_md5(self):
         self.assertEqual(
             self.md5("The quick brown fox jumps over the lazy dog"),
@@ -79,5 +91,29 @@ class BaseTest(unittest.TestCase):
         )
 
 
+    def test_uuid(self):
+        self.assertEqual(len(self.uuid()), 36)
+        self.assertNotEqual(self.uuid(), self.uuid())
+        self.assertEqual(
+            self.uuid("kcl-lang.io"), "95a555ea-1d0b-5fcd-ae96-201599016553"
+        )
+        self.assertEqual(
+            self.uuid("https://kcl-lang.io", "url"),
+            "d710c93c-d881-543e-a2e5-660a6cdfddae",
+        )
+
+    def test_hmac_sha256(self):
+        self.assertEqual(
+            self.hmac_sha256("key", "The quick brown fox jumps over the lazy dog"),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8",
+        )
+
+    def test_blake3(self):
+        self.assertEqual(
+            self.blake3(""),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+        )
+
+
 if __name__ == "__main__":
     unittest.main()
@@ -0,0 +1,268 @@
+//! [kclvm_tools::affected] module finds the entry programs affected by a set of
+//! changed files, so CI only renders the configs changed by a pull request.
+//!
+//! The entry programs (targets) are the settings files with `kcl_cli_configs.files`
+//! and the `[[targets]]` in kcl.mod. A target is affected when a changed file is
+//!
+//! - one of the files of the main package or any package it imports transitively,
+//! - a new or removed KCL file in the directory of an imported package,
+//! - the `.zip`/`.tar.gz` archive an imported package is loaded from,
+//! - the kcl.mod file of the program root or the settings file of the target.
+//!
+//! A target which can not be loaded is always affected.
+use anyhow::{anyhow, Result};
+use kclvm_config::modfile::{load_mod_file, KCL_FILE_SUFFIX, KCL_MOD_FILE};
+use kclvm_config::settings::SettingsFile;
+use kclvm_config::vfs::normalize_path;
+use kclvm_parser::deps::load_dep_graph;
+use kclvm_parser::LoadProgramOptions;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[cfg(test)]
+mod tests;
+
+/// An entry program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub name: String,
+    /// The directory the files are relative to.
+    pub work_dir: String,
+    pub files: Vec<String>,
+    /// The settings file which defines the target.
+    pub settings_file: Option<String>,
+}
+
+/// An affected target and the changed files affecting it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AffectedTarget {
+    pub target: Target,
+    pub changed_files: Vec<String>,
+    /// The error when the target can not be loaded.
+    pub error: Option<String>,
+}
+
+/// Load the target from the settings file, the files in it are relative to the
+/// directory of the settings file.
+pub fn load_settings_target(settings_file: &str) -> Result<Target> {
+    let content = std::fs::read_to_string(settings_file)
+        .map_err(|err| anyhow!("failed to read '{}': {}", settings_file, err))?;
+    let settings: SettingsFile = serde_yaml::from_str(&content)
+        .map_err(|err| anyhow!("invalid settings file '{}': {}", settings_file, err))?;
+    let files = match settings.kcl_cli_configs {
+        Some(config) => match config.files {
+            Some(files) if !files.is_empty() => files,
+            _ => config.file.unwrap_or_default(),
+        },
+        None => vec![],
+    };
+    if files.is_empty() {
+        return Err(anyhow!(
+            "no KCL files in the settings file '{}'",
+            settings_file
+        ));
+    }
+    Ok(Target {
+        name: settings_file.to_string(),
+        work_dir: parent_dir(settings_file),
+        files,
+        settings_file: Some(settings_file.to_string()),
+    })
+}
+
+/// Load the `[[targets]]` of the kcl.mod file under the root directory.
+pub fn load_mod_targets(root: &str) -> Vec<Target> {
+    load_mod_file(root)
+        .targets
+        .unwrap_or_default()
+        .into_iter()
+        .map(|target| Target {
+            name: target.name.unwrap_or_else(|| target.files.join(",")),
+            work_dir: root.to_string(),
+            files: target.files,
+            settings_file: None,
+        })
+        .collect()
+}
+
+/// Returns the targets affected by the changed files in the order of the targets.
+pub fn affected_targets(targets: &[Target], changed_files: &[String]) -> Vec<AffectedTarget> {
+    let changed_files: BTreeSet<PathBuf> = changed_files
+        .iter()
+        .map(|file| canonical_path(file))
+        .collect();
+    let mut affected = vec![];
+    for target in targets {
+        let (watched, error) = match target_paths(target) {
+            Ok(paths) => (paths, None),
+            Err(err) => (TargetPaths::default(), Some(err)),
+        };
+        let target_changed_files: Vec<String> = changed_files
+            .iter()
+            .filter(|file| watched.contains(file))
+            .map(|file| file.display().to_string())
+            .collect();
+        if error.is_some() || !target_changed_files.is_empty() {
+            affected.push(AffectedTarget {
+                target: target.clone(),
+                changed_files: target_changed_files,
+                error,
+            });
+        }
+    }
+    affected
+}
+
+/// The files and the package directories of a target.
+#[derive(Debug, Default)]
+struct TargetPaths {
+    files: BTreeSet<PathBuf>,
+    pkg_dirs: BTreeSet<PathBuf>,
+}
+
+impl TargetPaths {
+    fn contains(&self, file: &Path) -> bool {
+        if self.files.contains(file) {
+            return true;
+        }
+        // A new or removed package file.
+        let name = file
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        name.ends_with(KCL_FILE_SUFFIX)
+            && !name.ends_with("_test.k")
+            && !name.starts_with('_')
+            && matches!(file.parent(), Some(dir) if self.pkg_dirs.contains(dir))
+    }
+}
+
+fn target_paths(target: &Target) -> Result<TargetPaths, String> {
+    let files: Vec<String> = target
+        .files
+        .iter()
+        .map(|file| Path::new(&target.work_dir).join(file).display().to_string())
+        .collect();
+    let opts = LoadProgramOptions {
+        work_dir: target.work_dir.clone(),
+        ..Default::default()
+    };
+    let graph = load_dep_graph(
+        &files.iter().map(|f| f.as_str()).collect::<Vec<&str>>(),
+        Some(opts),
+    )
+    .map_err(|err| err.to_string())?;
+    let mut paths = TargetPaths::default();
+    for (pkgpath, pkg_files) in &graph.pkgs {
+        for file in pkg_files {
+            let file = canonical_path(file);
+            if pkgpath != kclvm_ast::MAIN_PKG {
+                if let Some(dir) = file.parent() {
+                    if dir.is_dir() {
+                        paths.pkg_dirs.insert(dir.to_path_buf());
+                    } else if let Some(archive) = dir.ancestors().find(|path| path.is_file()) {
+                        // The files loaded from a package archive do not exist on disk,
+                        // and the archive file is changed instead.
+                        paths.files.insert(archive.to_path_buf());
+                    }
+                }
+            }
+            paths.files.insert(file);
+        }
+    }
+    for file in &files {
+        paths.files.insert(canonical_path(file));
+        let root = canonical_path(&parent_dir(file));
+        for dir in root.ancestors() {
+            let kcl_mod = dir.join(KCL_MOD_FILE);
+            if kcl_mod.exists() {
+                paths.files.insert(kcl_mod);
+                break;
+            }
+        }
+    }
+    if let Some(settings_file) = &target.settings_file {
+        paths.files.insert(canonical_path(settings_file));
+    }
+    Ok(paths)
+}
+
+/// Returns the files changed since the git revision `rev`, including the changes not
+/// committed and the untracked files which are not ignored. The paths are absolute.
+pub fn git_changed_files(rev: &str) -> Result<Vec<String>> {
+    git_changed_files_in(".", rev)
+}
+
+fn git_changed_files_in(dir: &str, rev: &str) -> Result<Vec<String>> {
+    let toplevel = git(&["-C", dir, "rev-parse", "--show-toplevel"])?;
+    let toplevel = toplevel.trim();
+    let changed = git(&["-C", toplevel, "diff", "--name-only", rev, "--"])?;
+    let untracked = git(&["-C", toplevel, "ls-files", "--others", "--exclude-standard"])?;
+    let mut files = vec![];
+    for line in changed.lines().chain(untracked.lines()) {
+        if line.is_empty() {
+            continue;
+        }
+        let file = Path::new(toplevel).join(line).display().to_string();
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+    Ok(files)
+}
+
+/// Returns the changed files of the git revision or the file list, `base` is a file
+/// list when it is an existing file which contains one path per line.
+pub fn changed_files(base: &str) -> Result<Vec<String>> {
+    if Path::new(base).is_file() {
+        let content = std::fs::read_to_string(base)
+            .map_err(|err| anyhow!("failed to read '{}': {}", base, err))?;
+        Ok(content
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| line.to_string())
+            .collect())
+    } else {
+        git_changed_files(base)
+    }
+}
+
+fn git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|err| anyhow!("failed to run git: {}", err))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Returns the absolute path with the symbolic links resolved, so the changed files and
+/// the files of the targets are compared in the same form. The part of the path which does
+/// not exist, e.g. a removed file, is kept as it is.
+fn canonical_path(path: &str) -> PathBuf {
+    let path = normalize_path(path);
+    for dir in path.ancestors() {
+        if let Ok(canonical) = dir.canonicalize() {
+            return match path.strip_prefix(dir) {
+                Ok(rest) if !rest.as_os_str().is_empty() => canonical.join(rest),
+                _ => canonical,
+            };
+        }
+    }
+    path
+}
+
+fn parent_dir(path: &str) -> String {
+    match Path::new(path).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.display().to_string(),
+        _ => ".".to_string(),
+    }
+}
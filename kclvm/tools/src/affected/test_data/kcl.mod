@@ -0,0 +1,3 @@
+[[targets]]
+name = "a"
+files = ["apps/a/main.k"]
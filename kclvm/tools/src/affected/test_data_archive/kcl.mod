@@ -0,0 +1,2 @@
+[deps]
+paths = ["vendor/lib.tar.gz", "vendor/util.zip"]
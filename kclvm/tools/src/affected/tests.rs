@@ -0,0 +1,139 @@
+use super::{
+    affected_targets, git_changed_files_in, load_mod_targets, load_settings_target, Target,
+};
+use std::path::Path;
+use std::process::Command;
+
+const TEST_ROOT: &str = "./src/affected/test_data";
+
+fn test_targets() -> Vec<Target> {
+    let mut targets = load_mod_targets(TEST_ROOT);
+    targets.push(load_settings_target("./src/affected/test_data/apps/b/settings.yaml").unwrap());
+    targets
+}
+
+fn affected_names(changed_files: &[&str]) -> Vec<String> {
+    let changed_files: Vec<String> = changed_files
+        .iter()
+        .map(|file| Path::new(TEST_ROOT).join(file).display().to_string())
+        .collect();
+    affected_targets(&test_targets(), &changed_files)
+        .into_iter()
+        .map(|affected| {
+            assert!(affected.error.is_none(), "{:?}", affected.error);
+            affected.target.name
+        })
+        .collect()
+}
+
+#[test]
+fn test_load_targets() {
+    let targets = test_targets();
+    assert_eq!(targets.len(), 2);
+    assert_eq!(targets[0].name, "a");
+    assert_eq!(targets[0].files, vec!["apps/a/main.k".to_string()]);
+    assert_eq!(targets[1].work_dir, "./src/affected/test_data/apps/b");
+    assert_eq!(targets[1].files, vec!["main.k".to_string()]);
+    assert!(load_settings_target("./src/affected/test_data/kcl.mod").is_err());
+}
+
+#[test]
+fn test_affected_targets() {
+    let settings_b = "./src/affected/test_data/apps/b/settings.yaml";
+    assert_eq!(affected_names(&["base/base.k"]), vec!["a"]);
+    // A new file in an imported package.
+    assert_eq!(affected_names(&["base/new.k"]), vec!["a"]);
+    assert!(affected_names(&["base/base_test.k"]).is_empty());
+    assert_eq!(affected_names(&["apps/b/main.k"]), vec![settings_b]);
+    assert_eq!(affected_names(&["apps/b/settings.yaml"]), vec![settings_b]);
+    assert_eq!(affected_names(&["kcl.mod"]), vec!["a", settings_b]);
+    assert!(affected_names(&["README.md", "apps/c/main.k"]).is_empty());
+
+    let affected = affected_targets(
+        &test_targets(),
+        &["./src/affected/test_data/base/base.k".to_string()],
+    );
+    assert_eq!(
+        affected[0].changed_files,
+        vec![Path::new(TEST_ROOT)
+            .canonicalize()
+            .unwrap()
+            .join("base/base.k")
+            .display()
+            .to_string()]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_affected_targets_with_symlink() {
+    let link = std::env::temp_dir().join(format!("kclvm_affected_{}", std::process::id()));
+    let _ = std::fs::remove_file(&link);
+    std::os::unix::fs::symlink(Path::new(TEST_ROOT).canonicalize().unwrap(), &link).unwrap();
+    // The changed file is given through the symbolic link of the program root.
+    let changed_file = link.join("base/base.k").display().to_string();
+    let affected = affected_targets(&test_targets(), &[changed_file]);
+    std::fs::remove_file(&link).unwrap();
+    let names: Vec<String> = affected.into_iter().map(|a| a.target.name).collect();
+    assert_eq!(names, vec!["a"]);
+}
+
+#[test]
+fn test_git_changed_files() {
+    let dir = std::env::temp_dir().join(format!("kclvm_affected_git_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("pkg")).unwrap();
+    let dir = dir.canonicalize().unwrap();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(&dir)
+            .args(["-c", "user.name=kcl", "-c", "user.email=kcl@kcl"])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?}", args);
+    };
+    std::fs::write(dir.join("main.k"), "a = 1").unwrap();
+    std::fs::write(dir.join(".gitignore"), "*.log").unwrap();
+    git(&["init", "-q"]);
+    git(&["add", "-A"]);
+    git(&["commit", "-q", "-m", "init"]);
+    std::fs::write(dir.join("main.k"), "a = 2").unwrap();
+    std::fs::write(dir.join("pkg/new.k"), "b = 1").unwrap();
+    std::fs::write(dir.join("debug.log"), "").unwrap();
+    let files = git_changed_files_in(dir.join("pkg").to_str().unwrap(), "HEAD").unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        files,
+        vec![
+            dir.join("main.k").display().to_string(),
+            dir.join("pkg/new.k").display().to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_affected_targets_with_archive() {
+    let root = "./src/affected/test_data_archive";
+    let targets = vec![Target {
+        name: "archive".to_string(),
+        work_dir: root.to_string(),
+        files: vec!["main.k".to_string()],
+        settings_file: None,
+    }];
+    let affected_names = |changed_file: &str| -> Vec<String> {
+        let changed_file = Path::new(root).join(changed_file).display().to_string();
+        affected_targets(&targets, &[changed_file])
+            .into_iter()
+            .map(|affected| {
+                assert!(affected.error.is_none(), "{:?}", affected.error);
+                affected.target.name
+            })
+            .collect()
+    };
+    assert_eq!(affected_names("vendor/lib.tar.gz"), vec!["archive"]);
+    assert_eq!(affected_names("vendor/util.zip"), vec!["archive"]);
+    assert!(affected_names("vendor/other.zip").is_empty());
+}
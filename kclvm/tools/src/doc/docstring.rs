@@ -0,0 +1,146 @@
+//! Parse the KCL schema and rule docstrings written in the numpydoc style, e.g.
+//!
+//! ```no_check
+//! schema Person:
+//!     """Person is a simple schema
+//!
+//!     Attributes
+//!     ----------
+//!     name : str, default is "Alice", required
+//!         The name of the person.
+//!
+//!     Examples
+//!     --------
+//!     person = Person {
+//!         name = "Alice"
+//!     }
+//!     """
+//! ```
+use indexmap::IndexMap;
+
+const ATTRIBUTES_SECTION: &str = "attributes";
+const EXAMPLES_SECTIONS: [&str; 2] = ["examples", "example"];
+
+/// Docstring contains the description, the attribute descriptions and
+/// the examples parsed from a schema or rule docstring.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Docstring {
+    /// The description text before the first section.
+    pub description: String,
+    /// The attribute descriptions in the `Attributes` section.
+    pub attrs: IndexMap<String, String>,
+    /// The source code in the `Examples` section.
+    pub examples: String,
+}
+
+/// Parse the raw docstring literal such as `"""doc"""` or the docstring value
+/// into [Docstring].
+pub fn parse_docstring(raw: &str) -> Docstring {
+    let lines = dedent(&strip_quotes(raw));
+    let mut doc = Docstring::default();
+    let mut description: Vec<&str> = vec![];
+    let mut examples: Vec<&str> = vec![];
+    let mut section: Option<String> = None;
+    let mut attr: Option<(String, Vec<&str>)> = None;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].as_str();
+        // A section header is a title line underlined by dashes.
+        if is_section_underline(lines.get(i + 1)) && !line.trim().is_empty() {
+            if let Some((name, desc)) = attr.take() {
+                doc.attrs.insert(name, join_lines(&desc));
+            }
+            section = Some(line.trim().to_lowercase());
+            i += 2;
+            continue;
+        }
+        match section.as_deref() {
+            None => description.push(line),
+            Some(ATTRIBUTES_SECTION) => {
+                if line.trim().is_empty() {
+                    if let Some((_, desc)) = &mut attr {
+                        desc.push("");
+                    }
+                } else if !line.starts_with(char::is_whitespace) {
+                    if let Some((name, desc)) = attr.take() {
+                        doc.attrs.insert(name, join_lines(&desc));
+                    }
+                    let name = line
+                        .split([':', ','])
+                        .next()
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string();
+                    attr = Some((name, vec![]));
+                } else if let Some((_, desc)) = &mut attr {
+                    desc.push(line.trim());
+                }
+            }
+            Some(name) if EXAMPLES_SECTIONS.contains(&name) => examples.push(line),
+            // Unknown sections are kept in the description.
+            Some(_) => description.push(line),
+        }
+        i += 1;
+    }
+    if let Some((name, desc)) = attr.take() {
+        doc.attrs.insert(name, join_lines(&desc));
+    }
+    doc.description = join_lines(&description);
+    doc.examples = join_lines(&dedent(&examples.join("\n")));
+    doc
+}
+
+/// Strip the string prefix and quotes of a raw docstring literal.
+fn strip_quotes(raw: &str) -> String {
+    let s = raw.trim();
+    let s = s
+        .strip_prefix(|c| c == 'r' || c == 'R')
+        .filter(|s| s.starts_with(['"', '\'']))
+        .unwrap_or(s);
+    for quote in ["\"\"\"", "'''", "\"", "'"] {
+        if s.len() >= 2 * quote.len() && s.starts_with(quote) && s.ends_with(quote) {
+            return s[quote.len()..s.len() - quote.len()].to_string();
+        }
+    }
+    s.to_string()
+}
+
+/// Remove the common leading whitespace of all lines except the first line.
+fn dedent(s: &str) -> Vec<String> {
+    let lines: Vec<&str> = s.lines().collect();
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, l)| {
+            if i == 0 {
+                l.trim().to_string()
+            } else if l.trim().is_empty() {
+                "".to_string()
+            } else {
+                l[indent..].trim_end().to_string()
+            }
+        })
+        .collect()
+}
+
+fn is_section_underline(line: Option<&String>) -> bool {
+    matches!(line, Some(line) if line.trim().len() >= 3 && line.trim().chars().all(|c| c == '-'))
+}
+
+/// Join lines and trim the leading and trailing blank lines.
+fn join_lines<S: AsRef<str>>(lines: &[S]) -> String {
+    lines
+        .iter()
+        .map(|l| l.as_ref())
+        .collect::<Vec<&str>>()
+        .join("\n")
+        .trim_matches('\n')
+        .to_string()
+}
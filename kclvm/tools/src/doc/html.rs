@@ -0,0 +1,239 @@
+//! Render the package documents into static HTML pages.
+use super::markdown::file_name;
+use super::{page_name, DocFormat, PackageDoc, SchemaDoc, SchemaRef, TypeSegment};
+use std::fmt::Write;
+
+const STYLE: &str = r#"body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; max-width: 960px; margin: 0 auto; padding: 16px; color: #24292f; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #d0d7de; padding: 6px 12px; text-align: left; vertical-align: top; }
+code, pre { font-family: SFMono-Regular, Consolas, Menlo, monospace; background: #f6f8fa; }
+pre { padding: 12px; overflow: auto; }
+.description { white-space: pre-wrap; }"#;
+
+/// Render the index page which lists all the packages and their schemas.
+pub fn render_index(pkgs: &[PackageDoc]) -> String {
+    let mut body = String::new();
+    writeln!(body, "<h1>Packages</h1>\n<ul>").unwrap();
+    for pkg in pkgs {
+        let page = page_name(&pkg.pkgpath, DocFormat::Html);
+        writeln!(
+            body,
+            "<li><a href=\"{}\">{}</a>",
+            escape(&page),
+            escape(pkg.name())
+        )
+        .unwrap();
+        if !pkg.schemas.is_empty() {
+            writeln!(body, "<ul>").unwrap();
+            for schema in &pkg.schemas {
+                writeln!(
+                    body,
+                    "<li><a href=\"{}#{}\">{} {}</a></li>",
+                    escape(&page),
+                    schema.anchor(),
+                    schema.kind(),
+                    escape(&schema.name)
+                )
+                .unwrap();
+            }
+            writeln!(body, "</ul>").unwrap();
+        }
+        writeln!(body, "</li>").unwrap();
+    }
+    writeln!(body, "</ul>").unwrap();
+    page("Packages", &body)
+}
+
+/// Render the page of the package `pkg`.
+pub fn render_package(pkg: &PackageDoc) -> String {
+    let mut body = String::new();
+    writeln!(
+        body,
+        "<p><a href=\"{}\">Packages</a></p>",
+        page_name("", DocFormat::Html)
+    )
+    .unwrap();
+    writeln!(body, "<h1>Package {}</h1>", escape(pkg.name())).unwrap();
+    if !pkg.files.is_empty() {
+        let files = pkg
+            .files
+            .iter()
+            .map(|f| format!("<code>{}</code>", escape(&file_name(f))))
+            .collect::<Vec<String>>()
+            .join(", ");
+        writeln!(body, "<p>Source: {}</p>", files).unwrap();
+    }
+    if !pkg.schemas.is_empty() {
+        writeln!(body, "<ul>").unwrap();
+        for schema in &pkg.schemas {
+            writeln!(
+                body,
+                "<li><a href=\"#{}\">{} {}</a></li>",
+                schema.anchor(),
+                schema.kind(),
+                escape(&schema.name)
+            )
+            .unwrap();
+        }
+        writeln!(body, "</ul>").unwrap();
+    }
+    for schema in &pkg.schemas {
+        render_schema(&mut body, pkg, schema);
+    }
+    page(&format!("Package {}", pkg.name()), &body)
+}
+
+fn render_schema(out: &mut String, pkg: &PackageDoc, schema: &SchemaDoc) {
+    writeln!(
+        out,
+        "<h2 id=\"{}\">{} {}</h2>",
+        schema.anchor(),
+        schema.kind(),
+        escape(&schema.name)
+    )
+    .unwrap();
+    if !schema.description.is_empty() {
+        writeln!(
+            out,
+            "<p class=\"description\">{}</p>",
+            escape(&schema.description)
+        )
+        .unwrap();
+    }
+    writeln!(
+        out,
+        "<p>Source: <code>{}:{}</code></p>",
+        escape(&file_name(&schema.filename)),
+        schema.line
+    )
+    .unwrap();
+    if let Some(base) = &schema.base {
+        writeln!(out, "<h3>Base Schema</h3>\n<p>{}</p>", link(pkg, base)).unwrap();
+    }
+    if let Some(protocol) = &schema.protocol {
+        writeln!(out, "<h3>Protocol</h3>\n<p>{}</p>", link(pkg, protocol)).unwrap();
+    }
+    if !schema.mixins.is_empty() {
+        let title = if schema.is_rule {
+            "Parent Rules"
+        } else {
+            "Mixins"
+        };
+        writeln!(
+            out,
+            "<h3>{}</h3>\n<p>{}</p>",
+            title,
+            links(pkg, &schema.mixins)
+        )
+        .unwrap();
+    }
+    if !schema.subclasses.is_empty() {
+        writeln!(
+            out,
+            "<h3>Subclasses</h3>\n<p>{}</p>",
+            links(pkg, &schema.subclasses)
+        )
+        .unwrap();
+    }
+    if !schema.attrs.is_empty() {
+        writeln!(out, "<h3>Attributes</h3>\n<table>").unwrap();
+        writeln!(
+            out,
+            "<tr><th>Name and Description</th><th>Type</th><th>Default Value</th><th>Required</th></tr>"
+        )
+        .unwrap();
+        for attr in &schema.attrs {
+            let mut name = format!("<strong>{}</strong>", escape(&attr.name));
+            if !attr.description.is_empty() {
+                name.push_str("<br />");
+                name.push_str(&escape(&attr.description).replace('\n', "<br />"));
+            }
+            let default = match &attr.default {
+                Some(default) => format!("<code>{}</code>", escape(default)),
+                None => "".to_string(),
+            };
+            let required = if attr.is_optional {
+                "optional"
+            } else {
+                "<strong>required</strong>"
+            };
+            writeln!(
+                out,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                name,
+                type_str(pkg, &attr.ty),
+                default,
+                required
+            )
+            .unwrap();
+        }
+        writeln!(out, "</table>").unwrap();
+    }
+    if let Some(index_signature) = &schema.index_signature {
+        writeln!(
+            out,
+            "<h3>Index Signature</h3>\n<p>{}</p>",
+            type_str(pkg, index_signature)
+        )
+        .unwrap();
+    }
+    if !schema.checks.is_empty() {
+        writeln!(
+            out,
+            "<h3>Checks</h3>\n<pre><code>{}</code></pre>",
+            escape(&schema.checks.join("\n"))
+        )
+        .unwrap();
+    }
+    if !schema.examples.is_empty() {
+        writeln!(
+            out,
+            "<h3>Examples</h3>\n<pre><code>{}</code></pre>",
+            escape(&schema.examples)
+        )
+        .unwrap();
+    }
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        STYLE,
+        body
+    )
+}
+
+fn link(pkg: &PackageDoc, schema: &SchemaRef) -> String {
+    format!(
+        "<a href=\"{}\">{}</a>",
+        escape(&schema.link(&pkg.pkgpath, DocFormat::Html)),
+        escape(&schema.display_name(&pkg.pkgpath))
+    )
+}
+
+fn links(pkg: &PackageDoc, schemas: &[SchemaRef]) -> String {
+    schemas
+        .iter()
+        .map(|schema| link(pkg, schema))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn type_str(pkg: &PackageDoc, segments: &[TypeSegment]) -> String {
+    segments
+        .iter()
+        .map(|segment| match segment {
+            TypeSegment::Text(text) => escape(text),
+            TypeSegment::Schema(schema) => link(pkg, schema),
+        })
+        .collect()
+}
+
+/// Escape the HTML special characters.
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,203 @@
+//! Render the package documents into Markdown pages.
+use super::{page_name, DocFormat, PackageDoc, SchemaDoc, SchemaRef, TypeSegment};
+use std::fmt::Write;
+use std::path::Path;
+
+const GENERATED_COMMENT: &str = "<!-- Auto generated by the KCL doc tool, please do not edit. -->";
+
+/// Render the index page which lists all the packages and their schemas.
+pub fn render_index(pkgs: &[PackageDoc]) -> String {
+    let mut out = String::new();
+    writeln!(out, "# Packages\n").unwrap();
+    for pkg in pkgs {
+        writeln!(
+            out,
+            "- [{}]({})",
+            pkg.name(),
+            page_name(&pkg.pkgpath, DocFormat::Markdown)
+        )
+        .unwrap();
+        for schema in &pkg.schemas {
+            writeln!(
+                out,
+                "  - [{} {}]({}#{})",
+                schema.kind(),
+                schema.name,
+                page_name(&pkg.pkgpath, DocFormat::Markdown),
+                schema.anchor()
+            )
+            .unwrap();
+        }
+    }
+    writeln!(out, "\n{}", GENERATED_COMMENT).unwrap();
+    out
+}
+
+/// Render the page of the package `pkg`.
+pub fn render_package(pkg: &PackageDoc) -> String {
+    let mut out = String::new();
+    writeln!(out, "# Package {}\n", pkg.name()).unwrap();
+    if !pkg.files.is_empty() {
+        let files = pkg
+            .files
+            .iter()
+            .map(|f| format!("`{}`", file_name(f)))
+            .collect::<Vec<String>>()
+            .join(", ");
+        writeln!(out, "Source: {}\n", files).unwrap();
+    }
+    for schema in &pkg.schemas {
+        writeln!(
+            out,
+            "- [{} {}](#{})",
+            schema.kind(),
+            schema.name,
+            schema.anchor()
+        )
+        .unwrap();
+    }
+    if !pkg.schemas.is_empty() {
+        writeln!(out).unwrap();
+    }
+    for schema in &pkg.schemas {
+        render_schema(&mut out, pkg, schema);
+    }
+    writeln!(out, "{}", GENERATED_COMMENT).unwrap();
+    out
+}
+
+fn render_schema(out: &mut String, pkg: &PackageDoc, schema: &SchemaDoc) {
+    writeln!(out, "## {} {}\n", schema.kind(), schema.name).unwrap();
+    if !schema.description.is_empty() {
+        writeln!(out, "{}\n", schema.description).unwrap();
+    }
+    writeln!(
+        out,
+        "Source: `{}:{}`\n",
+        file_name(&schema.filename),
+        schema.line
+    )
+    .unwrap();
+    if let Some(base) = &schema.base {
+        writeln!(out, "### Base Schema\n\n{}\n", link(pkg, base)).unwrap();
+    }
+    if let Some(protocol) = &schema.protocol {
+        writeln!(out, "### Protocol\n\n{}\n", link(pkg, protocol)).unwrap();
+    }
+    if !schema.mixins.is_empty() {
+        let title = if schema.is_rule {
+            "Parent Rules"
+        } else {
+            "Mixins"
+        };
+        writeln!(out, "### {}\n\n{}\n", title, links(pkg, &schema.mixins)).unwrap();
+    }
+    if !schema.subclasses.is_empty() {
+        writeln!(
+            out,
+            "### Subclasses\n\n{}\n",
+            links(pkg, &schema.subclasses)
+        )
+        .unwrap();
+    }
+    if !schema.attrs.is_empty() {
+        writeln!(out, "### Attributes\n").unwrap();
+        writeln!(out, "|Name and Description|Type|Default Value|Required|").unwrap();
+        writeln!(out, "|--------------------|----|-------------|--------|").unwrap();
+        for attr in &schema.attrs {
+            let mut name = format!("**{}**", attr.name);
+            if !attr.description.is_empty() {
+                name.push_str("<br />");
+                name.push_str(&escape_cell(&attr.description));
+            }
+            let default = match &attr.default {
+                Some(default) => format!("`{}`", escape_code_cell(default)),
+                None => "".to_string(),
+            };
+            let required = if attr.is_optional {
+                "optional"
+            } else {
+                "**required**"
+            };
+            writeln!(
+                out,
+                "|{}|{}|{}|{}|",
+                name,
+                type_str(pkg, &attr.ty),
+                default,
+                required
+            )
+            .unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+    if let Some(index_signature) = &schema.index_signature {
+        writeln!(
+            out,
+            "### Index Signature\n\n{}\n",
+            type_str(pkg, index_signature)
+        )
+        .unwrap();
+    }
+    if !schema.checks.is_empty() {
+        writeln!(
+            out,
+            "### Checks\n\n```kcl\n{}\n```\n",
+            schema.checks.join("\n")
+        )
+        .unwrap();
+    }
+    if !schema.examples.is_empty() {
+        writeln!(out, "### Examples\n\n```kcl\n{}\n```\n", schema.examples).unwrap();
+    }
+}
+
+fn link(pkg: &PackageDoc, schema: &SchemaRef) -> String {
+    format!(
+        "[{}]({})",
+        schema.display_name(&pkg.pkgpath),
+        schema.link(&pkg.pkgpath, DocFormat::Markdown)
+    )
+}
+
+fn links(pkg: &PackageDoc, schemas: &[SchemaRef]) -> String {
+    schemas
+        .iter()
+        .map(|schema| link(pkg, schema))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn type_str(pkg: &PackageDoc, segments: &[TypeSegment]) -> String {
+    segments
+        .iter()
+        .map(|segment| match segment {
+            // Escape the brackets to avoid parsing list types and index signatures as links.
+            TypeSegment::Text(text) => escape_cell(text).replace('[', "\\[").replace(']', "\\]"),
+            TypeSegment::Schema(schema) => link(pkg, schema),
+        })
+        .collect()
+}
+
+/// Escape the text in a Markdown table cell.
+fn escape_cell(s: &str) -> String {
+    s.replace('|', "\\|")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\n', "<br />")
+}
+
+/// Escape the code span in a Markdown table cell, code spans can not contain line breaks.
+fn escape_code_cell(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+pub(crate) fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
@@ -0,0 +1,481 @@
+//! [kclvm_tools::doc] module generates the reference documents of KCL packages.
+//!
+//! The generator loads and resolves the program with [kclvm_parser::load_program]
+//! and [kclvm_sema::resolver::resolve_program], collects the schemas and rules of
+//! each package with their docstrings, types, defaults, inheritance, mixins, index
+//! signatures and check expressions, and renders one Markdown or HTML page per
+//! package together with an index page.
+use anyhow::{anyhow, Result};
+use kclvm_ast::ast;
+use kclvm_ast::MAIN_PKG;
+use kclvm_ast_pretty::{print_ast_node, ASTNode};
+use kclvm_parser::LoadProgramOptions;
+use kclvm_sema::resolver::scope::ProgramScope;
+use kclvm_sema::ty::{SchemaIndexSignature, SchemaType, Type, TypeKind};
+use std::path::Path;
+
+pub mod docstring;
+pub mod html;
+pub mod markdown;
+#[cfg(test)]
+mod tests;
+
+use crate::util::{load_resolved_program, lookup_schema_ty};
+use docstring::parse_docstring;
+
+/// The output format of the generated documents.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DocFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl DocFormat {
+    /// The file extension of the generated pages.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DocFormat::Markdown => "md",
+            DocFormat::Html => "html",
+        }
+    }
+}
+
+impl TryFrom<&str> for DocFormat {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "md" | "markdown" => Ok(DocFormat::Markdown),
+            "html" => Ok(DocFormat::Html),
+            _ => Err(anyhow!("unsupported doc format '{}'", s)),
+        }
+    }
+}
+
+/// DocOptions contains the options of the document generation:
+/// - format: the output format of the pages.
+/// - output_dir: the directory which the pages are written into.
+/// - include_private: whether to document the private schemas and attributes
+///   whose names start with `_`.
+#[derive(Debug, Default, Clone)]
+pub struct DocOptions {
+    pub format: DocFormat,
+    pub output_dir: String,
+    pub include_private: bool,
+}
+
+/// The document of a KCL package.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PackageDoc {
+    /// The package path, the main package is `__main__`.
+    pub pkgpath: String,
+    /// The source files of the package.
+    pub files: Vec<String>,
+    /// The schemas, mixins, protocols and rules defined in the package.
+    pub schemas: Vec<SchemaDoc>,
+}
+
+impl PackageDoc {
+    /// The display name of the package.
+    pub fn name(&self) -> &str {
+        pkg_name(&self.pkgpath)
+    }
+}
+
+/// The document of a schema or a rule.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SchemaDoc {
+    pub name: String,
+    pub pkgpath: String,
+    pub filename: String,
+    pub line: u64,
+    pub is_mixin: bool,
+    pub is_protocol: bool,
+    pub is_rule: bool,
+    /// The description in the docstring.
+    pub description: String,
+    /// The examples in the docstring.
+    pub examples: String,
+    /// The base schema.
+    pub base: Option<SchemaRef>,
+    /// The protocol of a mixin or a rule.
+    pub protocol: Option<SchemaRef>,
+    /// The mixins of a schema or the parent rules of a rule.
+    pub mixins: Vec<SchemaRef>,
+    /// The schemas which inherit from this schema.
+    pub subclasses: Vec<SchemaRef>,
+    pub attrs: Vec<AttrDoc>,
+    pub index_signature: Option<Vec<TypeSegment>>,
+    pub checks: Vec<String>,
+}
+
+impl SchemaDoc {
+    /// The kind name of the schema used in the titles.
+    pub fn kind(&self) -> &'static str {
+        if self.is_rule {
+            "Rule"
+        } else if self.is_mixin {
+            "Mixin"
+        } else if self.is_protocol {
+            "Protocol"
+        } else {
+            "Schema"
+        }
+    }
+
+    /// The anchor of the schema in the package page.
+    pub fn anchor(&self) -> String {
+        anchor(self.kind(), &self.name)
+    }
+}
+
+/// The document of a schema attribute.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AttrDoc {
+    pub name: String,
+    pub description: String,
+    pub ty: Vec<TypeSegment>,
+    pub default: Option<String>,
+    pub is_optional: bool,
+}
+
+/// A reference to a schema or a rule in a package.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SchemaRef {
+    pub name: String,
+    pub pkgpath: String,
+    pub kind: &'static str,
+}
+
+impl SchemaRef {
+    fn new(ty: &SchemaType) -> Self {
+        let kind = if ty.is_rule {
+            "Rule"
+        } else if ty.is_mixin {
+            "Mixin"
+        } else if ty.is_protocol {
+            "Protocol"
+        } else {
+            "Schema"
+        };
+        Self {
+            name: ty.name.clone(),
+            pkgpath: ty.pkgpath.clone(),
+            kind,
+        }
+    }
+
+    /// The referenced name qualified by the package name when it is
+    /// outside of the package `pkgpath`.
+    pub fn display_name(&self, pkgpath: &str) -> String {
+        if self.pkgpath == pkgpath {
+            self.name.clone()
+        } else {
+            format!("{}.{}", pkg_name(&self.pkgpath), self.name)
+        }
+    }
+
+    /// The link of the reference from the page of the package `pkgpath`.
+    pub fn link(&self, pkgpath: &str, format: DocFormat) -> String {
+        let anchor = anchor(self.kind, &self.name);
+        if self.pkgpath == pkgpath {
+            format!("#{}", anchor)
+        } else {
+            format!("{}#{}", page_name(&self.pkgpath, format), anchor)
+        }
+    }
+}
+
+/// A segment of a type string, the schema segments are rendered as
+/// cross-reference links.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeSegment {
+    Text(String),
+    Schema(SchemaRef),
+}
+
+/// Generates the documents of the packages loaded from `files` into
+/// `opts.output_dir` and returns the written file paths.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kclvm_tools::doc::{gen_doc, DocOptions};
+///
+/// let opts = DocOptions {
+///     output_dir: "docs".to_string(),
+///     ..Default::default()
+/// };
+/// gen_doc(&["main.k"], &opts, None).unwrap();
+/// ```
+pub fn gen_doc(
+    files: &[&str],
+    opts: &DocOptions,
+    load_opts: Option<LoadProgramOptions>,
+) -> Result<Vec<String>> {
+    let pkgs = load_package_docs(files, opts, load_opts)?;
+    let output_dir = Path::new(&opts.output_dir);
+    std::fs::create_dir_all(output_dir)?;
+    let mut pages = vec![(
+        page_name("", opts.format),
+        match opts.format {
+            DocFormat::Markdown => markdown::render_index(&pkgs),
+            DocFormat::Html => html::render_index(&pkgs),
+        },
+    )];
+    for pkg in &pkgs {
+        let page = match opts.format {
+            DocFormat::Markdown => markdown::render_package(pkg),
+            DocFormat::Html => html::render_package(pkg),
+        };
+        pages.push((page_name(&pkg.pkgpath, opts.format), page));
+    }
+    let mut written = vec![];
+    for (name, content) in pages {
+        let path = output_dir.join(name);
+        std::fs::write(&path, content)?;
+        written.push(path.to_string_lossy().to_string());
+    }
+    Ok(written)
+}
+
+/// Loads and resolves the program from `files` and returns the documents of
+/// all the packages ordered by the package path.
+pub fn load_package_docs(
+    files: &[&str],
+    opts: &DocOptions,
+    load_opts: Option<LoadProgramOptions>,
+) -> Result<Vec<PackageDoc>> {
+    let (program, scope) = load_resolved_program(files, load_opts)?;
+    let mut pkgpaths: Vec<&String> = program.pkgs.keys().collect();
+    pkgpaths.sort();
+    let mut pkgs: Vec<PackageDoc> = pkgpaths
+        .into_iter()
+        .map(|pkgpath| package_doc(pkgpath, &program.pkgs[pkgpath], &scope, opts))
+        .collect();
+    link_subclasses(&mut pkgs);
+    Ok(pkgs)
+}
+
+fn package_doc(
+    pkgpath: &str,
+    modules: &[ast::Module],
+    scope: &ProgramScope,
+    opts: &DocOptions,
+) -> PackageDoc {
+    let mut pkg = PackageDoc {
+        pkgpath: pkgpath.to_string(),
+        ..Default::default()
+    };
+    for module in modules {
+        pkg.files.push(module.filename.clone());
+        for stmt in &module.body {
+            let (name, doc, checks) = match &stmt.node {
+                ast::Stmt::Schema(schema_stmt) => (
+                    &schema_stmt.name.node,
+                    &schema_stmt.doc,
+                    &schema_stmt.checks,
+                ),
+                ast::Stmt::Rule(rule_stmt) => {
+                    (&rule_stmt.name.node, &rule_stmt.doc, &rule_stmt.checks)
+                }
+                _ => continue,
+            };
+            if name.starts_with('_') && !opts.include_private {
+                continue;
+            }
+            let schema_ty = match lookup_schema_ty(scope, pkgpath, name) {
+                Some(ty) => ty,
+                None => continue,
+            };
+            let docstring = parse_docstring(doc);
+            let mut schema = SchemaDoc {
+                name: name.clone(),
+                pkgpath: pkgpath.to_string(),
+                filename: module.filename.clone(),
+                line: stmt.line,
+                is_mixin: schema_ty.is_mixin,
+                is_protocol: schema_ty.is_protocol,
+                is_rule: schema_ty.is_rule,
+                description: docstring.description,
+                examples: docstring.examples,
+                base: schema_ty.base.as_ref().map(|ty| SchemaRef::new(ty)),
+                protocol: schema_ty.protocol.as_ref().map(|ty| SchemaRef::new(ty)),
+                mixins: schema_ty.mixins.iter().map(SchemaRef::new).collect(),
+                index_signature: schema_ty
+                    .index_signature
+                    .as_ref()
+                    .map(|sig| index_signature_segments(sig)),
+                checks: checks.iter().map(|check| check_str(&check.node)).collect(),
+                ..Default::default()
+            };
+            if let ast::Stmt::Schema(schema_stmt) = &stmt.node {
+                for attr_stmt in &schema_stmt.body {
+                    if let ast::Stmt::SchemaAttr(attr) = &attr_stmt.node {
+                        let name = &attr.name.node;
+                        if name.starts_with('_') && !opts.include_private {
+                            continue;
+                        }
+                        let description = if attr.doc.is_empty() {
+                            docstring.attrs.get(name).cloned().unwrap_or_default()
+                        } else {
+                            parse_docstring(&attr.doc).description
+                        };
+                        schema.attrs.push(AttrDoc {
+                            name: name.clone(),
+                            description,
+                            ty: match schema_ty.attrs.get(name) {
+                                Some(attr_ty) => type_segments(&attr_ty.ty),
+                                None => vec![TypeSegment::Text(attr.type_str.node.clone())],
+                            },
+                            default: attr
+                                .value
+                                .as_ref()
+                                .map(|value| print_ast_node(ASTNode::Expr(value))),
+                            is_optional: attr.is_optional,
+                        });
+                    }
+                }
+            }
+            pkg.schemas.push(schema);
+        }
+    }
+    pkg
+}
+
+/// Record the subclasses of each schema from the base schema references.
+fn link_subclasses(pkgs: &mut [PackageDoc]) {
+    let mut subclasses: Vec<(SchemaRef, SchemaRef)> = vec![];
+    for pkg in pkgs.iter() {
+        for schema in &pkg.schemas {
+            if let Some(base) = &schema.base {
+                subclasses.push((
+                    base.clone(),
+                    SchemaRef {
+                        name: schema.name.clone(),
+                        pkgpath: schema.pkgpath.clone(),
+                        kind: schema.kind(),
+                    },
+                ));
+            }
+        }
+    }
+    for (base, subclass) in subclasses {
+        if let Some(schema) = pkgs
+            .iter_mut()
+            .filter(|pkg| pkg.pkgpath == base.pkgpath)
+            .flat_map(|pkg| pkg.schemas.iter_mut())
+            .find(|schema| schema.name == base.name)
+        {
+            schema.subclasses.push(subclass);
+        }
+    }
+}
+
+/// Split the type string into text and schema segments.
+pub fn type_segments(ty: &Type) -> Vec<TypeSegment> {
+    let mut segments = vec![];
+    push_type_segments(ty, &mut segments);
+    merge_segments(segments)
+}
+
+/// Merge the adjacent text segments.
+fn merge_segments(segments: Vec<TypeSegment>) -> Vec<TypeSegment> {
+    let mut merged: Vec<TypeSegment> = vec![];
+    for segment in segments {
+        match (merged.last_mut(), segment) {
+            (Some(TypeSegment::Text(last)), TypeSegment::Text(text)) => last.push_str(&text),
+            (_, segment) => merged.push(segment),
+        }
+    }
+    merged
+}
+
+fn push_type_segments(ty: &Type, segments: &mut Vec<TypeSegment>) {
+    match &ty.kind {
+        TypeKind::StrLit(v) => segments.push(TypeSegment::Text(format!("{:?}", v))),
+        TypeKind::BoolLit(v) => segments.push(TypeSegment::Text(
+            if *v { "True" } else { "False" }.to_string(),
+        )),
+        TypeKind::IntLit(v) => segments.push(TypeSegment::Text(v.to_string())),
+        TypeKind::FloatLit(v) => segments.push(TypeSegment::Text(v.to_string())),
+        TypeKind::List(item_ty) => {
+            segments.push(TypeSegment::Text("[".to_string()));
+            push_type_segments(item_ty, segments);
+            segments.push(TypeSegment::Text("]".to_string()));
+        }
+        TypeKind::Dict(key_ty, val_ty) => {
+            segments.push(TypeSegment::Text("{".to_string()));
+            push_type_segments(key_ty, segments);
+            segments.push(TypeSegment::Text(":".to_string()));
+            push_type_segments(val_ty, segments);
+            segments.push(TypeSegment::Text("}".to_string()));
+        }
+        TypeKind::Union(types) => {
+            for (i, ty) in types.iter().enumerate() {
+                if i > 0 {
+                    segments.push(TypeSegment::Text(" | ".to_string()));
+                }
+                push_type_segments(ty, segments);
+            }
+        }
+        TypeKind::Schema(schema_ty) => {
+            segments.push(TypeSegment::Schema(SchemaRef::new(schema_ty)))
+        }
+        _ => segments.push(TypeSegment::Text(ty.ty_str())),
+    }
+}
+
+fn index_signature_segments(sig: &SchemaIndexSignature) -> Vec<TypeSegment> {
+    let mut prefix = "[".to_string();
+    if let Some(key_name) = &sig.key_name {
+        prefix.push_str(&format!("{}: ", key_name));
+    }
+    if sig.any_other {
+        prefix.push_str("...");
+    }
+    let mut segments = vec![TypeSegment::Text(prefix)];
+    push_type_segments(&sig.key_ty, &mut segments);
+    segments.push(TypeSegment::Text("]: ".to_string()));
+    push_type_segments(&sig.val_ty, &mut segments);
+    merge_segments(segments)
+}
+
+fn check_str(check: &ast::CheckExpr) -> String {
+    let mut s = print_ast_node(ASTNode::Expr(&check.test));
+    if let Some(if_cond) = &check.if_cond {
+        s.push_str(" if ");
+        s.push_str(&print_ast_node(ASTNode::Expr(if_cond)));
+    }
+    if let Some(msg) = &check.msg {
+        s.push_str(", ");
+        s.push_str(&print_ast_node(ASTNode::Expr(msg)));
+    }
+    s
+}
+
+/// The display name of the package `pkgpath`.
+pub fn pkg_name(pkgpath: &str) -> &str {
+    if pkgpath == MAIN_PKG {
+        "main"
+    } else {
+        pkgpath
+    }
+}
+
+/// The page file name of the package `pkgpath`, the index page for the
+/// empty package path. The package pages are prefixed with `pkg-`, which is
+/// not valid in a package path, so they never collide with the index page or
+/// with each other.
+pub fn page_name(pkgpath: &str, format: DocFormat) -> String {
+    if pkgpath.is_empty() {
+        format!("index.{}", format.extension())
+    } else {
+        format!("pkg-{}.{}", pkgpath, format.extension())
+    }
+}
+
+fn anchor(kind: &str, name: &str) -> String {
+    format!("{}-{}", kind, name).to_lowercase()
+}
@@ -0,0 +1,162 @@
+use std::path::PathBuf;
+
+use super::docstring::parse_docstring;
+use super::*;
+
+const CARGO_DIR: &str = env!("CARGO_MANIFEST_DIR");
+const REL_PATH: &str = "src/doc/test_data";
+
+fn test_data_path(name: &str) -> String {
+    let mut path = PathBuf::from(CARGO_DIR);
+    path.push(REL_PATH);
+    path.push(name);
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_parse_docstring() {
+    let doc = parse_docstring(
+        r#"'''Person is a simple schema
+
+    Attributes
+    ----------
+    name: str, default is "Default"
+        A Normal attribute named 'name'
+    age: int, default = 18, optional
+        A Normal attribute named 'age'
+        in years.
+
+    Examples
+    --------
+    person = Person {
+        name: "Alice"
+    }
+    '''"#,
+    );
+    assert_eq!(doc.description, "Person is a simple schema");
+    assert_eq!(doc.attrs["name"], "A Normal attribute named 'name'");
+    assert_eq!(
+        doc.attrs["age"],
+        "A Normal attribute named 'age'\nin years."
+    );
+    assert_eq!(doc.examples, "person = Person {\n    name: \"Alice\"\n}");
+    let doc = parse_docstring("\"ServiceRule checks the services.\"");
+    assert_eq!(doc.description, "ServiceRule checks the services.");
+    assert!(doc.attrs.is_empty());
+}
+
+#[test]
+fn test_load_package_docs() {
+    let pkgs =
+        load_package_docs(&[&test_data_path("main.k")], &DocOptions::default(), None).unwrap();
+    let pkgpaths: Vec<&str> = pkgs.iter().map(|pkg| pkg.pkgpath.as_str()).collect();
+    assert_eq!(pkgpaths, vec!["__main__", "base"]);
+
+    let main = &pkgs[0];
+    let names: Vec<&str> = main.schemas.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["Resource", "Service", "ReplicaMixin", "ServiceRule"]
+    );
+    let kinds: Vec<&str> = main.schemas.iter().map(|s| s.kind()).collect();
+    assert_eq!(kinds, vec!["Schema", "Schema", "Mixin", "Rule"]);
+
+    let resource = &main.schemas[0];
+    assert_eq!(resource.subclasses[0].name, "Service");
+    assert_eq!(
+        resource.attrs[0].ty,
+        vec![TypeSegment::Schema(SchemaRef {
+            name: "Metadata".to_string(),
+            pkgpath: "base".to_string(),
+            kind: "Schema",
+        })]
+    );
+
+    let service = &main.schemas[1];
+    assert_eq!(service.base.as_ref().unwrap().name, "Resource");
+    assert_eq!(service.mixins[0].name, "ReplicaMixin");
+    let attrs: Vec<&str> = service.attrs.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(attrs, vec!["kind", "ports", "type"]);
+    assert_eq!(
+        service.attrs[1].description,
+        "The ports exposed by the service."
+    );
+    assert_eq!(service.attrs[1].default.as_deref(), Some("[80]"));
+    assert!(service.attrs[2].is_optional);
+    assert_eq!(
+        service.attrs[2].ty,
+        vec![TypeSegment::Text(
+            "\"ClusterIP\" | \"NodePort\"".to_string()
+        )]
+    );
+    assert_eq!(
+        service.index_signature,
+        Some(vec![TypeSegment::Text("[key: str]: any".to_string())])
+    );
+    assert_eq!(
+        service.checks,
+        vec![
+            "len(ports) > 0",
+            "all port in ports {\n    0 < port < 65536\n} if ports, \"invalid port\"",
+        ]
+    );
+    assert_eq!(
+        service.examples,
+        "service = Service {\n    metadata.name = \"app\"\n}"
+    );
+
+    let metadata = &pkgs[1].schemas[0];
+    assert_eq!(
+        metadata.checks,
+        vec!["len(name) <= 63, \"the name is too long\""]
+    );
+}
+
+#[test]
+fn test_render_markdown() {
+    let pkgs =
+        load_package_docs(&[&test_data_path("main.k")], &DocOptions::default(), None).unwrap();
+    let page = markdown::render_package(&pkgs[0]);
+    assert!(page.starts_with("# Package main\n"));
+    assert!(page.contains("## Schema Service\n"));
+    assert!(page.contains("### Base Schema\n\n[Resource](#schema-resource)\n"));
+    assert!(page.contains("### Mixins\n\n[ReplicaMixin](#mixin-replicamixin)\n"));
+    assert!(page.contains(
+        "|**metadata**<br />The metadata of the resource.|[base.Metadata](pkg-base.md#schema-metadata)||**required**|"
+    ));
+    assert!(page.contains(
+        "|**type**<br />The type of the service.|\"ClusterIP\" \\| \"NodePort\"||optional|"
+    ));
+    let index = markdown::render_index(&pkgs);
+    assert!(index
+        .contains("- [base](pkg-base.md)\n  - [Schema Metadata](pkg-base.md#schema-metadata)\n"));
+}
+
+#[test]
+fn test_gen_doc_html() {
+    let output_dir = test_data_path("output_html");
+    let opts = DocOptions {
+        format: DocFormat::Html,
+        output_dir: output_dir.clone(),
+        ..Default::default()
+    };
+    let files = gen_doc(&[&test_data_path("main.k")], &opts, None).unwrap();
+    assert_eq!(files.len(), 3);
+    let page =
+        std::fs::read_to_string(PathBuf::from(&output_dir).join("pkg-__main__.html")).unwrap();
+    assert!(page.contains("<h2 id=\"schema-service\">Schema Service</h2>"));
+    assert!(page.contains("<a href=\"pkg-base.html#schema-metadata\">base.Metadata</a>"));
+    assert!(page.contains("<td>&quot;ClusterIP&quot; | &quot;NodePort&quot;</td>"));
+    std::fs::remove_dir_all(&output_dir).unwrap();
+}
+
+#[test]
+fn test_page_name() {
+    assert_eq!(
+        page_name("__main__", DocFormat::Markdown),
+        "pkg-__main__.md"
+    );
+    assert_eq!(page_name("main", DocFormat::Markdown), "pkg-main.md");
+    assert_eq!(page_name("index", DocFormat::Html), "pkg-index.html");
+    assert_eq!(page_name("", DocFormat::Markdown), "index.md");
+}
@@ -0,0 +1,250 @@
+//! Convert the simple schema check expressions into JSON Schema keywords, e.g.
+//!
+//! ```no_check
+//! check:
+//!     0 < port <= 65535          # exclusiveMinimum: 0, maximum: 65535
+//!     len(name) <= 63            # maxLength: 63
+//!     regex.match(name, "[a-z]") # pattern: "^[a-z]"
+//!     kind in ["A", "B"]         # enum: ["A", "B"]
+//! ```
+//!
+//! The checks which can not be expressed by the keywords of a single attribute
+//! such as the conditional checks are skipped.
+use super::export::Attr;
+use indexmap::IndexMap;
+use kclvm_ast::ast;
+use kclvm_sema::ty::TypeKind;
+use serde_json::{json, Map, Value};
+
+const MIN_KEYWORDS: [&str; 5] = [
+    "minimum",
+    "exclusiveMinimum",
+    "minLength",
+    "minItems",
+    "minProperties",
+];
+const MAX_KEYWORDS: [&str; 5] = [
+    "maximum",
+    "exclusiveMaximum",
+    "maxLength",
+    "maxItems",
+    "maxProperties",
+];
+
+/// The operand of a comparison in the check expression.
+enum Operand {
+    /// A schema attribute.
+    Attr(String),
+    /// The length of a schema attribute, e.g. `len(name)`.
+    Len(String),
+    /// A number literal.
+    Number(Value),
+}
+
+/// Apply the keywords converted from the check expression to the attribute properties.
+pub(crate) fn apply_check(
+    check: &ast::CheckExpr,
+    properties: &mut Map<String, Value>,
+    attrs: &IndexMap<String, Attr>,
+) {
+    if check.if_cond.is_some() {
+        return;
+    }
+    let mut conds = vec![];
+    split_and(&check.test.node, &mut conds);
+    for cond in conds {
+        for (name, keyword, value) in check_keywords(cond, attrs) {
+            if let Some(Value::Object(prop)) = properties.get_mut(&name) {
+                merge_keyword(prop, keyword, value);
+            }
+        }
+    }
+}
+
+/// Split the `a and b` expressions into the condition list.
+fn split_and<'e>(expr: &'e ast::Expr, conds: &mut Vec<&'e ast::Expr>) {
+    match expr {
+        ast::Expr::Binary(binary_expr)
+            if matches!(binary_expr.op, ast::BinOrCmpOp::Bin(ast::BinOp::And)) =>
+        {
+            split_and(&binary_expr.left.node, conds);
+            split_and(&binary_expr.right.node, conds);
+        }
+        ast::Expr::Paren(paren_expr) => split_and(&paren_expr.expr.node, conds),
+        _ => conds.push(expr),
+    }
+}
+
+fn check_keywords(
+    expr: &ast::Expr,
+    attrs: &IndexMap<String, Attr>,
+) -> Vec<(String, &'static str, Value)> {
+    let mut keywords = vec![];
+    match expr {
+        ast::Expr::Compare(compare) => {
+            let mut left = &compare.left.node;
+            for (op, right) in compare.ops.iter().zip(&compare.comparators) {
+                let right = &right.node;
+                if matches!(op, ast::CmpOp::In) {
+                    if let (Some(Operand::Attr(name)), ast::Expr::List(list)) =
+                        (operand(left, attrs), right)
+                    {
+                        let values: Option<Vec<Value>> =
+                            list.elts.iter().map(|elt| literal(&elt.node)).collect();
+                        if let Some(values) = values {
+                            keywords.push((name, "enum", Value::Array(values)));
+                        }
+                    }
+                } else {
+                    match (operand(left, attrs), operand(right, attrs)) {
+                        (Some(Operand::Number(n)), Some(operand)) => {
+                            if let Some(op) = flip(op) {
+                                keywords.append(&mut compare_keywords(operand, &op, n, attrs));
+                            }
+                        }
+                        (Some(operand), Some(Operand::Number(n))) => {
+                            keywords.append(&mut compare_keywords(operand, op, n, attrs));
+                        }
+                        _ => {}
+                    }
+                }
+                left = right;
+            }
+        }
+        ast::Expr::Call(call) => {
+            if let ast::Expr::Identifier(func) = &call.func.node {
+                if func.names == ["regex", "match"] && call.args.len() == 2 {
+                    if let (Some(Operand::Attr(name)), ast::Expr::StringLit(pattern)) =
+                        (operand(&call.args[0].node, attrs), &call.args[1].node)
+                    {
+                        // `regex.match` matches the pattern at the beginning of the string.
+                        let pattern = if pattern.value.starts_with('^') {
+                            pattern.value.clone()
+                        } else {
+                            format!("^{}", pattern.value)
+                        };
+                        keywords.push((name, "pattern", json!(pattern)));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    keywords
+}
+
+/// Convert the comparison `operand op n` into the keywords.
+fn compare_keywords(
+    operand: Operand,
+    op: &ast::CmpOp,
+    n: Value,
+    attrs: &IndexMap<String, Attr>,
+) -> Vec<(String, &'static str, Value)> {
+    match operand {
+        Operand::Attr(name) => {
+            if !matches!(attrs[&name].ty.kind, TypeKind::Int | TypeKind::Float) {
+                return vec![];
+            }
+            let keyword = match op {
+                ast::CmpOp::Gt => "exclusiveMinimum",
+                ast::CmpOp::GtE => "minimum",
+                ast::CmpOp::Lt => "exclusiveMaximum",
+                ast::CmpOp::LtE => "maximum",
+                ast::CmpOp::Eq => "const",
+                _ => return vec![],
+            };
+            vec![(name, keyword, n)]
+        }
+        Operand::Len(name) => {
+            let (min, max) = match &attrs[&name].ty.kind {
+                TypeKind::Str | TypeKind::StrLit(_) => ("minLength", "maxLength"),
+                TypeKind::List(_) => ("minItems", "maxItems"),
+                TypeKind::Dict(_, _) | TypeKind::Schema(_) => ("minProperties", "maxProperties"),
+                _ => return vec![],
+            };
+            let n = match n.as_i64() {
+                Some(n) => n,
+                None => return vec![],
+            };
+            match op {
+                ast::CmpOp::Gt => vec![(name, min, json!(n + 1))],
+                ast::CmpOp::GtE => vec![(name, min, json!(n))],
+                ast::CmpOp::Lt if n > 0 => vec![(name, max, json!(n - 1))],
+                ast::CmpOp::LtE => vec![(name, max, json!(n))],
+                ast::CmpOp::Eq => vec![(name.clone(), min, json!(n)), (name, max, json!(n))],
+                _ => vec![],
+            }
+        }
+        Operand::Number(_) => vec![],
+    }
+}
+
+fn operand(expr: &ast::Expr, attrs: &IndexMap<String, Attr>) -> Option<Operand> {
+    match expr {
+        ast::Expr::Identifier(identifier)
+            if identifier.names.len() == 1 && attrs.contains_key(&identifier.names[0]) =>
+        {
+            Some(Operand::Attr(identifier.names[0].clone()))
+        }
+        ast::Expr::Call(call) if call.args.len() == 1 && call.keywords.is_empty() => {
+            match (&call.func.node, operand(&call.args[0].node, attrs)) {
+                (ast::Expr::Identifier(func), Some(Operand::Attr(name)))
+                    if func.names == ["len"] =>
+                {
+                    Some(Operand::Len(name))
+                }
+                _ => None,
+            }
+        }
+        ast::Expr::NumberLit(_) | ast::Expr::Unary(_) => literal(expr).map(Operand::Number),
+        ast::Expr::Paren(paren_expr) => operand(&paren_expr.expr.node, attrs),
+        _ => None,
+    }
+}
+
+/// Convert the literal expression into the JSON value.
+fn literal(expr: &ast::Expr) -> Option<Value> {
+    match expr {
+        ast::Expr::NumberLit(number) if number.binary_suffix.is_none() => match number.value {
+            ast::NumberLitValue::Int(v) => Some(json!(v)),
+            ast::NumberLitValue::Float(v) => Some(json!(v)),
+        },
+        ast::Expr::Unary(unary_expr) if matches!(unary_expr.op, ast::UnaryOp::USub) => {
+            match literal(&unary_expr.operand.node)? {
+                Value::Number(n) if n.is_i64() => Some(json!(-n.as_i64()?)),
+                Value::Number(n) => Some(json!(-n.as_f64()?)),
+                _ => None,
+            }
+        }
+        ast::Expr::StringLit(string_lit) => Some(json!(string_lit.value)),
+        ast::Expr::NameConstantLit(name_constant) => match name_constant.value {
+            ast::NameConstant::True => Some(json!(true)),
+            ast::NameConstant::False => Some(json!(false)),
+            ast::NameConstant::None => Some(Value::Null),
+            ast::NameConstant::Undefined => None,
+        },
+        _ => None,
+    }
+}
+
+/// Flip the comparison operator when the operands are swapped.
+fn flip(op: &ast::CmpOp) -> Option<ast::CmpOp> {
+    match op {
+        ast::CmpOp::Gt => Some(ast::CmpOp::Lt),
+        ast::CmpOp::GtE => Some(ast::CmpOp::LtE),
+        ast::CmpOp::Lt => Some(ast::CmpOp::Gt),
+        ast::CmpOp::LtE => Some(ast::CmpOp::GtE),
+        ast::CmpOp::Eq => Some(ast::CmpOp::Eq),
+        _ => None,
+    }
+}
+
+/// Merge the keyword into the property, keep the stricter bound of the range keywords.
+fn merge_keyword(prop: &mut Map<String, Value>, keyword: &str, value: Value) {
+    let value = match (prop.get(keyword).and_then(|v| v.as_f64()), value.as_f64()) {
+        (Some(old), Some(new)) if MIN_KEYWORDS.contains(&keyword) && old > new => return,
+        (Some(old), Some(new)) if MAX_KEYWORDS.contains(&keyword) && old < new => return,
+        _ => value,
+    };
+    prop.insert(keyword.to_string(), value);
+}
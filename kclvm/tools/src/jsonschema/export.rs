@@ -0,0 +1,279 @@
+use super::check::apply_check;
+use super::ExportOptions;
+use crate::doc::docstring::parse_docstring;
+use crate::util::lookup_schema_ty;
+use anyhow::{anyhow, Result};
+use indexmap::IndexMap;
+use kclvm_ast::{ast, MAIN_PKG};
+use kclvm_sema::resolver::scope::ProgramScope;
+use kclvm_sema::ty::{SchemaType, Type, TypeKind};
+use serde_json::{json, Map, Value};
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// The schema attribute collected from the schema, its base schemas and mixins.
+pub(crate) struct Attr {
+    pub ty: Rc<Type>,
+    pub required: bool,
+    pub description: String,
+}
+
+/// Exporter converts the schema types into the JSON Schema definitions.
+pub(crate) struct Exporter<'a> {
+    program: &'a ast::Program,
+    scope: &'a ProgramScope,
+    opts: &'a ExportOptions,
+    pub(crate) defs: Map<String, Value>,
+    queue: VecDeque<(String, String)>,
+}
+
+impl<'a> Exporter<'a> {
+    pub(crate) fn new(
+        program: &'a ast::Program,
+        scope: &'a ProgramScope,
+        opts: &'a ExportOptions,
+    ) -> Self {
+        Self {
+            program,
+            scope,
+            opts,
+            defs: Map::new(),
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Export the selected main package schemas and all the schemas referenced by them,
+    /// returns the definition names of the selected schemas.
+    pub(crate) fn export(&mut self) -> Result<Vec<String>> {
+        let schemas = self.main_schemas();
+        let roots: Vec<SchemaType> = if self.opts.schemas.is_empty() {
+            schemas
+        } else {
+            self.opts
+                .schemas
+                .iter()
+                .map(|name| {
+                    schemas
+                        .iter()
+                        .find(|ty| &ty.name == name)
+                        .cloned()
+                        .ok_or_else(|| anyhow!("schema '{}' not found in the main package", name))
+                })
+                .collect::<Result<Vec<SchemaType>>>()?
+        };
+        let names = roots.iter().map(def_name).collect();
+        for ty in &roots {
+            self.queue.push_back((ty.pkgpath.clone(), ty.name.clone()));
+        }
+        while let Some((pkgpath, name)) = self.queue.pop_front() {
+            let ty = match lookup_schema_ty(self.scope, &pkgpath, &name) {
+                Some(ty) => ty,
+                None => continue,
+            };
+            let key = def_name(&ty);
+            if !self.defs.contains_key(&key) {
+                // Insert a placeholder first to break the cycle of the recursive schemas.
+                self.defs.insert(key.clone(), Value::Null);
+                let def = self.schema_def(&ty);
+                self.defs.insert(key, def);
+            }
+        }
+        Ok(names)
+    }
+
+    /// The exportable schemas defined in the main package.
+    fn main_schemas(&self) -> Vec<SchemaType> {
+        let mut schemas = vec![];
+        if let Some(scope) = self.scope.scope_map.get(MAIN_PKG) {
+            for obj in scope.borrow().elems.values() {
+                if let TypeKind::Schema(ty) = &obj.borrow().ty.kind {
+                    if !ty.is_instance
+                        && !ty.is_mixin
+                        && !ty.is_protocol
+                        && !ty.is_rule
+                        && ty.pkgpath == MAIN_PKG
+                        && !ty.name.starts_with('_')
+                    {
+                        schemas.push(ty.clone());
+                    }
+                }
+            }
+        }
+        schemas
+    }
+
+    /// Get the schema statement which defines the schema type in its package.
+    fn schema_stmt(&self, ty: &SchemaType) -> Option<&'a ast::SchemaStmt> {
+        self.program
+            .pkgs
+            .get(&ty.pkgpath)?
+            .iter()
+            .flat_map(|module| module.body.iter())
+            .find_map(|stmt| match &stmt.node {
+                ast::Stmt::Schema(schema_stmt) if schema_stmt.name.node == ty.name => {
+                    Some(schema_stmt)
+                }
+                _ => None,
+            })
+    }
+
+    /// Collect the attributes and checks of the schema, its base schemas and mixins.
+    fn collect(
+        &self,
+        ty: &SchemaType,
+        attrs: &mut IndexMap<String, Attr>,
+        checks: &mut Vec<&'a ast::CheckExpr>,
+    ) {
+        let ty = lookup_schema_ty(self.scope, &ty.pkgpath, &ty.name).unwrap_or_else(|| ty.clone());
+        if let Some(base) = &ty.base {
+            self.collect(base, attrs, checks);
+        }
+        for mixin in &ty.mixins {
+            self.collect(mixin, attrs, checks);
+        }
+        let docstring = parse_docstring(&ty.doc);
+        for (name, attr) in &ty.attrs {
+            if name.starts_with("__") || (name.starts_with('_') && !self.opts.include_private) {
+                continue;
+            }
+            let description = docstring.attrs.get(name).cloned().unwrap_or_else(|| {
+                attrs
+                    .get(name)
+                    .map(|attr| attr.description.clone())
+                    .unwrap_or_default()
+            });
+            attrs.insert(
+                name.clone(),
+                Attr {
+                    ty: attr.ty.clone(),
+                    required: !attr.is_optional && !attr.has_default,
+                    description,
+                },
+            );
+        }
+        if let Some(schema_stmt) = self.schema_stmt(&ty) {
+            checks.extend(schema_stmt.checks.iter().map(|check| &check.node));
+        }
+    }
+
+    fn schema_def(&mut self, ty: &SchemaType) -> Value {
+        let mut attrs = IndexMap::new();
+        let mut checks = vec![];
+        self.collect(ty, &mut attrs, &mut checks);
+
+        let mut def = Map::new();
+        def.insert("type".to_string(), json!("object"));
+        def.insert("title".to_string(), json!(ty.name));
+        let description = parse_docstring(&ty.doc).description;
+        if !description.is_empty() {
+            def.insert("description".to_string(), json!(description));
+        }
+        let mut properties = Map::new();
+        let mut required = vec![];
+        for (name, attr) in &attrs {
+            let mut prop = self.type_schema(&attr.ty);
+            if !attr.description.is_empty() {
+                if let Value::Object(prop) = &mut prop {
+                    prop.insert("description".to_string(), json!(attr.description));
+                }
+            }
+            properties.insert(name.clone(), prop);
+            if attr.required {
+                required.push(json!(name));
+            }
+        }
+        for check in checks {
+            apply_check(check, &mut properties, &attrs);
+        }
+        def.insert("properties".to_string(), Value::Object(properties));
+        if !required.is_empty() {
+            def.insert("required".to_string(), Value::Array(required));
+        }
+        let additional_properties = match &ty.index_signature {
+            Some(index_signature) => self.type_schema(&index_signature.val_ty),
+            None => json!(false),
+        };
+        def.insert("additionalProperties".to_string(), additional_properties);
+        Value::Object(def)
+    }
+
+    /// Convert the KCL type into the JSON Schema and record the referenced schemas.
+    pub(crate) fn type_schema(&mut self, ty: &Type) -> Value {
+        match &ty.kind {
+            TypeKind::None => json!({"type": "null"}),
+            TypeKind::Bool => json!({"type": "boolean"}),
+            TypeKind::Int => json!({"type": "integer"}),
+            TypeKind::Float => json!({"type": "number"}),
+            TypeKind::Str => json!({"type": "string"}),
+            TypeKind::BoolLit(_)
+            | TypeKind::IntLit(_)
+            | TypeKind::FloatLit(_)
+            | TypeKind::StrLit(_) => enum_schema(&[ty]),
+            TypeKind::List(item_ty) => json!({"type": "array", "items": self.type_schema(item_ty)}),
+            TypeKind::Dict(_, val_ty) => {
+                json!({"type": "object", "additionalProperties": self.type_schema(val_ty)})
+            }
+            TypeKind::Union(types) => {
+                let types: Vec<&Type> = types.iter().map(|ty| ty.as_ref()).collect();
+                if types.iter().all(|ty| ty.is_literal()) {
+                    enum_schema(&types)
+                } else {
+                    let one_of: Vec<Value> = types.iter().map(|ty| self.type_schema(ty)).collect();
+                    json!({ "oneOf": one_of })
+                }
+            }
+            TypeKind::Schema(schema_ty) => {
+                self.queue
+                    .push_back((schema_ty.pkgpath.clone(), schema_ty.name.clone()));
+                json!({"$ref": format!("{}{}", self.opts.format.ref_prefix(), def_name(schema_ty))})
+            }
+            // Number multiplier values such as `1Gi` are exported as strings.
+            TypeKind::NumberMultiplier(_) => json!({"type": "string"}),
+            _ => json!({}),
+        }
+    }
+}
+
+/// The definition name of the schema, the schemas outside the main package
+/// are qualified by the package path.
+pub(crate) fn def_name(ty: &SchemaType) -> String {
+    if ty.pkgpath == MAIN_PKG || ty.pkgpath.is_empty() {
+        ty.name.clone()
+    } else {
+        format!("{}.{}", ty.pkgpath, ty.name)
+    }
+}
+
+/// Convert the literal types into an `enum` schema.
+fn enum_schema(types: &[&Type]) -> Value {
+    let values: Vec<Value> = types.iter().filter_map(|ty| literal_value(ty)).collect();
+    let type_names: Vec<&str> = types.iter().filter_map(|ty| literal_type(ty)).collect();
+    let mut schema = Map::new();
+    if let Some(first) = type_names.first() {
+        if type_names.iter().all(|name| name == first) {
+            schema.insert("type".to_string(), json!(first));
+        }
+    }
+    schema.insert("enum".to_string(), Value::Array(values));
+    Value::Object(schema)
+}
+
+fn literal_value(ty: &Type) -> Option<Value> {
+    match &ty.kind {
+        TypeKind::BoolLit(v) => Some(json!(v)),
+        TypeKind::IntLit(v) => Some(json!(v)),
+        TypeKind::FloatLit(v) => Some(json!(v)),
+        TypeKind::StrLit(v) => Some(json!(v)),
+        _ => None,
+    }
+}
+
+fn literal_type(ty: &Type) -> Option<&'static str> {
+    match &ty.kind {
+        TypeKind::BoolLit(_) => Some("boolean"),
+        TypeKind::IntLit(_) => Some("integer"),
+        TypeKind::FloatLit(_) => Some("number"),
+        TypeKind::StrLit(_) => Some("string"),
+        _ => None,
+    }
+}
@@ -0,0 +1,824 @@
+//! Generate the KCL schema source from JSON Schema documents, OpenAPI
+//! specifications and Kubernetes CustomResourceDefinitions.
+//!
+//! The generator builds [kclvm_ast::ast::SchemaStmt] nodes with docstrings,
+//! optional attributes, default values and check blocks, and prints them with
+//! [kclvm_ast_pretty::print_ast_module]. Inline object schemas become separate
+//! schemas named by the parent schema and the attribute name.
+use crate::util::loader::LoaderKind;
+use crate::vet::expr_builder::ExprBuilder;
+use anyhow::{anyhow, bail, Result};
+use kclvm_ast::ast::{
+    AugOp, BinOp, BinOrAugOp, BinOrCmpOp, BinaryExpr, CallExpr, CheckExpr, CmpOp, Compare, Expr,
+    ExprContext, Identifier, ImportStmt, Module, NameConstant, NameConstantLit, Node, NodeRef,
+    SchemaAttr, SchemaIndexSignature, SchemaStmt, Stmt, StringLit,
+};
+use kclvm_ast::node_ref;
+use kclvm_ast_pretty::print_ast_module;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// The KCL keywords which need the `$` prefix to be used as attribute names.
+const KEYWORDS: [&str; 31] = [
+    "as",
+    "import",
+    "rule",
+    "schema",
+    "mixin",
+    "protocol",
+    "check",
+    "for",
+    "assert",
+    "if",
+    "elif",
+    "else",
+    "or",
+    "and",
+    "not",
+    "in",
+    "is",
+    "lambda",
+    "all",
+    "any",
+    "filter",
+    "map",
+    "type",
+    "True",
+    "False",
+    "None",
+    "Undefined",
+    "pass",
+    "return",
+    "final",
+    "def",
+];
+const CRD_KIND: &str = "CustomResourceDefinition";
+
+/// The format of the imported document.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// A JSON Schema document, the root schema and the schemas in
+    /// `$defs` or `definitions` are imported.
+    #[default]
+    JsonSchema,
+    /// An OpenAPI v3 or Swagger v2 document, the schemas in `components.schemas`
+    /// or `definitions` are imported.
+    OpenApi,
+    /// A YAML stream of Kubernetes CustomResourceDefinitions, each served version
+    /// is imported as a schema named by the resource kind.
+    Crd,
+}
+
+impl TryFrom<&str> for ImportFormat {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "jsonschema" | "json-schema" => Ok(ImportFormat::JsonSchema),
+            "openapi" | "swagger" => Ok(ImportFormat::OpenApi),
+            "crd" => Ok(ImportFormat::Crd),
+            _ => Err(anyhow!("unsupported import format '{}'", s)),
+        }
+    }
+}
+
+/// ImportOptions contains the options of the schema import:
+/// - format: the format of the imported document.
+/// - name: the schema name of the JSON Schema root, defaults to the root `title`
+///   or the file name.
+#[derive(Debug, Default, Clone)]
+pub struct ImportOptions {
+    pub format: ImportFormat,
+    pub name: String,
+}
+
+/// Generates the KCL schema source from the JSON or YAML file `path`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kclvm_tools::jsonschema::{import_file, ImportFormat, ImportOptions};
+///
+/// let opts = ImportOptions {
+///     format: ImportFormat::Crd,
+///     ..Default::default()
+/// };
+/// let code = import_file("crd.yaml", &opts).unwrap();
+/// std::fs::write("crd.k", code).unwrap();
+/// ```
+pub fn import_file(path: &str, opts: &ImportOptions) -> Result<String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| anyhow!("failed to read '{}': {}", path, err))?;
+    let mut opts = opts.clone();
+    if opts.name.is_empty() {
+        if let Some(stem) = Path::new(path).file_stem() {
+            opts.name = stem.to_string_lossy().to_string();
+        }
+    }
+    import_str(&content, &opts)
+}
+
+/// Generates the KCL schema source from the JSON or YAML string `content`.
+pub fn import_str(content: &str, opts: &ImportOptions) -> Result<String> {
+    Ok(print_ast_module(&import_module(content, opts)?))
+}
+
+/// Generates the KCL module which contains the imported schema statements.
+pub fn import_module(content: &str, opts: &ImportOptions) -> Result<Module> {
+    // YAML is a superset of JSON, all the formats can be loaded as YAML streams.
+    let mut docs = vec![];
+    for document in serde_yaml::Deserializer::from_str(content) {
+        let doc = Value::deserialize(document)?;
+        if !doc.is_null() {
+            docs.push(doc);
+        }
+    }
+    let mut importer = Importer::default();
+    match opts.format {
+        ImportFormat::JsonSchema => {
+            let doc = docs
+                .first()
+                .ok_or_else(|| anyhow!("empty JSON Schema document"))?;
+            importer.register_defs(doc.get("$defs"), "#/$defs/");
+            importer.register_defs(doc.get("definitions"), "#/definitions/");
+            let root = doc
+                .get("title")
+                .and_then(|title| title.as_str())
+                .filter(|_| opts.name.is_empty())
+                .unwrap_or(&opts.name);
+            if doc.get("properties").is_some() || doc.get("allOf").is_some() {
+                let name = importer.alloc_name(if root.is_empty() { "Root" } else { root });
+                importer.register_ref("#", &name);
+                importer.gen_schema(&name, doc)?;
+            }
+            importer.gen_defs(doc.get("$defs"), "#/$defs/")?;
+            importer.gen_defs(doc.get("definitions"), "#/definitions/")?;
+        }
+        ImportFormat::OpenApi => {
+            let doc = docs
+                .first()
+                .ok_or_else(|| anyhow!("empty OpenAPI document"))?;
+            let components = doc.get("components").and_then(|c| c.get("schemas"));
+            importer.register_defs(components, "#/components/schemas/");
+            importer.register_defs(doc.get("definitions"), "#/definitions/");
+            importer.gen_defs(components, "#/components/schemas/")?;
+            importer.gen_defs(doc.get("definitions"), "#/definitions/")?;
+        }
+        ImportFormat::Crd => {
+            let crds: Vec<&Value> = docs
+                .iter()
+                .filter(|doc| doc.get("kind").and_then(|kind| kind.as_str()) == Some(CRD_KIND))
+                .collect();
+            if crds.is_empty() {
+                bail!("no {} found in the document", CRD_KIND);
+            }
+            for crd in crds {
+                importer.gen_crd(crd)?;
+            }
+        }
+    }
+    Ok(importer.module())
+}
+
+#[derive(Default)]
+struct Importer {
+    /// The schema names of the references such as `#/$defs/Name`.
+    ref_names: HashMap<String, String>,
+    used_names: HashSet<String>,
+    /// The generated schema statements, the slots are reserved before the nested
+    /// schemas are generated to keep the parent schemas first.
+    stmts: Vec<Option<NodeRef<Stmt>>>,
+    import_regex: bool,
+}
+
+impl Importer {
+    fn module(self) -> Module {
+        let mut body = vec![];
+        if self.import_regex {
+            body.push(node_ref!(Stmt::Import(ImportStmt {
+                path: "regex".to_string(),
+                rawpath: "regex".to_string(),
+                name: "regex".to_string(),
+                asname: None,
+            })));
+        }
+        body.extend(self.stmts.into_iter().flatten());
+        Module {
+            filename: "".to_string(),
+            pkg: "".to_string(),
+            doc: "".to_string(),
+            name: "".to_string(),
+            body,
+            comments: vec![],
+        }
+    }
+
+    fn register_defs(&mut self, defs: Option<&Value>, prefix: &str) {
+        if let Some(Value::Object(defs)) = defs {
+            for key in defs.keys() {
+                let name = self.alloc_name(key);
+                self.register_ref(&format!("{}{}", prefix, key), &name);
+            }
+        }
+    }
+
+    fn register_ref(&mut self, reference: &str, name: &str) {
+        self.ref_names
+            .insert(reference.to_string(), name.to_string());
+    }
+
+    fn gen_defs(&mut self, defs: Option<&Value>, prefix: &str) -> Result<()> {
+        if let Some(Value::Object(defs)) = defs {
+            for (key, def) in defs {
+                let name = self.ref_names[&format!("{}{}", prefix, key)].clone();
+                self.gen_schema(&name, def)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn gen_crd(&mut self, crd: &Value) -> Result<()> {
+        let spec = crd
+            .get("spec")
+            .ok_or_else(|| anyhow!("invalid {}: missing spec", CRD_KIND))?;
+        let group = spec
+            .get("group")
+            .and_then(|g| g.as_str())
+            .unwrap_or_default();
+        let kind = spec
+            .get("names")
+            .and_then(|names| names.get("kind"))
+            .and_then(|kind| kind.as_str())
+            .ok_or_else(|| anyhow!("invalid {}: missing spec.names.kind", CRD_KIND))?;
+        let mut versions: Vec<(String, Value)> = vec![];
+        match spec.get("versions").and_then(|v| v.as_array()) {
+            Some(items) => {
+                for item in items {
+                    let version = item
+                        .get("name")
+                        .and_then(|n| n.as_str())
+                        .unwrap_or_default();
+                    // The v1beta1 CRDs may share the schema of all the versions in spec.validation.
+                    let schema = item
+                        .get("schema")
+                        .or_else(|| spec.get("validation"))
+                        .and_then(|s| s.get("openAPIV3Schema"));
+                    if let Some(schema) = schema {
+                        versions.push((version.to_string(), schema.clone()));
+                    }
+                }
+            }
+            None => {
+                let version = spec
+                    .get("version")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                if let Some(schema) = spec
+                    .get("validation")
+                    .and_then(|s| s.get("openAPIV3Schema"))
+                {
+                    versions.push((version.to_string(), schema.clone()));
+                }
+            }
+        }
+        let multiple = versions.len() > 1;
+        for (version, mut schema) in versions {
+            let api_version = if group.is_empty() {
+                version.clone()
+            } else {
+                format!("{}/{}", group, version)
+            };
+            if let Value::Object(schema) = &mut schema {
+                let properties = schema
+                    .entry("properties")
+                    .or_insert_with(|| json!({}))
+                    .as_object_mut()
+                    .ok_or_else(|| anyhow!("invalid {}: invalid properties", CRD_KIND))?;
+                for (key, value) in [("apiVersion", &api_version), ("kind", &kind.to_string())] {
+                    let description = properties
+                        .get(key)
+                        .and_then(|p| p.get("description"))
+                        .cloned()
+                        .unwrap_or(Value::Null);
+                    let mut prop = json!({"type": "string", "enum": [value], "default": value});
+                    if !description.is_null() {
+                        prop["description"] = description;
+                    }
+                    properties.insert(key.to_string(), prop);
+                }
+                let required = schema.entry("required").or_insert_with(|| json!([]));
+                if let Value::Array(required) = required {
+                    for key in ["apiVersion", "kind"] {
+                        if !required.contains(&json!(key)) {
+                            required.push(json!(key));
+                        }
+                    }
+                }
+            }
+            let name = if multiple {
+                format!("{}{}", kind, pascal_case(&version))
+            } else {
+                kind.to_string()
+            };
+            let name = self.alloc_name(&name);
+            self.gen_schema(&name, &schema)?;
+        }
+        Ok(())
+    }
+
+    /// Allocate a unique schema name from the definition key, e.g.
+    /// `io.k8s.api.core.v1.Pod` is named `Pod` when it is not used.
+    fn alloc_name(&mut self, key: &str) -> String {
+        let last = key.rsplit(['.', '/']).next().unwrap_or(key);
+        let mut candidates = vec![pascal_case(last), pascal_case(key)];
+        candidates.dedup();
+        let mut name = candidates
+            .into_iter()
+            .find(|name| !self.used_names.contains(name))
+            .unwrap_or_else(|| pascal_case(key));
+        if self.used_names.contains(&name) {
+            let mut i = 1;
+            while self.used_names.contains(&format!("{}{}", name, i)) {
+                i += 1;
+            }
+            name = format!("{}{}", name, i);
+        }
+        self.used_names.insert(name.clone());
+        name
+    }
+
+    fn gen_schema(&mut self, name: &str, schema: &Value) -> Result<()> {
+        let index = self.stmts.len();
+        self.stmts.push(None);
+
+        let mut parent_name = None;
+        let mut properties = Map::new();
+        let mut required: Vec<String> = vec![];
+        let mut additional = None;
+        let mut parts = vec![schema];
+        if let Some(Value::Array(all_of)) = schema.get("allOf") {
+            parts.extend(all_of.iter());
+        }
+        for part in parts {
+            if let Some(reference) = part.get("$ref").and_then(|r| r.as_str()) {
+                if parent_name.is_none() {
+                    parent_name = self.ref_names.get(reference).cloned();
+                }
+                continue;
+            }
+            if let Some(Value::Object(props)) = part.get("properties") {
+                properties.extend(props.clone());
+            }
+            if let Some(Value::Array(items)) = part.get("required") {
+                required.extend(items.iter().filter_map(|r| r.as_str()).map(String::from));
+            }
+            match part.get("additionalProperties") {
+                Some(Value::Bool(true)) => additional = Some("any".to_string()),
+                Some(Value::Object(value)) => {
+                    additional = Some(
+                        self.type_str(&Value::Object(value.clone()), &format!("{}Value", name))?,
+                    )
+                }
+                _ => {}
+            }
+            if part.get("x-kubernetes-preserve-unknown-fields") == Some(&Value::Bool(true)) {
+                additional = Some("any".to_string());
+            }
+        }
+
+        let mut body = vec![];
+        let mut checks = vec![];
+        let mut attr_docs = vec![];
+        for (key, prop) in &properties {
+            let attr_name = match attr_name(key) {
+                Some(attr_name) => attr_name,
+                None => {
+                    // The keys which are not valid identifiers are accepted by the index signature.
+                    additional.get_or_insert_with(|| "any".to_string());
+                    continue;
+                }
+            };
+            let type_str = self.type_str(prop, &format!("{}{}", name, pascal_case(key)))?;
+            let is_optional = !required.contains(key);
+            let default = match prop.get("default") {
+                Some(default) if !default.is_null() => Some(
+                    ExprBuilder::new_with_str(LoaderKind::JSON, default.to_string())?
+                        .build(None)?,
+                ),
+                _ => None,
+            };
+            let mut attr_doc = format!("{} : {}", key, type_str);
+            if let Some(default) = prop.get("default").filter(|d| !d.is_null()) {
+                attr_doc.push_str(&format!(", default is {}", default));
+            }
+            attr_doc.push_str(if is_optional {
+                ", optional"
+            } else {
+                ", required"
+            });
+            attr_docs.push((
+                attr_doc,
+                prop.get("description")
+                    .and_then(|d| d.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+            ));
+            checks.append(&mut self.gen_checks(&attr_name, prop, is_optional));
+            body.push(node_ref!(Stmt::SchemaAttr(SchemaAttr {
+                doc: "".to_string(),
+                name: node_ref!(attr_name),
+                type_str: node_ref!(type_str),
+                op: default.as_ref().map(|_| BinOrAugOp::Aug(AugOp::Assign)),
+                value: default,
+                is_optional,
+                decorators: vec![],
+                ty: None,
+            })));
+        }
+        let description = schema
+            .get("description")
+            .and_then(|d| d.as_str())
+            .unwrap_or_default();
+        let stmt = SchemaStmt {
+            doc: docstring(description, &attr_docs),
+            name: node_ref!(name.to_string()),
+            parent_name: parent_name.map(|parent_name| node_ref!(identifier(&parent_name))),
+            for_host_name: None,
+            is_mixin: false,
+            is_protocol: false,
+            args: None,
+            mixins: vec![],
+            body,
+            decorators: vec![],
+            checks,
+            index_signature: additional.map(|value_type| {
+                node_ref!(SchemaIndexSignature {
+                    key_name: None,
+                    key_type: node_ref!("str".to_string()),
+                    value_type: node_ref!(value_type),
+                    value: None,
+                    any_other: true,
+                    value_ty: None,
+                })
+            }),
+        };
+        self.stmts[index] = Some(node_ref!(Stmt::Schema(stmt)));
+        Ok(())
+    }
+
+    /// Convert the JSON Schema into the KCL type string, the inline object
+    /// schemas are generated as the schemas named `nested_name`.
+    fn type_str(&mut self, schema: &Value, nested_name: &str) -> Result<String> {
+        if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+            return Ok(self
+                .ref_names
+                .get(reference)
+                .cloned()
+                .unwrap_or_else(|| "any".to_string()));
+        }
+        if let Some(Value::Array(values)) = schema.get("enum") {
+            let literals: Vec<String> = values.iter().filter_map(literal_type_str).collect();
+            return Ok(if literals.is_empty() {
+                "any".to_string()
+            } else {
+                literals.join(" | ")
+            });
+        }
+        if let Some(value) = schema.get("const") {
+            return Ok(literal_type_str(value).unwrap_or_else(|| "any".to_string()));
+        }
+        if schema.get("x-kubernetes-int-or-string") == Some(&Value::Bool(true)) {
+            return Ok("int | str".to_string());
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(Value::Array(items)) = schema.get(key) {
+                let mut types: Vec<String> = vec![];
+                for (i, item) in items.iter().enumerate() {
+                    let ty = self.type_str(item, &format!("{}{}", nested_name, i))?;
+                    if ty == "any" {
+                        return Ok(ty);
+                    }
+                    if !types.contains(&ty) {
+                        types.push(ty);
+                    }
+                }
+                if !types.is_empty() {
+                    return Ok(types.join(" | "));
+                }
+            }
+        }
+        if let Some(Value::Array(items)) = schema.get("allOf") {
+            if items.len() == 1 && schema.get("properties").is_none() {
+                return self.type_str(&items[0], nested_name);
+            }
+        }
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(ty)) => vec![ty.as_str()],
+            Some(Value::Array(types)) => types
+                .iter()
+                .filter_map(|ty| ty.as_str())
+                .filter(|ty| *ty != "null")
+                .collect(),
+            _ => vec![],
+        };
+        let types = if types.is_empty() {
+            if schema.get("properties").is_some() || schema.get("allOf").is_some() {
+                vec!["object"]
+            } else {
+                return Ok("any".to_string());
+            }
+        } else {
+            types
+        };
+        let mut type_strs = vec![];
+        for ty in types {
+            let type_str = match ty {
+                "string" => "str".to_string(),
+                "integer" => "int".to_string(),
+                "number" => "float".to_string(),
+                "boolean" => "bool".to_string(),
+                "array" => match schema.get("items") {
+                    Some(items) => format!("[{}]", self.type_str(items, nested_name)?),
+                    None => "[any]".to_string(),
+                },
+                "object" => {
+                    let has_properties = matches!(schema.get("properties"), Some(Value::Object(props)) if !props.is_empty())
+                        || schema.get("allOf").is_some();
+                    if has_properties {
+                        let name = self.alloc_name(nested_name);
+                        self.gen_schema(&name, schema)?;
+                        name
+                    } else {
+                        match schema.get("additionalProperties") {
+                            Some(Value::Object(value)) => format!(
+                                "{{str:{}}}",
+                                self.type_str(&Value::Object(value.clone()), nested_name)?
+                            ),
+                            _ => "{str:any}".to_string(),
+                        }
+                    }
+                }
+                _ => "any".to_string(),
+            };
+            type_strs.push(type_str);
+        }
+        Ok(type_strs.join(" | "))
+    }
+
+    /// Convert the validation keywords of the attribute into the check expressions.
+    fn gen_checks(
+        &mut self,
+        name: &str,
+        schema: &Value,
+        is_optional: bool,
+    ) -> Vec<NodeRef<CheckExpr>> {
+        let mut tests = vec![];
+        // OpenAPI v3.0 uses boolean `exclusiveMinimum` and `exclusiveMaximum` with `minimum` and `maximum`.
+        let exclusive = |key: &str| schema.get(key) == Some(&Value::Bool(true));
+        let bound = |key: &str| schema.get(key).filter(|v| v.is_number());
+        let lower = match (bound("exclusiveMinimum"), bound("minimum")) {
+            (Some(v), _) => Some((v, CmpOp::Lt)),
+            (None, Some(v)) if exclusive("exclusiveMinimum") => Some((v, CmpOp::Lt)),
+            (None, Some(v)) => Some((v, CmpOp::LtE)),
+            _ => None,
+        };
+        let upper = match (bound("exclusiveMaximum"), bound("maximum")) {
+            (Some(v), _) => Some((v, CmpOp::Lt)),
+            (None, Some(v)) if exclusive("exclusiveMaximum") => Some((v, CmpOp::Lt)),
+            (None, Some(v)) => Some((v, CmpOp::LtE)),
+            _ => None,
+        };
+        if let Some(test) = range_test(identifier_expr(name), lower, upper) {
+            tests.push(test);
+        }
+        for (min, max) in [
+            ("minLength", "maxLength"),
+            ("minItems", "maxItems"),
+            ("minProperties", "maxProperties"),
+        ] {
+            let len = node_ref!(Expr::Call(CallExpr {
+                func: identifier_expr("len"),
+                args: vec![identifier_expr(name)],
+                keywords: vec![],
+            }));
+            let lower = bound(min).map(|v| (v, CmpOp::LtE));
+            let upper = bound(max).map(|v| (v, CmpOp::LtE));
+            if let Some(test) = range_test(len, lower, upper) {
+                tests.push(test);
+            }
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(|p| p.as_str()) {
+            self.import_regex = true;
+            tests.push(node_ref!(Expr::Call(CallExpr {
+                func: identifier_expr("regex.match"),
+                args: vec![identifier_expr(name), string_expr(pattern)],
+                keywords: vec![],
+            })));
+        }
+        if let Some(multiple_of) = bound("multipleOf") {
+            tests.push(node_ref!(Expr::Compare(Compare {
+                left: node_ref!(Expr::Binary(BinaryExpr {
+                    left: identifier_expr(name),
+                    op: BinOrCmpOp::Bin(BinOp::Mod),
+                    right: value_expr(multiple_of),
+                })),
+                ops: vec![CmpOp::Eq],
+                comparators: vec![value_expr(&json!(0))],
+            })));
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            tests.push(node_ref!(Expr::Call(CallExpr {
+                func: identifier_expr("isunique"),
+                args: vec![identifier_expr(name)],
+                keywords: vec![],
+            })));
+        }
+        tests
+            .into_iter()
+            .map(|test| {
+                node_ref!(CheckExpr {
+                    test,
+                    // The optional attributes are only checked when they are set.
+                    if_cond: is_optional.then(|| {
+                        node_ref!(Expr::Compare(Compare {
+                            left: identifier_expr(name),
+                            ops: vec![CmpOp::IsNot],
+                            comparators: vec![node_ref!(Expr::NameConstantLit(NameConstantLit {
+                                value: NameConstant::None,
+                            }))],
+                        }))
+                    }),
+                    msg: None,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Build the comparison `lower op expr op upper`.
+fn range_test(
+    expr: NodeRef<Expr>,
+    lower: Option<(&Value, CmpOp)>,
+    upper: Option<(&Value, CmpOp)>,
+) -> Option<NodeRef<Expr>> {
+    let (left, mut ops, mut comparators) = match lower {
+        Some((v, op)) => (value_expr(v), vec![op], vec![expr]),
+        None => (expr, vec![], vec![]),
+    };
+    if let Some((v, op)) = upper {
+        ops.push(op);
+        comparators.push(value_expr(v));
+    }
+    if ops.is_empty() {
+        None
+    } else {
+        Some(node_ref!(Expr::Compare(Compare {
+            left,
+            ops,
+            comparators,
+        })))
+    }
+}
+
+/// Build the schema docstring in the numpydoc style.
+pub(super) fn docstring(description: &str, attrs: &[(String, String)]) -> String {
+    let mut lines: Vec<String> = description.trim().lines().map(String::from).collect();
+    if !attrs.is_empty() {
+        if !lines.is_empty() {
+            lines.push("".to_string());
+        }
+        lines.push("Attributes".to_string());
+        lines.push("----------".to_string());
+        for (attr, description) in attrs {
+            lines.push(attr.to_string());
+            for line in description.trim().lines() {
+                lines.push(format!("    {}", line.trim()));
+            }
+        }
+    }
+    if lines.is_empty() {
+        return "".to_string();
+    }
+    // The docstring is printed at the schema body indentation.
+    let body = lines
+        .iter()
+        .map(|line| {
+            let line = line.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"");
+            if line.trim().is_empty() {
+                "".to_string()
+            } else {
+                format!("    {}", line.trim_end())
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!("\"\"\"\n{}\n    \"\"\"", body)
+}
+
+/// The attribute name of the property key, the KCL keywords are prefixed by `$`
+/// and the keys which are not valid identifiers return `None`.
+pub(super) fn attr_name(key: &str) -> Option<String> {
+    let mut chars = key.chars();
+    let valid = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        None
+    } else if KEYWORDS.contains(&key) {
+        Some(format!("${}", key))
+    } else {
+        Some(key.to_string())
+    }
+}
+
+/// Convert the name into a PascalCase schema name, e.g. `pod-spec` to `PodSpec`.
+pub(super) fn pascal_case(name: &str) -> String {
+    let name: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(c) => c.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => "".to_string(),
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("Schema{}", name)
+    } else {
+        name
+    }
+}
+
+fn literal_type_str(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(quote_str(s)),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(true) => Some("True".to_string()),
+        Value::Bool(false) => Some("False".to_string()),
+        _ => None,
+    }
+}
+
+pub(super) fn identifier(name: &str) -> Identifier {
+    Identifier {
+        names: name.split('.').map(String::from).collect(),
+        pkgpath: "".to_string(),
+        ctx: ExprContext::Load,
+    }
+}
+
+fn identifier_expr(name: &str) -> NodeRef<Expr> {
+    node_ref!(Expr::Identifier(identifier(name)))
+}
+
+fn string_expr(value: &str) -> NodeRef<Expr> {
+    // A `${` in a string literal starts an interpolation whatever its escaping,
+    // only the raw strings keep it as it is.
+    let raw_value = if value.contains("${")
+        && !value.contains(|c: char| c == '"' || c.is_control())
+        && !value.ends_with('\\')
+    {
+        format!("r\"{}\"", value)
+    } else {
+        quote_str(value)
+    };
+    node_ref!(Expr::StringLit(StringLit {
+        is_long_string: false,
+        raw_value,
+        value: value.to_string(),
+    }))
+}
+
+/// Quotes `value` as a double-quoted KCL string literal. The escapes follow
+/// the KCL lexer instead of the Rust `{:?}` format, e.g. `\x07` for `\u{7}`.
+fn quote_str(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            // All the control characters are in the Latin-1 range.
+            c if c.is_control() => quoted.push_str(&format!("\\x{:02x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn value_expr(value: &Value) -> NodeRef<Expr> {
+    ExprBuilder::new_with_str(LoaderKind::JSON, value.to_string())
+        .and_then(|builder| builder.build(None))
+        .unwrap_or_else(|_| {
+            node_ref!(Expr::NameConstantLit(NameConstantLit {
+                value: NameConstant::None,
+            }))
+        })
+}
@@ -0,0 +1,290 @@
+//! Infer the KCL schemas from sample JSON or YAML documents.
+//!
+//! Every object in the samples is an observation of a schema named by the
+//! root schema name and the attribute path, e.g. the `spec` object in the
+//! `Deployment` samples is an observation of `DeploymentSpec`. The types of the
+//! same attribute are unified with [kclvm_sema::ty::sup], and the attributes
+//! which are missing or `None` in some observations are optional.
+use super::import::{attr_name, docstring, identifier, pascal_case};
+use crate::util::loader::LoaderKind;
+use crate::vet::expr_builder::ExprBuilder;
+use anyhow::{anyhow, bail, Result};
+use indexmap::IndexMap;
+use kclvm_ast::ast::{
+    AssignStmt, Expr, ExprContext, Identifier, ListExpr, Module, Node, NodeRef, SchemaAttr,
+    SchemaStmt, Stmt,
+};
+use kclvm_ast::node_ref;
+use kclvm_ast_pretty::print_ast_module;
+use kclvm_sema::ty::{sup, Type};
+use serde::Deserialize;
+use serde_yaml::Value;
+use std::path::Path;
+use std::rc::Rc;
+
+/// InferOptions contains the options of the schema inference:
+/// - name: the root schema name, defaults to the first file name.
+#[derive(Debug, Default, Clone)]
+pub struct InferOptions {
+    pub name: String,
+}
+
+/// Infers the KCL schemas from the JSON or YAML sample files, and generates the
+/// KCL source which contains the schemas and the samples expressed with them.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kclvm_tools::jsonschema::{infer_files, InferOptions};
+///
+/// let code = infer_files(&["data.yaml"], &InferOptions::default()).unwrap();
+/// std::fs::write("data.k", code).unwrap();
+/// ```
+pub fn infer_files(files: &[&str], opts: &InferOptions) -> Result<String> {
+    let mut contents = vec![];
+    for file in files {
+        contents.push(
+            std::fs::read_to_string(file)
+                .map_err(|err| anyhow!("failed to read '{}': {}", file, err))?,
+        );
+    }
+    let mut opts = opts.clone();
+    if opts.name.is_empty() {
+        if let Some(stem) = files.first().and_then(|file| Path::new(file).file_stem()) {
+            opts.name = stem.to_string_lossy().to_string();
+        }
+    }
+    let contents: Vec<&str> = contents.iter().map(|c| c.as_str()).collect();
+    infer_str(&contents, &opts)
+}
+
+/// Infers the KCL schemas from the JSON or YAML sample strings.
+pub fn infer_str(contents: &[&str], opts: &InferOptions) -> Result<String> {
+    Ok(print_ast_module(&infer_module(contents, opts)?))
+}
+
+/// Infers the KCL module which contains the schema statements and the samples.
+pub fn infer_module(contents: &[&str], opts: &InferOptions) -> Result<Module> {
+    let mut samples = vec![];
+    // YAML is a superset of JSON, and a YAML stream may contain many samples.
+    for content in contents {
+        for document in serde_yaml::Deserializer::from_str(content) {
+            match Value::deserialize(document)? {
+                Value::Null => {}
+                // The items of the top level list are all samples.
+                Value::Sequence(items) => samples.extend(items),
+                sample => samples.push(sample),
+            }
+        }
+    }
+    if samples.is_empty() {
+        bail!("no sample data to infer the schema");
+    }
+    let name = pascal_case(if opts.name.is_empty() {
+        "Root"
+    } else {
+        &opts.name
+    });
+    let mut inferrer = Inferrer::default();
+    for sample in &samples {
+        match sample {
+            Value::Mapping(mapping) if is_schema_mapping(mapping) => {
+                inferrer.observe(&name, mapping)
+            }
+            _ => bail!(
+                "the sample data must be objects, got {}",
+                value_kind(sample)
+            ),
+        }
+    }
+
+    let mut body = inferrer.schema_stmts();
+    let mut instances = vec![];
+    for sample in &samples {
+        let content = serde_yaml::to_string(sample)?;
+        instances
+            .push(ExprBuilder::new_with_str(LoaderKind::YAML, content)?.build(Some(name.clone()))?);
+    }
+    let value = if instances.len() == 1 {
+        instances.remove(0)
+    } else {
+        node_ref!(Expr::List(ListExpr {
+            elts: instances,
+            ctx: ExprContext::Load,
+        }))
+    };
+    body.push(node_ref!(Stmt::Assign(AssignStmt {
+        targets: vec![node_ref!(Identifier {
+            ctx: ExprContext::Store,
+            ..identifier(&variable_name(&name))
+        })],
+        value,
+        type_annotation: None,
+        ty: None,
+    })));
+    Ok(Module {
+        filename: "".to_string(),
+        pkg: "".to_string(),
+        doc: "".to_string(),
+        name: "".to_string(),
+        body,
+        comments: vec![],
+    })
+}
+
+/// The observed attributes of a schema.
+#[derive(Default)]
+struct Shape {
+    /// The number of the observed objects.
+    count: usize,
+    attrs: IndexMap<String, Field>,
+}
+
+/// The observed types of a schema attribute.
+#[derive(Default)]
+struct Field {
+    /// The number of the objects which set the attribute to a value except `None`.
+    count: usize,
+    types: Vec<Rc<Type>>,
+}
+
+#[derive(Default)]
+struct Inferrer {
+    /// The shapes in the order of the first observation, the root schema is the first one.
+    shapes: IndexMap<String, Shape>,
+}
+
+impl Inferrer {
+    fn observe(&mut self, name: &str, mapping: &serde_yaml::Mapping) {
+        self.shapes.entry(name.to_string()).or_default().count += 1;
+        for (key, value) in mapping {
+            let key = key.as_str().unwrap_or_default();
+            let ty = self.infer(value, &format!("{}{}", name, pascal_case(key)));
+            let shape = self.shapes.get_mut(name).unwrap();
+            let field = shape.attrs.entry(key.to_string()).or_default();
+            if !value.is_null() {
+                field.count += 1;
+            }
+            field.types.push(ty);
+        }
+    }
+
+    /// Infer the type of the value, the objects are observed as the schema `name`.
+    fn infer(&mut self, value: &Value, name: &str) -> Rc<Type> {
+        match value {
+            Value::Null => Rc::new(Type::NONE),
+            Value::Bool(_) => Rc::new(Type::BOOL),
+            Value::Number(number) if number.is_i64() => Rc::new(Type::INT),
+            Value::Number(_) => Rc::new(Type::FLOAT),
+            Value::String(_) => Rc::new(Type::STR),
+            Value::Sequence(items) => {
+                let types: Vec<Rc<Type>> =
+                    items.iter().map(|item| self.infer(item, name)).collect();
+                Type::list_ref(sup(&types))
+            }
+            Value::Mapping(mapping) if is_schema_mapping(mapping) => {
+                self.observe(name, mapping);
+                Rc::new(Type::named(name))
+            }
+            Value::Mapping(mapping) => {
+                // The mappings with the keys which are not identifiers such as labels are dicts.
+                let types: Vec<Rc<Type>> = mapping
+                    .values()
+                    .map(|item| self.infer(item, &format!("{}Value", name)))
+                    .collect();
+                Type::dict_ref(Rc::new(Type::STR), sup(&types))
+            }
+            Value::Tagged(tagged) => self.infer(&tagged.value, name),
+        }
+    }
+
+    fn schema_stmts(&self) -> Vec<NodeRef<Stmt>> {
+        let mut stmts = vec![];
+        for (name, shape) in &self.shapes {
+            let mut body = vec![];
+            let mut attr_docs = vec![];
+            for (key, field) in &shape.attrs {
+                // The keys of the observed mappings are all identifiers.
+                let attr_name = attr_name(key).unwrap_or_else(|| key.to_string());
+                let ty = sup(&field.types);
+                // The attributes which are always `None` have no type information.
+                let type_str = if ty.is_none() {
+                    Type::ANY.ty_str()
+                } else {
+                    ty.ty_str()
+                };
+                let is_optional = field.count < shape.count;
+                attr_docs.push((
+                    format!(
+                        "{} : {}, {}",
+                        key,
+                        type_str,
+                        if is_optional { "optional" } else { "required" }
+                    ),
+                    "".to_string(),
+                ));
+                body.push(node_ref!(Stmt::SchemaAttr(SchemaAttr {
+                    doc: "".to_string(),
+                    name: node_ref!(attr_name),
+                    type_str: node_ref!(type_str),
+                    op: None,
+                    value: None,
+                    is_optional,
+                    decorators: vec![],
+                    ty: None,
+                })));
+            }
+            stmts.push(node_ref!(Stmt::Schema(SchemaStmt {
+                doc: docstring("", &attr_docs),
+                name: node_ref!(name.to_string()),
+                parent_name: None,
+                for_host_name: None,
+                is_mixin: false,
+                is_protocol: false,
+                args: None,
+                mixins: vec![],
+                body,
+                decorators: vec![],
+                checks: vec![],
+                index_signature: None,
+            })));
+        }
+        stmts
+    }
+}
+
+/// Whether the mapping is inferred as a schema, all its keys must be identifiers.
+fn is_schema_mapping(mapping: &serde_yaml::Mapping) -> bool {
+    !mapping.is_empty()
+        && mapping
+            .keys()
+            .all(|key| key.as_str().and_then(attr_name).is_some())
+}
+
+fn value_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Sequence(_) => "list",
+        Value::Mapping(_) => "dict",
+        Value::Tagged(_) => "tagged value",
+    }
+}
+
+/// Convert the schema name into a snake_case variable name, e.g. `PodSpec` to `pod_spec`.
+fn variable_name(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    attr_name(&result).unwrap_or(result)
+}
@@ -0,0 +1,151 @@
+//! [kclvm_tools::jsonschema] module exports the KCL schemas to JSON Schema
+//! (draft 2020-12) documents and OpenAPI v3 components, and imports JSON Schema
+//! documents, OpenAPI specifications and Kubernetes CRDs into the KCL schema source.
+//! The schemas can also be inferred from sample JSON or YAML data. The main API
+//! functions are `export_files`, `import_file` and `infer_files`.
+//!
+//! The exporter maps the resolved [kclvm_sema::ty::SchemaType] and [kclvm_sema::ty::TypeKind]
+//! to JSON Schema keywords:
+//! - literal types and unions of literal types become `enum`, other unions become `oneOf`.
+//! - index signatures become `additionalProperties`, schemas without index signatures
+//!   are closed with `additionalProperties: false`.
+//! - attributes without the optional marker and default values are listed in `required`.
+//! - simple check expressions such as ranges, `len` and `regex.match` become
+//!   `minimum`, `maxLength`, `pattern` and so on.
+use anyhow::{anyhow, Result};
+use kclvm_ast::ast;
+use kclvm_parser::LoadProgramOptions;
+use kclvm_sema::resolver::scope::ProgramScope;
+use serde_json::{json, Map, Value};
+
+mod check;
+mod export;
+mod import;
+mod infer;
+#[cfg(test)]
+mod tests;
+
+use crate::util::load_resolved_program;
+use export::Exporter;
+pub use import::{import_file, import_module, import_str, ImportFormat, ImportOptions};
+pub use infer::{infer_files, infer_module, infer_str, InferOptions};
+
+pub const JSON_SCHEMA_DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+pub const OPENAPI_VERSION: &str = "3.1.0";
+
+/// The format of the exported document.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// A JSON Schema document with the schemas in `$defs`.
+    #[default]
+    JsonSchema,
+    /// An OpenAPI document with the schemas in `components.schemas`.
+    OpenApi,
+}
+
+impl ExportFormat {
+    /// The reference prefix of the schema definitions.
+    pub fn ref_prefix(&self) -> &'static str {
+        match self {
+            ExportFormat::JsonSchema => "#/$defs/",
+            ExportFormat::OpenApi => "#/components/schemas/",
+        }
+    }
+}
+
+impl TryFrom<&str> for ExportFormat {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "jsonschema" | "json-schema" => Ok(ExportFormat::JsonSchema),
+            "openapi" => Ok(ExportFormat::OpenApi),
+            _ => Err(anyhow!("unsupported export format '{}'", s)),
+        }
+    }
+}
+
+/// ExportOptions contains the options of the schema export:
+/// - format: the format of the exported document.
+/// - schemas: the names of the main package schemas to export, all the main
+///   package schemas are exported when it is empty. The schemas referenced by
+///   the exported schemas are always exported.
+/// - title and version: the `info` of the OpenAPI document.
+/// - include_private: whether to export the private attributes whose names start with `_`.
+#[derive(Debug, Default, Clone)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    pub schemas: Vec<String>,
+    pub title: String,
+    pub version: String,
+    pub include_private: bool,
+}
+
+/// Loads the KCL program from `files` and exports its schemas.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kclvm_tools::jsonschema::{export_files, ExportFormat, ExportOptions};
+///
+/// let opts = ExportOptions {
+///     format: ExportFormat::OpenApi,
+///     ..Default::default()
+/// };
+/// let doc = export_files(&["main.k"], &opts, None).unwrap();
+/// println!("{}", serde_json::to_string_pretty(&doc).unwrap());
+/// ```
+pub fn export_files(
+    files: &[&str],
+    opts: &ExportOptions,
+    load_opts: Option<LoadProgramOptions>,
+) -> Result<Value> {
+    let (program, scope) = load_resolved_program(files, load_opts)?;
+    export_program(&program, &scope, opts)
+}
+
+/// Exports the schemas of a resolved KCL program.
+pub fn export_program(
+    program: &ast::Program,
+    scope: &ProgramScope,
+    opts: &ExportOptions,
+) -> Result<Value> {
+    let mut exporter = Exporter::new(program, scope, opts);
+    let roots = exporter.export()?;
+    let defs = Value::Object(exporter.defs);
+    let mut doc = Map::new();
+    match opts.format {
+        ExportFormat::JsonSchema => {
+            doc.insert("$schema".to_string(), json!(JSON_SCHEMA_DRAFT));
+            // Validate the document against the schema when only one schema is selected.
+            if opts.schemas.len() == 1 {
+                if let Some(root) = roots.first() {
+                    doc.insert(
+                        "$ref".to_string(),
+                        json!(format!("{}{}", opts.format.ref_prefix(), root)),
+                    );
+                }
+            }
+            doc.insert("$defs".to_string(), defs);
+        }
+        ExportFormat::OpenApi => {
+            let title = if opts.title.is_empty() {
+                "KCL schemas"
+            } else {
+                &opts.title
+            };
+            let version = if opts.version.is_empty() {
+                "0.0.0"
+            } else {
+                &opts.version
+            };
+            doc.insert("openapi".to_string(), json!(OPENAPI_VERSION));
+            doc.insert(
+                "info".to_string(),
+                json!({"title": title, "version": version}),
+            );
+            doc.insert("components".to_string(), json!({ "schemas": defs }));
+        }
+    }
+    Ok(Value::Object(doc))
+}
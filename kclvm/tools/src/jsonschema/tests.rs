@@ -0,0 +1,299 @@
+use std::path::PathBuf;
+
+use super::*;
+use serde_json::json;
+
+const CARGO_DIR: &str = env!("CARGO_MANIFEST_DIR");
+const REL_PATH: &str = "src/jsonschema/test_data";
+
+fn test_data_path(name: &str) -> String {
+    let mut path = PathBuf::from(CARGO_DIR);
+    path.push(REL_PATH);
+    path.push(name);
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_export_json_schema() {
+    let doc = export_files(
+        &[&test_data_path("main.k")],
+        &ExportOptions::default(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(doc["$schema"], json!(JSON_SCHEMA_DRAFT));
+    assert!(doc.get("$ref").is_none());
+    let defs = doc["$defs"].as_object().unwrap();
+    let names: Vec<&String> = defs.keys().collect();
+    assert_eq!(names, vec!["Port", "Resource", "Service", "base.Metadata"]);
+
+    assert_eq!(
+        defs["Resource"],
+        json!({
+            "type": "object",
+            "title": "Resource",
+            "description": "Resource is the base schema of all the resources.",
+            "properties": {
+                "metadata": {
+                    "$ref": "#/$defs/base.Metadata",
+                    "description": "The metadata of the resource."
+                }
+            },
+            "required": ["metadata"],
+            "additionalProperties": false
+        })
+    );
+    let service = &defs["Service"];
+    assert_eq!(service["required"], json!(["metadata", "ports"]));
+    let props = &service["properties"];
+    assert_eq!(props["metadata"]["$ref"], json!("#/$defs/base.Metadata"));
+    assert_eq!(
+        props["kind"],
+        json!({"type": "string", "enum": ["Service"]})
+    );
+    assert_eq!(
+        props["type"],
+        json!({"type": "string", "enum": ["ClusterIP", "NodePort"]})
+    );
+    assert_eq!(
+        props["ports"],
+        json!({"type": "array", "items": {"$ref": "#/$defs/Port"}, "minItems": 1})
+    );
+    assert_eq!(
+        props["selector"],
+        json!({"type": "object", "additionalProperties": {"type": "string"}})
+    );
+    assert_eq!(
+        props["replicas"],
+        json!({"type": "integer", "minimum": 1, "exclusiveMaximum": 100})
+    );
+    assert_eq!(
+        props["timeout"],
+        json!({"oneOf": [{"type": "integer"}, {"type": "string"}]})
+    );
+    assert!(props.get("_private").is_none());
+
+    let port = &defs["Port"];
+    assert_eq!(port["required"], json!(["port"]));
+    assert_eq!(port["additionalProperties"], json!({"type": "string"}));
+    assert_eq!(
+        port["properties"]["port"],
+        json!({"type": "integer", "minimum": 1, "maximum": 65535})
+    );
+    assert_eq!(
+        port["properties"]["protocol"],
+        json!({"type": "string", "enum": ["TCP", "UDP"]})
+    );
+
+    let metadata = &defs["base.Metadata"];
+    assert_eq!(
+        metadata["properties"]["name"],
+        json!({
+            "type": "string",
+            "description": "The name of the resource.",
+            "maxLength": 63,
+            "pattern": "^[a-z0-9]([-a-z0-9]*[a-z0-9])?$"
+        })
+    );
+}
+
+#[test]
+fn test_export_openapi() {
+    let opts = ExportOptions {
+        format: ExportFormat::OpenApi,
+        schemas: vec!["Service".to_string()],
+        title: "services".to_string(),
+        version: "1.0.0".to_string(),
+        ..Default::default()
+    };
+    let doc = export_files(&[&test_data_path("main.k")], &opts, None).unwrap();
+    assert_eq!(doc["openapi"], json!(OPENAPI_VERSION));
+    assert_eq!(
+        doc["info"],
+        json!({"title": "services", "version": "1.0.0"})
+    );
+    let schemas = doc["components"]["schemas"].as_object().unwrap();
+    let names: Vec<&String> = schemas.keys().collect();
+    assert_eq!(names, vec!["Port", "Service", "base.Metadata"]);
+    assert_eq!(
+        schemas["Service"]["properties"]["metadata"]["$ref"],
+        json!("#/components/schemas/base.Metadata")
+    );
+}
+
+#[test]
+fn test_export_selected_schema() {
+    let opts = ExportOptions {
+        schemas: vec!["Port".to_string()],
+        ..Default::default()
+    };
+    let doc = export_files(&[&test_data_path("main.k")], &opts, None).unwrap();
+    assert_eq!(doc["$ref"], json!("#/$defs/Port"));
+    assert_eq!(doc["$defs"].as_object().unwrap().len(), 1);
+
+    let opts = ExportOptions {
+        schemas: vec!["NotFound".to_string()],
+        ..Default::default()
+    };
+    let err = export_files(&[&test_data_path("main.k")], &opts, None).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "schema 'NotFound' not found in the main package"
+    );
+}
+
+#[test]
+fn test_import_json_schema() {
+    let code = import_file(
+        &test_data_path("import/person.json"),
+        &ImportOptions::default(),
+    )
+    .unwrap();
+    kclvm_parser::parse_file("person.k", Some(code.clone())).unwrap();
+    assert!(code.starts_with("import regex\n"));
+    for expected in [
+        "schema Person:\n    \"\"\"\n    A person in the address book.\n",
+        "    name : str, required\n        The full name.\n",
+        "    gender?: \"female\" | \"male\"\n",
+        "    labels?: {str:str}\n",
+        "    pets?: [Pet]\n",
+        "        0 <= age <= 150\n",
+        "        1 <= len(name)\n",
+        "        regex.match(name, \"^[A-Z]\")\n",
+        "        isunique(tags) if tags is not None\n",
+        "schema PersonAddress:",
+        "    city?: str = \"Hangzhou\"\n",
+        // `x-id` is not an identifier and `type` is a keyword.
+        "    [...str]: any\n    $type: str\n",
+    ] {
+        assert!(code.contains(expected), "{expected} not in {code}");
+    }
+    // The nested schemas are placed after the parent schemas.
+    assert!(code.find("schema Person:").unwrap() < code.find("schema PersonAddress:").unwrap());
+}
+
+#[test]
+fn test_import_string_escapes() {
+    let code = import_str(
+        r#"{
+            "title": "Text",
+            "type": "object",
+            "properties": {
+                "mark": {"enum": ["\"", "a\u0007\nb", "中文"]},
+                "word": {"type": "string", "pattern": "^\\w+${0}$"}
+            }
+        }"#,
+        &ImportOptions::default(),
+    )
+    .unwrap();
+    kclvm_parser::parse_file("text.k", Some(code.clone())).unwrap();
+    for expected in [
+        r#"    mark?: "\"" | "a\x07\nb" | "中文""#,
+        r#"        regex.match(word, r"^\w+${0}$")"#,
+    ] {
+        assert!(code.contains(expected), "{expected} not in {code}");
+    }
+}
+
+#[test]
+fn test_import_openapi() {
+    let code = import_file(
+        &test_data_path("import/openapi.yaml"),
+        &ImportOptions {
+            format: ImportFormat::OpenApi,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        code,
+        r#"schema Dog(Pet):
+    """
+    Attributes
+    ----------
+    bark : bool, optional
+    """
+    bark?: bool
+
+schema Pet:
+    """
+    Attributes
+    ----------
+    name : str, required
+    status : "available" | "sold", default is "available", optional
+    """
+    name: str
+    status?: "available" | "sold" = "available"
+
+"#
+    );
+}
+
+#[test]
+fn test_import_crd() {
+    let code = import_file(
+        &test_data_path("import/crd.yaml"),
+        &ImportOptions {
+            format: ImportFormat::Crd,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    kclvm_parser::parse_file("crd.k", Some(code.clone())).unwrap();
+    for expected in [
+        "schema CronTab:",
+        "    apiVersion: \"stable.example.com/v1\" = \"stable.example.com/v1\"\n",
+        "    kind: \"CronTab\" = \"CronTab\"\n",
+        "    spec?: CronTabSpec\n",
+        "schema CronTabSpec:",
+        "    port?: int | str\n",
+        "        1 <= replicas <= 10 if replicas is not None\n",
+    ] {
+        assert!(code.contains(expected), "{expected} not in {code}");
+    }
+    assert!(import_str(
+        "kind: ConfigMap",
+        &ImportOptions {
+            format: ImportFormat::Crd,
+            ..Default::default()
+        }
+    )
+    .is_err());
+}
+
+#[test]
+fn test_infer_schema() {
+    let code = infer_files(
+        &[&test_data_path("infer/deployment.yaml")],
+        &InferOptions::default(),
+    )
+    .unwrap();
+    kclvm_parser::parse_file("deployment.k", Some(code.clone())).unwrap();
+    for expected in [
+        "schema Deployment:",
+        "    metadata: DeploymentMetadata\n    spec: DeploymentSpec\n",
+        // The types of the samples are unified.
+        "    revision: int|str\n",
+        "    replicas: float\n",
+        // The keys of labels are not identifiers.
+        "    labels?: {str:str}\n",
+        "    containers: [DeploymentSpecContainers]\n",
+        // The attributes missing or None in some samples are optional.
+        "    paused?: any\n",
+        "    ports?: [DeploymentSpecContainersPorts]\n",
+        "    command?: [str]\n",
+        "schema DeploymentSpecContainersPorts:",
+        "deployment = [Deployment {",
+    ] {
+        assert!(code.contains(expected), "{expected} not in {code}");
+    }
+    let code = infer_str(
+        &["{\"name\": \"alice\"}"],
+        &InferOptions {
+            name: "person".to_string(),
+        },
+    )
+    .unwrap();
+    assert!(code.contains("\nperson = Person {\"name\": \"alice\"}\n"));
+    assert!(infer_str(&["1"], &InferOptions::default()).is_err());
+}
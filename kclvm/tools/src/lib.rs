@@ -1,7 +1,12 @@
+pub mod affected;
+pub mod doc;
 pub mod format;
+pub mod jsonschema;
 pub mod lint;
+pub mod testing;
 pub(crate) mod util;
 pub mod vet;
+pub mod watch;
 
 #[macro_use]
 extern crate kclvm_error;
@@ -1,7 +1,7 @@
 use indexmap::IndexSet;
 use kclvm_error::{Diagnostic, Level};
 use kclvm_parser::{load_program, LoadProgramOptions};
-use kclvm_sema::resolver::resolve_program;
+use kclvm_sema::resolver::session::ResolverSession;
 #[cfg(test)]
 mod tests;
 
@@ -56,10 +56,20 @@ mod tests;
 pub fn lint_files(
     files: &[&str],
     opts: Option<LoadProgramOptions>,
+) -> (IndexSet<Diagnostic>, IndexSet<Diagnostic>) {
+    lint_files_with_session(files, opts, &mut ResolverSession::default())
+}
+
+/// Same as [lint_files], and the packages not changed since the last lint in the
+/// resolver session are not checked again, e.g. in `kclvm_cli lint --watch`.
+pub fn lint_files_with_session(
+    files: &[&str],
+    opts: Option<LoadProgramOptions>,
+    session: &mut ResolverSession,
 ) -> (IndexSet<Diagnostic>, IndexSet<Diagnostic>) {
     // Parse AST program.
     let mut program = load_program(&files, opts).unwrap();
-    let scope = resolve_program(&mut program);
+    let scope = session.resolve(&mut program);
     let (mut errs, mut warnings) = (IndexSet::new(), IndexSet::new());
     for diag in &scope.diagnostics {
         if diag.level == Level::Error {
@@ -1,4 +1,5 @@
-use super::lint_files;
+use super::{lint_files, lint_files_with_session};
+use kclvm_sema::resolver::session::ResolverSession;
 
 #[test]
 fn test_lint() {
@@ -12,3 +13,16 @@ fn test_lint() {
         assert_eq!(diag.messages[0].message, m.to_string());
     }
 }
+
+#[test]
+fn test_lint_with_session() {
+    let mut session = ResolverSession::default();
+    let files = vec!["./src/lint/test_data/lint.k"];
+    let (errs, warnings) = lint_files_with_session(&files, None, &mut session);
+    assert_eq!(
+        lint_files_with_session(&files, None, &mut session),
+        (errs, warnings.clone())
+    );
+    assert_eq!(session.checked_pkgpaths(), &["__main__".to_string()]);
+    assert_eq!(lint_files(&files, None).1, warnings);
+}
@@ -0,0 +1,57 @@
+//! Render the test result as the JUnit XML report which is accepted by most CI systems.
+use super::TestResult;
+
+pub(crate) fn render(result: &TestResult) -> String {
+    let tests: usize = result.suites.iter().map(|suite| suite.cases.len()).sum();
+    let failures: usize = result.suites.iter().map(|suite| suite.failures()).sum();
+    let time: f64 = result
+        .suites
+        .iter()
+        .map(|suite| suite.duration.as_secs_f64())
+        .sum();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+        tests, failures, time
+    ));
+    for suite in &result.suites {
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+            escape(&suite.pkg_dir),
+            suite.cases.len(),
+            suite.failures(),
+            suite.duration.as_secs_f64()
+        ));
+        for case in &suite.cases {
+            let attrs = format!(
+                "name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                escape(&case.case.name),
+                escape(&case.case.file),
+                case.duration.as_secs_f64()
+            );
+            match &case.error {
+                Some(error) => {
+                    xml.push_str(&format!("    <testcase {}>\n", attrs));
+                    xml.push_str(&format!(
+                        "      <failure message=\"{}\">{}</failure>\n",
+                        escape(&error.message),
+                        escape(&error.to_string())
+                    ));
+                    xml.push_str("    </testcase>\n");
+                }
+                None => xml.push_str(&format!("    <testcase {}/>\n", attrs)),
+            }
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
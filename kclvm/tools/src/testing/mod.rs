@@ -0,0 +1,446 @@
+//! [kclvm_tools::testing] module discovers and runs the KCL tests, the main API
+//! function is `run_tests`.
+//!
+//! A test file is a KCL file named `*_test.k` placed in the directory of the
+//! package under test, and every top level lambda named `test_*` in it is a
+//! test case. e.g.
+//!
+//! ```kcl
+//! # math_test.k
+//! test_add = lambda {
+//!     assert add(1, 2) == 3
+//! }
+//! ```
+//!
+//! Each test case is compiled with the package files and the test file, and
+//! executed alone by the runner with a call of the test lambda appended, so
+//! the test cases never share their states. A test case fails when the execution
+//! panics, and the failure position comes from the runtime error diagnostics.
+//!
+//! The YAML output of a KCL program can also be checked against a golden file
+//! with `check_snapshot`.
+use anyhow::{anyhow, Result};
+use fancy_regex::Regex;
+use kclvm_ast::ast::{
+    CallExpr, Expr, ExprContext, ExprStmt, Identifier, Module, NameConstant, NameConstantLit, Node,
+    Program, Stmt,
+};
+use kclvm_ast::MAIN_PKG;
+use kclvm_config::modfile::KCL_FILE_SUFFIX;
+use kclvm_error::{DiagnosticId, ErrorKind, KclvmError, Level};
+use kclvm_parser::load_program;
+use kclvm_runner::{execute, ExecProgramArgs};
+use kclvm_sema::resolver::resolve_program;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+mod junit;
+mod snapshot;
+#[cfg(test)]
+mod tests;
+
+pub use snapshot::{check_snapshot, diff_yaml, DiffEntry, DiffKind, SnapshotResult};
+
+pub const TEST_FILE_SUFFIX: &str = "_test.k";
+pub const TEST_FUNC_PREFIX: &str = "test_";
+
+/// TestOptions contains the options of the test runner:
+/// - run: the regex which selects the test cases by names.
+/// - fail_fast: stop running the remaining tests after the first failure.
+/// - exec_args: the arguments to compile and execute the tests such as options and settings.
+#[derive(Debug, Default, Clone)]
+pub struct TestOptions {
+    pub run: Option<String>,
+    pub fail_fast: bool,
+    pub exec_args: ExecProgramArgs,
+}
+
+/// A package under test, which is a directory containing the test files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestSuite {
+    pub pkg_dir: String,
+    /// The KCL files of the package except the test files.
+    pub pkg_files: Vec<String>,
+    pub test_files: Vec<String>,
+}
+
+/// A test lambda defined in the test file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    pub name: String,
+    pub file: String,
+    pub line: u64,
+}
+
+/// The failure of a test case with the KCL position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestError {
+    pub message: String,
+    pub file: String,
+    pub line: i32,
+    pub column: i32,
+}
+
+impl std::fmt::Display for TestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.file.is_empty() {
+            write!(f, "{}", self.message)
+        } else if self.column > 0 {
+            write!(
+                f,
+                "{}:{}:{}: {}",
+                self.file, self.line, self.column, self.message
+            )
+        } else {
+            write!(f, "{}:{}: {}", self.file, self.line, self.message)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TestCaseResult {
+    pub case: TestCase,
+    pub duration: Duration,
+    pub error: Option<TestError>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TestSuiteResult {
+    pub pkg_dir: String,
+    pub cases: Vec<TestCaseResult>,
+    pub duration: Duration,
+}
+
+impl TestSuiteResult {
+    /// Returns the number of the failed test cases.
+    pub fn failures(&self) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| case.error.is_some())
+            .count()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TestResult {
+    pub suites: Vec<TestSuiteResult>,
+}
+
+impl TestResult {
+    /// Whether all the test cases are passed.
+    pub fn is_ok(&self) -> bool {
+        self.suites.iter().all(|suite| suite.failures() == 0)
+    }
+
+    /// Returns the JUnit XML report of the test result.
+    pub fn to_junit_xml(&self) -> String {
+        junit::render(self)
+    }
+}
+
+/// Discovers the test suites under the path recursively, the path can also be
+/// a test file and the suite contains only this test file.
+pub fn discover_test_suites(path: &str) -> Result<Vec<TestSuite>> {
+    let path = Path::new(path);
+    if !path.exists() {
+        return Err(anyhow!("'{}' not found", path.display()));
+    }
+    let (dir, only) = if path.is_file() {
+        (parent_dir(path), Some(path))
+    } else {
+        (path, None)
+    };
+    let files = if only.is_some() {
+        crate::util::get_kcl_files(dir, false)?
+    } else {
+        crate::util::get_kcl_files(dir, true)?
+    };
+    // Group the files by the package directories.
+    let mut dirs: BTreeMap<String, (Vec<String>, Vec<String>)> = BTreeMap::new();
+    for file in files {
+        let file_dir = parent_dir(Path::new(&file)).to_string_lossy().to_string();
+        let (pkg_files, test_files) = dirs.entry(file_dir).or_default();
+        if file.ends_with(TEST_FILE_SUFFIX) {
+            // The files are listed in the directory of the only test file.
+            if only.is_none()
+                || only.and_then(|only| only.file_name()) == Path::new(&file).file_name()
+            {
+                test_files.push(file);
+            }
+        } else if file.ends_with(KCL_FILE_SUFFIX) {
+            pkg_files.push(file);
+        }
+    }
+    Ok(dirs
+        .into_iter()
+        .filter(|(_, (_, test_files))| !test_files.is_empty())
+        .map(|(pkg_dir, (mut pkg_files, mut test_files))| {
+            pkg_files.sort();
+            test_files.sort();
+            TestSuite {
+                pkg_dir,
+                pkg_files,
+                test_files,
+            }
+        })
+        .collect())
+}
+
+/// Returns the directory of the file, the parent of a file name such as `foo_test.k`
+/// is empty and the current directory is returned.
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+/// Returns the test cases defined in the test file.
+pub fn load_test_cases(file: &str) -> Result<Vec<TestCase>> {
+    let module = kclvm_parser::parse_file(file, None).map_err(|err| anyhow!(err))?;
+    let mut cases = vec![];
+    for stmt in &module.body {
+        if let Stmt::Assign(assign_stmt) = &stmt.node {
+            if !matches!(assign_stmt.value.node, Expr::Lambda(_)) {
+                continue;
+            }
+            for target in &assign_stmt.targets {
+                let name = target.node.get_name();
+                if target.node.names.len() == 1 && name.starts_with(TEST_FUNC_PREFIX) {
+                    cases.push(TestCase {
+                        name,
+                        file: file.to_string(),
+                        line: stmt.line,
+                    });
+                }
+            }
+        }
+    }
+    Ok(cases)
+}
+
+/// Runs the test suites under the path, `on_case` is called once a test case is finished.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kclvm_tools::testing::{run_tests, TestOptions};
+///
+/// let result = run_tests("./pkg", &TestOptions::default(), |case| {
+///     println!("{}: {}", case.case.name, case.error.is_none());
+/// })
+/// .unwrap();
+/// std::fs::write("report.xml", result.to_junit_xml()).unwrap();
+/// ```
+pub fn run_tests<F>(path: &str, opts: &TestOptions, mut on_case: F) -> Result<TestResult>
+where
+    F: FnMut(&TestCaseResult),
+{
+    let run = match &opts.run {
+        Some(run) => {
+            Some(Regex::new(run).map_err(|err| anyhow!("invalid test name regex: {}", err))?)
+        }
+        None => None,
+    };
+    let mut result = TestResult::default();
+    for suite in discover_test_suites(path)? {
+        let start = Instant::now();
+        let mut suite_result = TestSuiteResult {
+            pkg_dir: suite.pkg_dir.clone(),
+            ..Default::default()
+        };
+        let mut stop = false;
+        for test_file in &suite.test_files {
+            let mut cases = vec![];
+            for case in load_test_cases(test_file)? {
+                let selected = match &run {
+                    Some(run) => run.is_match(&case.name).unwrap_or_default(),
+                    None => true,
+                };
+                if selected {
+                    cases.push(case);
+                }
+            }
+            if cases.is_empty() {
+                continue;
+            }
+            let mut files: Vec<&str> = suite.pkg_files.iter().map(|f| f.as_str()).collect();
+            files.push(test_file);
+            let program = load_test_program(&files, &opts.exec_args);
+            for case in cases {
+                let case_start = Instant::now();
+                let error = match &program {
+                    Ok(program) => run_test_case(program.clone(), &case, &opts.exec_args).err(),
+                    Err(err) => Some(err.clone()),
+                };
+                let case_result = TestCaseResult {
+                    case,
+                    duration: case_start.elapsed(),
+                    error,
+                };
+                on_case(&case_result);
+                stop = opts.fail_fast && case_result.error.is_some();
+                suite_result.cases.push(case_result);
+                if stop {
+                    break;
+                }
+            }
+            if stop {
+                break;
+            }
+        }
+        suite_result.duration = start.elapsed();
+        if !suite_result.cases.is_empty() {
+            result.suites.push(suite_result);
+        }
+        if stop {
+            break;
+        }
+    }
+    Ok(result)
+}
+
+/// Load and resolve the test program, the compile errors fail all the test cases.
+fn load_test_program(files: &[&str], args: &ExecProgramArgs) -> Result<Program, TestError> {
+    let mut program = load_program(files, Some(args.get_load_program_options()))
+        .map_err(|err| test_error(&err))?;
+    if let Some(module) = program
+        .pkgs
+        .get_mut(MAIN_PKG)
+        .and_then(|modules| modules.last_mut())
+    {
+        complete_test_lambdas(module);
+    }
+    let scope = resolve_program(&mut program.clone());
+    if let Some(diag) = scope
+        .diagnostics
+        .iter()
+        .find(|diag| diag.level == Level::Error)
+    {
+        return Err(match diag.messages.first() {
+            Some(msg) => TestError {
+                message: msg.message.clone(),
+                file: msg.pos.filename.clone(),
+                line: msg.pos.line as i32,
+                column: msg.pos.column.unwrap_or_default() as i32,
+            },
+            None => TestError {
+                message: "failed to resolve the program".to_string(),
+                file: "".to_string(),
+                line: 0,
+                column: 0,
+            },
+        });
+    }
+    Ok(program)
+}
+
+/// The last statement of a lambda body must be an expression, and the test lambdas
+/// which end with other statements such as `assert` are completed with `None`.
+fn complete_test_lambdas(module: &mut Module) {
+    for stmt in &mut module.body {
+        if let Stmt::Assign(assign_stmt) = &mut stmt.node {
+            let is_test = assign_stmt
+                .targets
+                .iter()
+                .any(|target| target.node.get_name().starts_with(TEST_FUNC_PREFIX));
+            let lambda_pos = assign_stmt.value.pos();
+            if let (true, Expr::Lambda(lambda_expr)) = (is_test, &mut assign_stmt.value.node) {
+                let pos = match lambda_expr.body.last() {
+                    Some(last) if matches!(last.node, Stmt::Expr(_)) => continue,
+                    Some(last) => last.pos(),
+                    None => lambda_pos,
+                };
+                let none = Node::node_with_pos(
+                    Expr::NameConstantLit(NameConstantLit {
+                        value: NameConstant::None,
+                    }),
+                    pos.clone(),
+                );
+                lambda_expr.body.push(Box::new(Node::node_with_pos(
+                    Stmt::Expr(ExprStmt {
+                        exprs: vec![Box::new(none)],
+                    }),
+                    pos,
+                )));
+            }
+        }
+    }
+}
+
+/// Executes the test case with a call of the test lambda appended to the test file.
+fn run_test_case(
+    mut program: Program,
+    case: &TestCase,
+    args: &ExecProgramArgs,
+) -> Result<(), TestError> {
+    // The test file is the last file of the main package.
+    let module = program
+        .pkgs
+        .get_mut(MAIN_PKG)
+        .and_then(|modules| modules.last_mut())
+        .ok_or_else(|| TestError {
+            message: format!("test file {} not loaded", case.file),
+            file: case.file.clone(),
+            line: 0,
+            column: 0,
+        })?;
+    let pos = (case.file.clone(), case.line, 1, case.line, 1);
+    let func = Node::node_with_pos(
+        Expr::Identifier(Identifier {
+            names: vec![case.name.clone()],
+            pkgpath: "".to_string(),
+            ctx: ExprContext::Load,
+        }),
+        pos.clone(),
+    );
+    let call = Node::node_with_pos(
+        Expr::Call(CallExpr {
+            func: Box::new(func),
+            args: vec![],
+            keywords: vec![],
+        }),
+        pos.clone(),
+    );
+    module.body.push(Box::new(Node::node_with_pos(
+        Stmt::Expr(ExprStmt {
+            exprs: vec![Box::new(call)],
+        }),
+        pos,
+    )));
+    match execute(program, 0, args) {
+        Ok(_) => Ok(()),
+        Err(err) => Err(test_error(&err)),
+    }
+}
+
+/// Convert the load or runner error into the test error.
+fn test_error(err: &KclvmError) -> TestError {
+    let diag = err.diagnostics().first();
+    let message = err.message();
+    let message = if !message.is_empty() {
+        message
+    } else if matches!(
+        diag.and_then(|diag| diag.code.as_ref()),
+        Some(DiagnosticId::Error(ErrorKind::AssertionError))
+    ) {
+        "assertion failed".to_string()
+    } else {
+        "runtime error".to_string()
+    };
+    match diag.and_then(|diag| diag.messages.first()) {
+        Some(msg) => TestError {
+            message,
+            file: msg.pos.filename.clone(),
+            line: msg.pos.line as i32,
+            column: msg.pos.column.unwrap_or_default() as i32,
+        },
+        None => TestError {
+            message,
+            file: "".to_string(),
+            line: 0,
+            column: 0,
+        },
+    }
+}
@@ -0,0 +1,219 @@
+//! Golden file snapshots of the KCL program YAML output.
+//!
+//! The actual YAML stream is compared with the stored snapshot structurally,
+//! so the differences are reported by the key paths such as `spec.replicas`
+//! and `containers[0].image` instead of the text lines.
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_yaml::Value;
+use std::path::Path;
+
+/// The kind of a snapshot difference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// The value only exists in the actual output.
+    Added,
+    /// The value only exists in the snapshot.
+    Removed,
+    Changed,
+}
+
+/// A difference between the snapshot and the actual output at the key path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffEntry {
+    pub path: String,
+    pub kind: DiffKind,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+impl std::fmt::Display for DiffEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let expected = self.expected.as_deref().unwrap_or_default();
+        let actual = self.actual.as_deref().unwrap_or_default();
+        match self.kind {
+            DiffKind::Added => write!(f, "+ {}: {}", self.path, actual),
+            DiffKind::Removed => write!(f, "- {}: {}", self.path, expected),
+            DiffKind::Changed => write!(f, "~ {}: {} -> {}", self.path, expected, actual),
+        }
+    }
+}
+
+/// The result of a snapshot check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotResult {
+    Matched,
+    /// The snapshot is written with the actual output.
+    Updated,
+    Mismatched(Vec<DiffEntry>),
+}
+
+/// Compares the actual YAML output with the snapshot file, and rewrites the
+/// snapshot with the actual output when `update` is true.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kclvm_tools::testing::{check_snapshot, SnapshotResult};
+///
+/// let actual = "name: kcl\n";
+/// if let SnapshotResult::Mismatched(diffs) = check_snapshot("expected.yaml", actual, false).unwrap() {
+///     for diff in diffs {
+///         println!("{}", diff);
+///     }
+/// }
+/// ```
+pub fn check_snapshot(path: &str, actual: &str, update: bool) -> Result<SnapshotResult> {
+    let exists = Path::new(path).exists();
+    if !exists && !update {
+        return Err(anyhow!(
+            "snapshot '{}' not found, run with --update to create it",
+            path
+        ));
+    }
+    let diffs = if !exists {
+        vec![]
+    } else {
+        let expected = std::fs::read_to_string(path)
+            .map_err(|err| anyhow!("failed to read snapshot '{}': {}", path, err))?;
+        if expected == actual {
+            return Ok(SnapshotResult::Matched);
+        }
+        diff_yaml(&expected, actual)?
+    };
+    if update {
+        std::fs::write(path, actual)
+            .map_err(|err| anyhow!("failed to write snapshot '{}': {}", path, err))?;
+        Ok(SnapshotResult::Updated)
+    } else if diffs.is_empty() {
+        // The formats are different but the values are the same.
+        Ok(SnapshotResult::Matched)
+    } else {
+        Ok(SnapshotResult::Mismatched(diffs))
+    }
+}
+
+/// Returns the structural differences between two YAML streams.
+pub fn diff_yaml(expected: &str, actual: &str) -> Result<Vec<DiffEntry>> {
+    let expected = load_documents(expected)?;
+    let actual = load_documents(actual)?;
+    let mut diffs = vec![];
+    if expected.len() == 1 && actual.len() == 1 {
+        diff_value(&expected[0], &actual[0], "", &mut diffs);
+    } else {
+        for i in 0..expected.len().max(actual.len()) {
+            let path = format!("documents[{}]", i);
+            match (expected.get(i), actual.get(i)) {
+                (Some(e), Some(a)) => diff_value(e, a, &path, &mut diffs),
+                (Some(e), None) => diffs.push(removed(&path, e)),
+                (None, Some(a)) => diffs.push(added(&path, a)),
+                (None, None) => {}
+            }
+        }
+    }
+    Ok(diffs)
+}
+
+fn load_documents(content: &str) -> Result<Vec<Value>> {
+    let mut documents = vec![];
+    for document in serde_yaml::Deserializer::from_str(content) {
+        documents.push(Value::deserialize(document)?);
+    }
+    // An empty stream is one null document.
+    if documents.is_empty() {
+        documents.push(Value::Null);
+    }
+    Ok(documents)
+}
+
+fn diff_value(expected: &Value, actual: &Value, path: &str, diffs: &mut Vec<DiffEntry>) {
+    match (expected, actual) {
+        (Value::Mapping(e), Value::Mapping(a)) => {
+            for (key, e_value) in e {
+                let key_path = join_key(path, key);
+                match a.get(key) {
+                    Some(a_value) => diff_value(e_value, a_value, &key_path, diffs),
+                    None => diffs.push(removed(&key_path, e_value)),
+                }
+            }
+            for (key, a_value) in a {
+                if !e.contains_key(key) {
+                    diffs.push(added(&join_key(path, key), a_value));
+                }
+            }
+        }
+        (Value::Sequence(e), Value::Sequence(a)) => {
+            for i in 0..e.len().max(a.len()) {
+                let index_path = format!("{}[{}]", path, i);
+                match (e.get(i), a.get(i)) {
+                    (Some(e), Some(a)) => diff_value(e, a, &index_path, diffs),
+                    (Some(e), None) => diffs.push(removed(&index_path, e)),
+                    (None, Some(a)) => diffs.push(added(&index_path, a)),
+                    (None, None) => {}
+                }
+            }
+        }
+        (e, a) if e != a => diffs.push(DiffEntry {
+            path: root_path(path),
+            kind: DiffKind::Changed,
+            expected: Some(render(e)),
+            actual: Some(render(a)),
+        }),
+        _ => {}
+    }
+}
+
+fn added(path: &str, value: &Value) -> DiffEntry {
+    DiffEntry {
+        path: root_path(path),
+        kind: DiffKind::Added,
+        expected: None,
+        actual: Some(render(value)),
+    }
+}
+
+fn removed(path: &str, value: &Value) -> DiffEntry {
+    DiffEntry {
+        path: root_path(path),
+        kind: DiffKind::Removed,
+        expected: Some(render(value)),
+        actual: None,
+    }
+}
+
+fn root_path(path: &str) -> String {
+    if path.is_empty() {
+        "<root>".to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+/// Join the key to the path, the keys which are not identifiers are quoted, e.g. `labels["app.kubernetes.io/name"]`.
+fn join_key(path: &str, key: &Value) -> String {
+    let key = match key {
+        Value::String(key) => key.clone(),
+        key => render(key),
+    };
+    let is_identifier = matches!(key.chars().next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !is_identifier {
+        format!("{}[{:?}]", path, key)
+    } else if path.is_empty() {
+        key
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Render the value in one line.
+fn render(value: &Value) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| {
+        serde_yaml::to_string(value)
+            .unwrap_or_default()
+            .trim_end()
+            .replace('\n', " ")
+    })
+}
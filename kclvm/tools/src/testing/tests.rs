@@ -0,0 +1,217 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::*;
+
+const CARGO_DIR: &str = env!("CARGO_MANIFEST_DIR");
+const REL_PATH: &str = "src/testing/test_data";
+
+fn test_data_path(name: &str) -> String {
+    let mut path = PathBuf::from(CARGO_DIR);
+    path.push(REL_PATH);
+    path.push(name);
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_discover_test_suites() {
+    let suites = discover_test_suites(&test_data_path("pkg")).unwrap();
+    assert_eq!(
+        suites,
+        vec![
+            TestSuite {
+                pkg_dir: test_data_path("pkg"),
+                pkg_files: vec![test_data_path("pkg/math.k")],
+                test_files: vec![test_data_path("pkg/math_test.k")],
+            },
+            TestSuite {
+                pkg_dir: test_data_path("pkg/sub"),
+                pkg_files: vec![test_data_path("pkg/sub/sub.k")],
+                test_files: vec![test_data_path("pkg/sub/sub_test.k")],
+            },
+        ]
+    );
+    let suites = discover_test_suites(&test_data_path("pkg/sub/sub_test.k")).unwrap();
+    assert_eq!(suites.len(), 1);
+    assert_eq!(suites[0].pkg_dir, test_data_path("pkg/sub"));
+    assert!(discover_test_suites(&test_data_path("not_found")).is_err());
+    // The test file name without a directory is in the current directory.
+    assert_eq!(parent_dir(Path::new("math_test.k")), Path::new("."));
+    assert_eq!(parent_dir(Path::new("pkg/math_test.k")), Path::new("pkg"));
+    // The errors walking the directory are returned.
+    assert!(crate::util::get_kcl_files(test_data_path("not_found"), true).is_err());
+}
+
+#[test]
+fn test_load_test_cases() {
+    let cases = load_test_cases(&test_data_path("pkg/math_test.k")).unwrap();
+    let names: Vec<&str> = cases.iter().map(|case| case.name.as_str()).collect();
+    // `inc` is not a test case.
+    assert_eq!(names, vec!["test_add", "test_add_zero"]);
+    assert_eq!(cases[1].line, 5);
+}
+
+#[test]
+fn test_run_tests() {
+    let mut finished = vec![];
+    let result = run_tests(&test_data_path("pkg"), &TestOptions::default(), |case| {
+        finished.push(case.case.name.clone())
+    })
+    .unwrap();
+    assert!(result.is_ok());
+    assert_eq!(finished, vec!["test_add", "test_add_zero", "test_name"]);
+
+    let result = run_tests(&test_data_path("failed"), &TestOptions::default(), |_| {}).unwrap();
+    assert!(!result.is_ok());
+    let cases = &result.suites[0].cases;
+    assert!(cases[0].error.is_none());
+    let error = cases[1].error.as_ref().unwrap();
+    assert_eq!(error.message, "bob is 10");
+    assert_eq!(error.file, test_data_path("failed/main_test.k"));
+    assert_eq!(error.line, 8);
+    assert!(cases[2].error.is_some());
+}
+
+#[test]
+fn test_run_tests_with_options() {
+    let opts = TestOptions {
+        run: Some("^test_add$".to_string()),
+        ..Default::default()
+    };
+    let result = run_tests(&test_data_path("pkg"), &opts, |_| {}).unwrap();
+    assert_eq!(result.suites.len(), 1);
+    assert_eq!(result.suites[0].cases.len(), 1);
+
+    let opts = TestOptions {
+        fail_fast: true,
+        ..Default::default()
+    };
+    let result = run_tests(&test_data_path("failed"), &opts, |_| {}).unwrap();
+    assert_eq!(result.suites[0].cases.len(), 2);
+    assert_eq!(result.suites[0].failures(), 1);
+
+    let opts = TestOptions {
+        run: Some("(".to_string()),
+        ..Default::default()
+    };
+    assert!(run_tests(&test_data_path("pkg"), &opts, |_| {}).is_err());
+}
+
+#[test]
+fn test_to_junit_xml() {
+    let case = |name: &str, error: Option<TestError>| TestCaseResult {
+        case: TestCase {
+            name: name.to_string(),
+            file: "main_test.k".to_string(),
+            line: 1,
+        },
+        duration: Duration::from_millis(10),
+        error,
+    };
+    let result = TestResult {
+        suites: vec![TestSuiteResult {
+            pkg_dir: "pkg".to_string(),
+            cases: vec![
+                case("test_ok", None),
+                case(
+                    "test_failed",
+                    Some(TestError {
+                        message: "a < b".to_string(),
+                        file: "main_test.k".to_string(),
+                        line: 2,
+                        column: 5,
+                    }),
+                ),
+            ],
+            duration: Duration::from_millis(20),
+        }],
+    };
+    assert_eq!(
+        result.to_junit_xml(),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="2" failures="1" time="0.020">
+  <testsuite name="pkg" tests="2" failures="1" time="0.020">
+    <testcase name="test_ok" classname="main_test.k" time="0.010"/>
+    <testcase name="test_failed" classname="main_test.k" time="0.010">
+      <failure message="a &lt; b">main_test.k:2:5: a &lt; b</failure>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+    );
+}
+
+#[test]
+fn test_diff_yaml() {
+    let expected = std::fs::read_to_string(test_data_path("snapshot/expected.yaml")).unwrap();
+    let actual = r#"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: nginx
+  labels:
+    app.kubernetes.io/name: web
+spec:
+  replicas: 4
+  containers:
+  - name: nginx
+    image: nginx:1.14.2
+  - name: sidecar
+    image: envoy
+  strategy: Recreate
+"#;
+    let diffs: Vec<String> = diff_yaml(&expected, actual)
+        .unwrap()
+        .iter()
+        .map(|diff| diff.to_string())
+        .collect();
+    assert_eq!(
+        diffs,
+        vec![
+            r#"~ metadata.labels["app.kubernetes.io/name"]: "nginx" -> "web""#,
+            "~ spec.replicas: 3 -> 4",
+            "- spec.paused: false",
+            r#"+ spec.containers[1]: {"name":"sidecar","image":"envoy"}"#,
+            r#"+ spec.strategy: "Recreate""#,
+        ]
+    );
+    // The key order and the formats are not compared.
+    assert!(diff_yaml("a: 1\nb: [1, 2]\n", "b:\n- 1\n- 2\na: 1\n")
+        .unwrap()
+        .is_empty());
+    let diffs = diff_yaml("a: 1\n---\nb: 2\n", "a: 2\n").unwrap();
+    assert_eq!(diffs[0].path, "documents[0].a");
+    assert_eq!(diffs[1].path, "documents[1]");
+    assert_eq!(diffs[1].kind, DiffKind::Removed);
+}
+
+#[test]
+fn test_check_snapshot() {
+    let expected = test_data_path("snapshot/expected.yaml");
+    let content = std::fs::read_to_string(&expected).unwrap();
+    assert_eq!(
+        check_snapshot(&expected, &content, false).unwrap(),
+        SnapshotResult::Matched
+    );
+    let actual = content.replace("replicas: 3", "replicas: 1");
+    match check_snapshot(&expected, &actual, false).unwrap() {
+        SnapshotResult::Mismatched(diffs) => {
+            assert_eq!(diffs.len(), 1);
+            assert_eq!(diffs[0].path, "spec.replicas");
+        }
+        result => panic!("unexpected snapshot result {:?}", result),
+    }
+    assert!(check_snapshot(&test_data_path("snapshot/not_found.yaml"), &actual, false).is_err());
+
+    let path = std::env::temp_dir().join(format!("kclvm_snapshot_{}.yaml", std::process::id()));
+    let path = path.to_str().unwrap();
+    assert_eq!(
+        check_snapshot(path, &content, true).unwrap(),
+        SnapshotResult::Updated
+    );
+    assert_eq!(
+        check_snapshot(path, &actual, true).unwrap(),
+        SnapshotResult::Updated
+    );
+    assert_eq!(std::fs::read_to_string(path).unwrap(), actual);
+    std::fs::remove_file(path).unwrap();
+}
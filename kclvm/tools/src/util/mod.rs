@@ -1,5 +1,11 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use kclvm_ast::ast;
 use kclvm_config::modfile::KCL_FILE_SUFFIX;
+use kclvm_error::Level;
+use kclvm_parser::{load_program, LoadProgramOptions};
+use kclvm_sema::resolver::resolve_program;
+use kclvm_sema::resolver::scope::ProgramScope;
+use kclvm_sema::ty::{SchemaType, TypeKind};
 use std::path::Path;
 use walkdir::WalkDir;
 
@@ -10,7 +16,8 @@ mod tests;
 /// Get kcl files from path.
 pub(crate) fn get_kcl_files<P: AsRef<Path>>(path: P, recursively: bool) -> Result<Vec<String>> {
     let mut files = vec![];
-    for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+    for entry in WalkDir::new(path) {
+        let entry = entry?;
         let path = entry.path();
         if path.is_file() {
             let file = path.to_str().unwrap();
@@ -21,3 +28,40 @@ pub(crate) fn get_kcl_files<P: AsRef<Path>>(path: P, recursively: bool) -> Resul
     }
     Ok(files)
 }
+
+/// Load and resolve the program from `files`, returns the first resolve error.
+pub(crate) fn load_resolved_program(
+    files: &[&str],
+    load_opts: Option<LoadProgramOptions>,
+) -> Result<(ast::Program, ProgramScope)> {
+    let mut program = load_program(files, load_opts).map_err(|err| anyhow!(err))?;
+    let scope = resolve_program(&mut program);
+    if let Some(diag) = scope
+        .diagnostics
+        .iter()
+        .find(|diag| diag.level == Level::Error)
+    {
+        let msg = diag
+            .messages
+            .first()
+            .map(|msg| format!("{}:{}: {}", msg.pos.filename, msg.pos.line, msg.message))
+            .unwrap_or_default();
+        return Err(anyhow!("failed to resolve the program: {}", msg));
+    }
+    Ok((program, scope))
+}
+
+/// Get the schema type named `name` in the package `pkgpath`.
+pub(crate) fn lookup_schema_ty(
+    scope: &ProgramScope,
+    pkgpath: &str,
+    name: &str,
+) -> Option<SchemaType> {
+    let pkg_scope = scope.scope_map.get(pkgpath)?;
+    let obj = pkg_scope.borrow().elems.get(name)?.clone();
+    let obj = obj.borrow();
+    match &obj.ty.kind {
+        TypeKind::Schema(schema_ty) => Some(schema_ty.clone()),
+        _ => None,
+    }
+}
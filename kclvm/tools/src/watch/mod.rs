@@ -0,0 +1,207 @@
+//! [kclvm_tools::watch] module watches the source files of a KCL program and
+//! notifies the caller after they are changed, it is used by `kclvm_cli run --watch`
+//! and `kclvm_cli lint --watch`.
+//!
+//! The watched paths are discovered by the program loader: the main files, the
+//! `kcl.mod` file under the program root and the directories (or the package
+//! archives) of all the transitively imported packages. The paths are polled
+//! and a change is reported after no more changes happen in the debounce time,
+//! so saving several files at once triggers only one re-execution.
+use anyhow::{anyhow, Result};
+use kclvm_config::modfile::{KCL_FILE_SUFFIX, KCL_MOD_FILE};
+use kclvm_parser::{load_program, LoadProgramOptions};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+#[cfg(test)]
+mod tests;
+
+/// The default poll interval of the watched paths.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// The default debounce time of the changes.
+const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    pub poll_interval: Duration,
+    pub debounce: Duration,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            debounce: DEFAULT_DEBOUNCE,
+        }
+    }
+}
+
+/// Returns the paths to watch of the program loaded from `files`, sorted and
+/// deduplicated. A directory path means all the KCL files in it are watched.
+pub fn watch_paths(files: &[&str], opts: Option<LoadProgramOptions>) -> Result<Vec<PathBuf>> {
+    let program = load_program(files, opts).map_err(|err| anyhow!(err))?;
+    let mut paths: Vec<PathBuf> = files.iter().map(|file| abs_path(file)).collect();
+    if !program.root.is_empty() {
+        paths.push(abs_path(&program.root).join(KCL_MOD_FILE));
+    }
+    for modules in program.pkgs.values() {
+        for module in modules {
+            if let Some(path) = package_path(&abs_path(&module.filename)) {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Returns the directory of the package file on disk. The files loaded from a
+/// package archive do not exist on disk, and the archive file is returned.
+fn package_path(filename: &Path) -> Option<PathBuf> {
+    let dir = filename.parent()?;
+    if dir.is_dir() {
+        return Some(dir.to_path_buf());
+    }
+    dir.ancestors()
+        .find(|path| path.is_file())
+        .map(|path| path.to_path_buf())
+}
+
+fn abs_path(path: &str) -> PathBuf {
+    let path = Path::new(path);
+    match path.canonicalize() {
+        Ok(path) => path,
+        Err(_) if path.is_absolute() => path.to_path_buf(),
+        Err(_) => std::env::current_dir().unwrap_or_default().join(path),
+    }
+}
+
+/// The modification times of the watched files, a missing file has no time.
+type Snapshot = BTreeMap<PathBuf, Option<SystemTime>>;
+
+/// Watcher polls the modification times of the watched paths.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kclvm_tools::watch::{watch_paths, Watcher, WatchOptions};
+///
+/// let mut watcher = Watcher::new(WatchOptions::default());
+/// loop {
+///     watcher.set_paths(watch_paths(&["main.k"], None).unwrap());
+///     // Run the program here.
+///     for path in watcher.wait() {
+///         println!("{} changed", path.display());
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Watcher {
+    opts: WatchOptions,
+    paths: Vec<PathBuf>,
+    snapshot: Snapshot,
+}
+
+impl Watcher {
+    pub fn new(opts: WatchOptions) -> Self {
+        Self {
+            opts,
+            paths: vec![],
+            snapshot: Snapshot::default(),
+        }
+    }
+
+    /// Sets the watched paths and records their current states, the changes
+    /// are detected against the states from now on.
+    pub fn set_paths(&mut self, paths: Vec<PathBuf>) {
+        self.snapshot = snapshot(&paths);
+        self.paths = paths;
+    }
+
+    /// Returns the watched paths.
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Returns the files changed, added or removed since the last poll without
+    /// blocking.
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let current = snapshot(&self.paths);
+        let mut changed: Vec<PathBuf> = current
+            .iter()
+            .filter(|(path, time)| self.snapshot.get(*path) != Some(time))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.extend(
+            self.snapshot
+                .keys()
+                .filter(|path| !current.contains_key(*path))
+                .cloned(),
+        );
+        changed.sort();
+        changed.dedup();
+        self.snapshot = current;
+        changed
+    }
+
+    /// Blocks until some files are changed and no more changes happen in the
+    /// debounce time, returns all the files changed.
+    pub fn wait(&mut self) -> Vec<PathBuf> {
+        let mut changed = vec![];
+        loop {
+            std::thread::sleep(self.opts.poll_interval);
+            changed.extend(self.poll());
+            if !changed.is_empty() {
+                break;
+            }
+        }
+        loop {
+            std::thread::sleep(self.opts.debounce);
+            let more = self.poll();
+            if more.is_empty() {
+                break;
+            }
+            changed.extend(more);
+        }
+        changed.sort();
+        changed.dedup();
+        changed
+    }
+}
+
+/// Records the modification times of the paths, the KCL files and the `kcl.mod`
+/// file in a watched directory are recorded.
+fn snapshot(paths: &[PathBuf]) -> Snapshot {
+    let mut snapshot = Snapshot::default();
+    for path in paths {
+        if path.is_dir() {
+            if let Ok(entries) = std::fs::read_dir(path) {
+                for entry in entries.flatten() {
+                    let file = entry.path();
+                    if is_watched_file(&file) {
+                        snapshot.insert(file.clone(), modified(&file));
+                    }
+                }
+            }
+        } else {
+            snapshot.insert(path.clone(), modified(path));
+        }
+    }
+    snapshot
+}
+
+fn is_watched_file(path: &Path) -> bool {
+    path.is_file()
+        && match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => name.ends_with(KCL_FILE_SUFFIX) || name == KCL_MOD_FILE,
+            None => false,
+        }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
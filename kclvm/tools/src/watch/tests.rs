@@ -0,0 +1,37 @@
+use super::{watch_paths, WatchOptions, Watcher};
+use std::path::Path;
+
+#[test]
+fn test_watch_paths() {
+    let root = Path::new("./src/watch/test_data").canonicalize().unwrap();
+    let paths = watch_paths(&["./src/watch/test_data/main.k"], None).unwrap();
+    assert_eq!(
+        paths,
+        vec![
+            root.clone(),
+            root.join("kcl.mod"),
+            root.join("main.k"),
+            root.join("pkg"),
+        ]
+    );
+}
+
+#[test]
+fn test_watcher_poll() {
+    let dir = std::env::temp_dir().join(format!("kclvm_watch_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.k"), "a = 1").unwrap();
+    std::fs::write(dir.join("a.yaml"), "a: 1").unwrap();
+    let mut watcher = Watcher::new(WatchOptions::default());
+    watcher.set_paths(vec![dir.clone()]);
+    assert!(watcher.poll().is_empty());
+    // The files other than the KCL files are not watched.
+    std::fs::write(dir.join("b.yaml"), "b: 1").unwrap();
+    assert!(watcher.poll().is_empty());
+    std::fs::write(dir.join("b.k"), "b = 1").unwrap();
+    assert_eq!(watcher.poll(), vec![dir.join("b.k")]);
+    std::fs::remove_file(dir.join("a.k")).unwrap();
+    assert_eq!(watcher.poll(), vec![dir.join("a.k")]);
+    assert!(watcher.poll().is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
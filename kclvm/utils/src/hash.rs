@@ -0,0 +1,41 @@
+// Copyright 2021 The KCL Authors. All rights reserved.
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+
+use kclvm_version as version;
+
+/// ContentHasher computes the content address of a cache entry from all the
+/// inputs which affect the cached data, e.g. the package AST, the hashes of
+/// the imported packages and the compiler options.
+pub struct ContentHasher {
+    sha256: Sha256,
+}
+
+impl ContentHasher {
+    pub fn new() -> Self {
+        let mut sha256 = Sha256::new();
+        // Different compilers never share the cache entries.
+        sha256.input_str(version::VERSION);
+        sha256.input_str(version::CHECK_SUM);
+        Self { sha256 }
+    }
+
+    /// Feed the string into the hasher. Each input is prefixed by its length,
+    /// so the inputs `["ab", "c"]` and `["a", "bc"]` have different hashes.
+    pub fn input_str(&mut self, input: &str) -> &mut Self {
+        self.sha256.input(&(input.len() as u64).to_le_bytes());
+        self.sha256.input_str(input);
+        self
+    }
+
+    /// Returns the hex string content address.
+    pub fn result(&mut self) -> String {
+        self.sha256.result_str()
+    }
+}
+
+impl Default for ContentHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,6 @@
+// Copyright 2021 The KCL Authors. All rights reserved.
+
+pub mod hash;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,15 @@
+use crate::hash::ContentHasher;
+
+#[test]
+fn test_content_hasher() {
+    let key = ContentHasher::new().input_str("ab").input_str("c").result();
+    assert_eq!(key.len(), 64);
+    assert_eq!(
+        key,
+        ContentHasher::new().input_str("ab").input_str("c").result()
+    );
+    assert_ne!(
+        key,
+        ContentHasher::new().input_str("a").input_str("bc").result()
+    );
+}